@@ -0,0 +1,418 @@
+//! Lint baselines for incremental adoption.
+//!
+//! A baseline is a JSON file listing accepted issues by fingerprint. Lint
+//! runs with `--baseline` hide issues found in it; `--baseline-update` merges
+//! the current issues into the file: existing entries are kept, new issues
+//! are appended, and entries whose issue no longer occurs are pruned. Runs
+//! limited to some files (`--files-from`) or rules (`--rule`) only prune
+//! entries of those files and rules.
+//! `--diff-baseline` hides baselined issues too and also lists the entries
+//! that no longer occur, for reviewing what a change introduced or fixed.
+
+use crate::lint::summarize;
+use crate::models::{Issue, LintResult, Summary};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// Default baseline file name inside the cache directory.
+pub const DEFAULT_FILE: &str = "baseline.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+/// Baseline file contents.
+pub struct Baseline {
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default)]
+    pub issues: Vec<BaselineEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+/// One accepted issue. Only `fingerprint` is used for matching; the other
+/// fields keep the file reviewable.
+pub struct BaselineEntry {
+    pub fingerprint: String,
+    pub file: String,
+    pub rule: String,
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Outcome counts of a baseline update.
+pub struct UpdateStats {
+    pub added: usize,
+    pub kept: usize,
+    pub pruned: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+/// The part of the repository a lint run covered. Baseline entries outside
+/// it are kept as they are, since their issues were not looked for.
+pub struct Scope {
+    /// Repo-relative files that were linted; `None` covers every file
+    pub files: Option<HashSet<String>>,
+    /// Index or sync rule ids that ran; empty covers every rule
+    pub rules: Vec<String>,
+}
+
+impl Scope {
+    /// Scope of a run over `files` (relative to `repo_root` or absolute;
+    /// `None` for all) and `rules` (empty for all).
+    pub fn new(repo_root: &Path, files: Option<&[String]>, rules: &[String]) -> Self {
+        let files = files.map(|fs| {
+            fs.iter()
+                .map(|f| {
+                    crate::explain::relative_to(repo_root, Path::new(f))
+                        .to_string_lossy()
+                        .replace('\\', "/")
+                })
+                .collect()
+        });
+        Scope {
+            files,
+            rules: rules.to_vec(),
+        }
+    }
+
+    /// Whether the run looked for the issue of `entry`.
+    pub fn covers(&self, entry: &BaselineEntry) -> bool {
+        let rule = entry.rule.strip_prefix("sync:").unwrap_or(&entry.rule);
+        self.files
+            .as_ref()
+            .is_none_or(|files| files.contains(&entry.file))
+            && (self.rules.is_empty() || self.rules.iter().any(|r| r == rule))
+    }
+}
+
+/// Stable fingerprint of an issue: repo-relative file, rule, path, message.
+pub fn fingerprint(repo_root: &Path, issue: &Issue) -> String {
+    let key = format!(
+        "{}\n{}\n{}\n{}",
+        normalize_file(repo_root, &issue.file),
+        issue.rule,
+        issue.path,
+        issue.message
+    );
+    format!("{:016x}", crate::utils::stable_hash(key.as_bytes()))
+}
+
+fn normalize_file(repo_root: &Path, file: &str) -> String {
+    let p = Path::new(file);
+    // Issue files are cwd-relative or absolute; store them repo-relative
+    let abs = if p.is_absolute() {
+        p.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .map(|d| d.join(p))
+            .unwrap_or_else(|_| p.to_path_buf())
+    };
+    let root = repo_root
+        .canonicalize()
+        .unwrap_or_else(|_| repo_root.to_path_buf());
+    let abs = abs.canonicalize().unwrap_or(abs);
+    abs.strip_prefix(&root)
+        .map(|r| r.to_path_buf())
+        .unwrap_or_else(|_| p.to_path_buf())
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Load a baseline; a missing file yields an empty baseline.
+pub fn load(path: &Path) -> Result<Baseline, String> {
+    if !path.exists() {
+        return Ok(Baseline::default());
+    }
+    let s = fs::read_to_string(path).map_err(|e| {
+        format!(
+            "Failed to read baseline '{}': {}",
+            path.to_string_lossy(),
+            e
+        )
+    })?;
+    serde_json::from_str(&s)
+        .map_err(|e| format!("Invalid baseline '{}': {}", path.to_string_lossy(), e))
+}
+
+/// Write a baseline as pretty JSON, creating parent directories.
+pub fn save(path: &Path, baseline: &Baseline) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create baseline dir: {}", e))?;
+    }
+    let s = serde_json::to_string_pretty(baseline)
+        .map_err(|e| format!("Failed to serialize baseline: {}", e))?;
+    fs::write(path, s + "\n").map_err(|e| {
+        format!(
+            "Failed to write baseline '{}': {}",
+            path.to_string_lossy(),
+            e
+        )
+    })
+}
+
+/// Merge current `issues` into `existing`: keep entries that still occur (in
+/// their original order), append new ones, and prune resolved ones. Entries
+/// outside `scope` are kept untouched.
+pub fn update(
+    existing: &Baseline,
+    repo_root: &Path,
+    issues: &[Issue],
+    scope: &Scope,
+) -> (Baseline, UpdateStats) {
+    let mut current: Vec<BaselineEntry> = Vec::new();
+    let mut seen = HashSet::new();
+    for is in issues {
+        let fp = fingerprint(repo_root, is);
+        if seen.insert(fp.clone()) {
+            current.push(BaselineEntry {
+                fingerprint: fp,
+                file: normalize_file(repo_root, &is.file),
+                rule: is.rule.clone(),
+                path: is.path.clone(),
+                message: is.message.clone(),
+            });
+        }
+    }
+    let mut stats = UpdateStats::default();
+    let mut kept_fps = HashSet::new();
+    let mut out: Vec<BaselineEntry> = Vec::new();
+    for e in &existing.issues {
+        let current = seen.contains(&e.fingerprint) || !scope.covers(e);
+        if current && kept_fps.insert(e.fingerprint.clone()) {
+            out.push(e.clone());
+            stats.kept += 1;
+        } else {
+            stats.pruned += 1;
+        }
+    }
+    for e in current {
+        if !kept_fps.contains(&e.fingerprint) {
+            out.push(e);
+            stats.added += 1;
+        }
+    }
+    (
+        Baseline {
+            version: 1,
+            issues: out,
+        },
+        stats,
+    )
+}
+
+/// Drop issues present in `baseline` and recompute the summary (keeping any
+/// `--fix` results).
+pub fn filter(result: LintResult, repo_root: &Path, baseline: &Baseline) -> LintResult {
+    let known: HashSet<&str> = baseline
+        .issues
+        .iter()
+        .map(|e| e.fingerprint.as_str())
+        .collect();
+    let files = result.summary.files;
+    let issues: Vec<Issue> = result
+        .issues
+        .into_iter()
+        .filter(|is| !known.contains(fingerprint(repo_root, is).as_str()))
+        .collect();
+    let summary = Summary {
+        fixed: result.summary.fixed,
+        ..summarize(&issues, files)
+    };
+    LintResult {
+        issues,
+        summary,
+        fixed: result.fixed,
+        resolved: Vec::new(),
+    }
+}
+
+/// `filter`, also moving baseline entries within `scope` that no current
+/// issue matches into `LintResult::resolved`.
+pub fn diff(
+    result: LintResult,
+    repo_root: &Path,
+    baseline: &Baseline,
+    scope: &Scope,
+) -> LintResult {
+    let current: HashSet<String> = result
+        .issues
+        .iter()
+        .map(|is| fingerprint(repo_root, is))
+        .collect();
+    let resolved = baseline
+        .issues
+        .iter()
+        .filter(|e| scope.covers(e) && !current.contains(&e.fingerprint))
+        .cloned()
+        .collect();
+    LintResult {
+        resolved,
+        ..filter(result, repo_root, baseline)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(file: &str, path: &str) -> Issue {
+        Issue {
+            file: file.into(),
+            rule: "r".into(),
+            severity: "error".into(),
+            path: path.into(),
+            message: "m".into(),
+            line: None,
+            col: None,
+        }
+    }
+
+    #[test]
+    fn test_update_adds_new_keeps_existing_and_prunes_resolved() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let a = issue(&root.join("a.json").to_string_lossy(), "$.x");
+        let b = issue(&root.join("b.json").to_string_lossy(), "$.y");
+        let c = issue(&root.join("c.json").to_string_lossy(), "$.z");
+
+        let (first, stats) = update(&Baseline::default(), root, &[a, b], &Scope::default());
+        assert_eq!(
+            stats,
+            UpdateStats {
+                added: 2,
+                kept: 0,
+                pruned: 0
+            }
+        );
+        assert_eq!(first.issues[0].file, "a.json");
+        let fp_a = first.issues[0].fingerprint.clone();
+        let fp_b = first.issues[1].fingerprint.clone();
+
+        // `b` was fixed, `c` is new
+        let a = issue(&root.join("a.json").to_string_lossy(), "$.x");
+        let path = root.join(".rigra").join(DEFAULT_FILE);
+        save(&path, &first).unwrap();
+        let loaded = load(&path).unwrap();
+        let (second, stats) = update(&loaded, root, &[c, a], &Scope::default());
+        assert_eq!(
+            stats,
+            UpdateStats {
+                added: 1,
+                kept: 1,
+                pruned: 1
+            }
+        );
+        let fps: Vec<&str> = second
+            .issues
+            .iter()
+            .map(|e| e.fingerprint.as_str())
+            .collect();
+        assert_eq!(fps[0], fp_a);
+        assert!(!fps.contains(&fp_b.as_str()));
+        assert_eq!(second.issues[1].file, "c.json");
+    }
+
+    #[test]
+    fn test_filter_hides_baselined_issues_and_recounts() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let known = issue("a.json", "$.x");
+        let (bl, _) = update(
+            &Baseline::default(),
+            root,
+            &[issue("a.json", "$.x")],
+            &Scope::default(),
+        );
+        let result = LintResult {
+            summary: summarize(&[], 2),
+            issues: vec![known, issue("a.json", "$.new")],
+            fixed: Vec::new(),
+            resolved: Vec::new(),
+        };
+        let res = filter(result, root, &bl);
+        assert_eq!(res.issues.len(), 1);
+        assert_eq!(res.issues[0].path, "$.new");
+        assert_eq!(res.summary.errors, 1);
+        assert_eq!(res.summary.files, 2);
+    }
+
+    #[test]
+    fn test_diff_reports_new_and_resolved_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let (bl, _) = update(
+            &Baseline::default(),
+            root,
+            &[issue("a.json", "$.x"), issue("a.json", "$.gone")],
+            &Scope::default(),
+        );
+        let result = LintResult {
+            summary: summarize(&[], 1),
+            issues: vec![issue("a.json", "$.x"), issue("a.json", "$.new")],
+            fixed: Vec::new(),
+            resolved: Vec::new(),
+        };
+        let res = diff(result, root, &bl, &Scope::default());
+        let paths: Vec<_> = res.issues.iter().map(|i| i.path.as_str()).collect();
+        assert_eq!(paths, vec!["$.new"]);
+        assert_eq!(res.summary.errors, 1);
+        assert_eq!(res.resolved.len(), 1);
+        assert_eq!(res.resolved[0].path, "$.gone");
+    }
+
+    #[test]
+    fn test_update_keeps_entries_outside_scope() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let a = || issue(&root.join("a.json").to_string_lossy(), "$.x");
+        let b = issue(&root.join("b.json").to_string_lossy(), "$.y");
+        let (bl, _) = update(&Baseline::default(), root, &[a(), b], &Scope::default());
+        // Only a.json was linted: b.json's entry was not looked for
+        let scope = Scope::new(root, Some(&["./a.json".to_string()]), &[]);
+        let (next, stats) = update(&bl, root, &[], &scope);
+        assert_eq!(
+            stats,
+            UpdateStats {
+                added: 0,
+                kept: 1,
+                pruned: 1
+            }
+        );
+        assert_eq!(next.issues[0].file, "b.json");
+    }
+
+    #[test]
+    fn test_rule_scope_limits_pruning_and_resolved() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let mut other = issue("a.json", "$.y");
+        other.rule = "other".into();
+        let mut drift = issue(".editorconfig", "$");
+        drift.rule = "sync:editorconfig".into();
+        let (bl, _) = update(
+            &Baseline::default(),
+            root,
+            &[issue("a.json", "$.x"), other, drift],
+            &Scope::default(),
+        );
+        // Only rule `r` ran and its issue is gone
+        let scope = Scope::new(root, None, &["r".to_string()]);
+        let (next, stats) = update(&bl, root, &[], &scope);
+        assert_eq!((stats.kept, stats.pruned), (2, 1));
+        assert!(next.issues.iter().all(|e| e.rule != "r"));
+        let result = LintResult {
+            summary: summarize(&[], 1),
+            issues: Vec::new(),
+            fixed: Vec::new(),
+            resolved: Vec::new(),
+        };
+        let res = diff(result, root, &bl, &scope);
+        assert_eq!(res.resolved.len(), 1);
+        assert_eq!(res.resolved[0].rule, "r");
+        // Sync drift entries belong to their sync rule id
+        let scope = Scope::new(root, None, &["editorconfig".to_string()]);
+        let (_, stats) = update(&bl, root, &[], &scope);
+        assert_eq!((stats.kept, stats.pruned), (2, 1));
+    }
+}
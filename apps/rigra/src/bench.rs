@@ -0,0 +1,153 @@
+//! Repeatable timing of lint and format over the current repo (`rigra bench`).
+//!
+//! Both passes run read-only: format previews without writing, and lint only
+//! reports sync status. Cache/state under the cache dir is left untouched.
+
+use crate::config::Effective;
+use crate::{format, lint};
+use std::time::{Duration, Instant};
+
+/// Wall-clock samples of one pass over `iterations` runs.
+#[derive(Debug)]
+pub struct BenchStats {
+    pub name: &'static str,
+    /// Files processed per run
+    pub files: usize,
+    /// One sample per run, sorted ascending
+    pub samples: Vec<Duration>,
+}
+
+impl BenchStats {
+    pub fn min(&self) -> Duration {
+        self.samples.first().copied().unwrap_or_default()
+    }
+
+    pub fn max(&self) -> Duration {
+        self.samples.last().copied().unwrap_or_default()
+    }
+
+    /// Middle sample; the mean of the two middle ones for even counts.
+    pub fn median(&self) -> Duration {
+        let n = self.samples.len();
+        match n {
+            0 => Duration::ZERO,
+            _ if n % 2 == 1 => self.samples[n / 2],
+            _ => (self.samples[n / 2 - 1] + self.samples[n / 2]) / 2,
+        }
+    }
+
+    /// Files per second at the median run time.
+    pub fn throughput(&self) -> f64 {
+        let secs = self.median().as_secs_f64();
+        if secs > 0.0 {
+            self.files as f64 / secs
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Run lint and then format (no writes) `iterations` times each.
+pub fn run_bench(eff: &Effective, iterations: usize) -> Vec<BenchStats> {
+    let root = eff.repo_root.to_string_lossy().to_string();
+    let lint = sample("lint", iterations, || {
+        let (res, _) = lint::run_lint(
+            &root,
+            &eff.index,
+            Some(&eff.cache_dir),
+            &eff.scope,
+            &eff.pattern_overrides,
+            eff.max_file_bytes,
+            eff.stream_arrays,
+            None,
+            &[],
+        );
+        res.summary.files
+    });
+    let format = sample("format", iterations, || {
+        let (results, _) = format::run_format(
+            &root,
+            &eff.index,
+            Some(&eff.cache_dir),
+            false,
+            false,
+            eff.strict_linebreak,
+            eff.lb_between_groups,
+            &eff.lb_before_fields,
+            &eff.lb_in_fields,
+            &eff.pattern_overrides,
+            eff.max_file_bytes,
+            false,
+            false,
+            None,
+            &[],
+        );
+        results.len()
+    });
+    vec![lint, format]
+}
+
+fn sample(name: &'static str, iterations: usize, mut run: impl FnMut() -> usize) -> BenchStats {
+    let mut files = 0;
+    let mut samples: Vec<Duration> = (0..iterations.max(1))
+        .map(|_| {
+            let started = Instant::now();
+            files = run();
+            started.elapsed()
+        })
+        .collect();
+    samples.sort();
+    BenchStats {
+        name,
+        files,
+        samples,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_bench_runs_once_without_writing() {
+        let td = tempfile::tempdir().unwrap();
+        let root = td.path();
+        fs::create_dir_all(root.join("conv")).unwrap();
+        fs::write(
+            root.join("conv/index.toml"),
+            "[[rules]]\nid = \"pkg\"\npatterns = [\"package.json\"]\npolicy = \"policy.toml\"\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("conv/policy.toml"),
+            "[order]\ntop = [[\"name\"], [\"version\"]]\n",
+        )
+        .unwrap();
+        let pkg = "{\"version\": \"1.0.0\", \"name\": \"x\"}";
+        fs::write(root.join("package.json"), pkg).unwrap();
+        // Pins repo-root detection to the temp dir
+        fs::write(root.join("rigra.toml"), "").unwrap();
+        let eff = crate::config::resolve_effective(
+            root.to_str(),
+            Some("conv/index.toml"),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+
+        // Zero iterations still runs each pass once
+        let stats = run_bench(&eff, 0);
+        assert_eq!(stats.len(), 2);
+        for s in &stats {
+            assert_eq!(s.samples.len(), 1, "{}", s.name);
+            assert_eq!(s.files, 1, "{}", s.name);
+            assert!(s.throughput() > 0.0, "{}", s.name);
+            assert!(s.min() <= s.median() && s.median() <= s.max());
+        }
+        assert_eq!(fs::read_to_string(root.join("package.json")).unwrap(), pkg);
+    }
+}
@@ -15,6 +15,46 @@ use clap::{Parser, Subcommand};
 pub struct Cli {
     #[command(subcommand)]
     pub cmd: Commands,
+    /// Disable all ignore mechanisms (e.g. [sync].ignore) for this run
+    #[arg(
+        long,
+        global = true,
+        action = clap::ArgAction::SetTrue,
+        help = "Disable all ignore mechanisms for this run"
+    )]
+    pub no_ignore: bool,
+    /// Relocate all cache/state directories
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        help = "Directory for rigra's cache and state (default: .rigra, or [cache].dir)"
+    )]
+    pub cache_dir: Option<String>,
+    /// Print per-rule timing for lint/format to stderr
+    #[arg(
+        long,
+        global = true,
+        action = clap::ArgAction::SetTrue,
+        help = "Report wall-clock time per rule (lint/format) on stderr"
+    )]
+    pub stats: bool,
+    /// Explain non-zero exits on stderr
+    #[arg(
+        long,
+        global = true,
+        action = clap::ArgAction::SetTrue,
+        help = "Print the reason and count on stderr when exiting non-zero"
+    )]
+    pub explain_exit: bool,
+    /// Disable writes for any command
+    #[arg(
+        long,
+        global = true,
+        action = clap::ArgAction::SetTrue,
+        help = "Never write files (format and merge preview, sync plans, lint --fix only reports; restore, conv install/prune and --fix-suggest FILE are refused)"
+    )]
+    pub dry_run: bool,
 }
 
 #[derive(Subcommand)]
@@ -30,65 +70,315 @@ pub enum Commands {
     #[command(
         about = "Run lint checks",
         long_about = "Validate files matched by index rules using TOML policies. Severity levels contribute to CI exits.",
-        after_help = "Examples:\n  rigra lint --index conv/index.toml\n  rigra lint --index conv/index.toml --output json"
+        after_help = "Examples:\n  rigra lint --index conv/index.toml\n  rigra lint --index conv/index.toml --output json\n  rigra lint --index conv/index.toml --fix\n  git diff --name-only main | rigra lint --index conv/index.toml --files-from -\n  rigra lint --index conv/index.toml --rule pkg --output json"
     )]
     Lint {
-        #[arg(long, help = "Repository root (default: current dir)")]
+        #[arg(
+            long,
+            help = "Repository root (default: $RIGRA_REPO_ROOT, else current dir)"
+        )]
         repo_root: Option<String>,
         #[arg(long, help = "Scope token for sync-related lint (e.g. repo, lib)")]
         scope: Option<String>,
-        #[arg(long, help = "Output mode: human|json (default: human)")]
+        #[arg(
+            long,
+            help = "Output mode: human|json|json-compact|tap|sarif|github (default: human)"
+        )]
         output: Option<String>,
         #[arg(long, help = "Path to index.toml (required)")]
         index: Option<String>,
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Hide issues recorded in this baseline file (default with --baseline-update: baseline.json in the cache dir)"
+        )]
+        baseline: Option<String>,
+        #[arg(long, action = clap::ArgAction::SetTrue, help = "Merge current issues into the baseline: add new, keep existing, prune resolved")]
+        baseline_update: bool,
+        #[arg(
+            long,
+            value_name = "PATH",
+            conflicts_with_all = ["baseline", "baseline_update"],
+            help = "Report only issues not in this baseline file, plus baselined issues that no longer occur"
+        )]
+        diff_baseline: Option<String>,
+        #[arg(long, action = clap::ArgAction::SetTrue, help = "Rewrite JSON files to resolve fixable issues (key order, sortedArray), then report what remains")]
+        fix: bool,
+        #[arg(long, action = clap::ArgAction::SetTrue, help = "Print each issue with its full relative path instead of grouping under directory headers (human output)")]
+        flat: bool,
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Only lint the newline-separated paths listed in FILE ('-' reads stdin), each with the policy of the rule matching it"
+        )]
+        files_from: Option<String>,
+        #[arg(long, action = clap::ArgAction::SetTrue, help = "Report every sync drift (sync:<id>) as an error, regardless of its configured level")]
+        fail_on_sync_drift: bool,
+        #[arg(
+            long,
+            value_name = "ID",
+            help = "Only lint this index or sync rule id (repeatable)"
+        )]
+        rule: Vec<String>,
+        #[arg(
+            long,
+            value_name = "N",
+            help = "Exit 3 when there are more than N warnings (errors still exit 1)"
+        )]
+        max_warnings: Option<usize>,
     },
     /// Format files deterministically
     #[command(
         about = "Apply deterministic formatting",
         long_about = "Reorder keys and adjust line breaks per policy. When --diff or --check is set, write is disabled.",
-        after_help = "Examples:\n  rigra format --index conv/index.toml --diff\n  rigra format --index conv/index.toml --write"
+        after_help = "Examples:\n  rigra format --index conv/index.toml --diff\n  rigra format --index conv/index.toml --write\n  rigra format --index conv/index.toml --emit-order\n  rigra format --index conv/index.toml --stdin --stdin-filename package.json < package.json"
     )]
     Format {
-        #[arg(long, help = "Repository root (default: current dir)")]
+        #[arg(
+            long,
+            help = "Repository root (default: $RIGRA_REPO_ROOT, else current dir)"
+        )]
         repo_root: Option<String>,
         #[arg(long, action = clap::ArgAction::SetTrue, help = "Write changes to files")]
         write: bool,
         #[arg(long, action = clap::ArgAction::SetTrue, help = "Show diffs for changed files (implies write=false)")]
         diff: bool,
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = 3,
+            help = "Unchanged lines shown around each diff hunk"
+        )]
+        diff_context: usize,
+        #[arg(
+            long,
+            action = clap::ArgAction::SetTrue,
+            help = "List only changed files in human output (already the default: unchanged files are never listed; JSON is unaffected)"
+        )]
+        changed_only: bool,
         #[arg(long, action = clap::ArgAction::SetTrue, help = "Exit non-zero if changes would occur (implies write=false)")]
         check: bool,
-        #[arg(long, help = "Output mode: human|json (default: human)")]
+        #[arg(long, help = "Output mode: human|json|json-compact (default: human)")]
         output: Option<String>,
         #[arg(long, help = "Path to index.toml (required)")]
         index: Option<String>,
+        #[arg(long, action = clap::ArgAction::SetTrue, help = "Skip reserializing files whose keys are already in policy order")]
+        assume_sorted: bool,
+        #[arg(long, action = clap::ArgAction::SetTrue, help = "Process files serially instead of in parallel (deterministic debugging)")]
+        serial: bool,
+        #[arg(
+            long,
+            value_name = "FILE",
+            num_args = 0..=1,
+            default_missing_value = "-",
+            help = "Write a git-applyable patch of all formatting changes to FILE (stdout when omitted; implies write=false)"
+        )]
+        fix_suggest: Option<String>,
+        #[arg(long, action = clap::ArgAction::SetTrue, help = "Print each file's resulting top-level key order instead of its content (implies write=false)")]
+        emit_order: bool,
+        #[arg(
+            long,
+            action = clap::ArgAction::SetTrue,
+            conflicts_with_all = ["write", "diff", "fix_suggest", "emit_order"],
+            help = "Format text read from stdin and print it to stdout instead of walking the index globs"
+        )]
+        stdin: bool,
+        #[arg(
+            long,
+            value_name = "PATH",
+            requires = "stdin",
+            help = "Path whose matching rule and policy apply to --stdin input (required when the index has several rules)"
+        )]
+        stdin_filename: Option<String>,
+        #[arg(
+            long,
+            value_name = "ID",
+            conflicts_with = "stdin",
+            help = "Only format files of this rule id (repeatable)"
+        )]
+        rule: Vec<String>,
     },
     /// Sync templates/configs
     #[command(
         about = "Sync templates/configs",
         long_about = "Copy files or perform smart JSON merges according to sync policy. Honors scope filters.",
-        after_help = "Examples:\n  rigra sync --index conv/index.toml --scope repo --dry-run\n  rigra sync --index conv/index.toml --scope lib --write"
+        after_help = "Examples:\n  rigra sync --index conv/index.toml --scope repo --dry-run\n  rigra sync --index conv/index.toml --scope lib --write\n  rigra sync --index conv/index.toml --interactive\n  rigra sync --index conv/index.toml --plan-only --output json"
     )]
     Sync {
-        #[arg(long, help = "Repository root (default: current dir)")]
+        #[arg(
+            long,
+            help = "Repository root (default: $RIGRA_REPO_ROOT, else current dir)"
+        )]
         repo_root: Option<String>,
         #[arg(long, help = "Scope token to select rules (e.g. repo, lib)")]
         scope: Option<String>,
-        #[arg(long, help = "Output mode: human|json (default: human)")]
+        #[arg(long, help = "Output mode: human|json|json-compact (default: human)")]
         output: Option<String>,
         #[arg(long, help = "Path to index.toml (required)")]
         index: Option<String>,
         #[arg(long, action = clap::ArgAction::SetTrue, help = "Apply changes to disk (disabled if --diff/--check)")]
         write: bool,
-        #[arg(long, action = clap::ArgAction::SetTrue, help = "Preview planned writes without changing files")]
-        dry_run: bool,
         #[arg(long, action = clap::ArgAction::SetTrue, help = "Exit non-zero if changes would occur")]
         check: bool,
+        #[arg(long, action = clap::ArgAction::SetTrue, help = "Compute the plan without writing and report a stable planHash")]
+        plan_only: bool,
+        #[arg(long, action = clap::ArgAction::SetTrue, help = "Confirm each write with a y/N prompt (shows a short diff)")]
+        interactive: bool,
+        #[arg(long, action = clap::ArgAction::SetTrue, help = "Answer yes to all prompts (required for --interactive without a TTY)")]
+        yes: bool,
+        #[arg(long, action = clap::ArgAction::SetTrue, help = "Re-sync rules even if their sources are unchanged since the last sync")]
+        force: bool,
+        #[arg(long, action = clap::ArgAction::SetTrue, help = "Compare content and list only targets that would change (ignores mtime state)")]
+        only_changed: bool,
+        #[arg(long, action = clap::ArgAction::SetTrue, help = "Restore targets from the backups taken before the last sync writes")]
+        restore: bool,
+        #[arg(
+            long,
+            value_name = "ID",
+            help = "Only sync this rule id (repeatable); with --restore, restore only these rules"
+        )]
+        rule: Vec<String>,
+    },
+    /// Merge two JSON files using the sync merge engine
+    #[command(
+        about = "Merge two JSON files",
+        long_about = "Apply the sync JSON merge (keep/override/array strategies) to a source and destination file without an index. Prints the result unless --write is set.",
+        after_help = "Examples:\n  rigra merge tpl/package.json package.json --keep name --array keywords=union\n  rigra merge a.json b.json --override scripts --write"
+    )]
+    Merge {
+        #[arg(help = "Source (template) JSON file")]
+        source: String,
+        #[arg(help = "Destination JSON file")]
+        dest: String,
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Keep destination value at path (repeatable)"
+        )]
+        keep: Vec<String>,
+        #[arg(
+            long = "override",
+            value_name = "PATH",
+            help = "Take source value at path (repeatable)"
+        )]
+        override_paths: Vec<String>,
+        #[arg(
+            long,
+            value_name = "PATH=STRATEGY",
+            help = "Array strategy union|replace at path (repeatable)"
+        )]
+        array: Vec<String>,
+        #[arg(long, action = clap::ArgAction::SetTrue, help = "Write the merged result to the destination file")]
+        write: bool,
+    },
+    /// Compare two policy files
+    #[command(
+        about = "Compare two policy files",
+        long_about = "Load two policies and list added, removed and changed checks plus differing top-level sections such as order.",
+        after_help = "Examples:\n  rigra diff-policy old/policy.toml conv/policy.toml\n  rigra diff-policy a.toml b.json5 --output json"
+    )]
+    DiffPolicy {
+        #[arg(help = "Old policy file")]
+        old: String,
+        #[arg(help = "New policy file")]
+        new: String,
+        #[arg(long, help = "Output mode: human|json|json-compact (default: human)")]
+        output: Option<String>,
+    },
+    /// Show which rules and checks apply
+    #[command(
+        about = "Explain rules",
+        long_about = "Show a rule's patterns, policy and check kinds, or with --file every rule whose patterns match that file.",
+        after_help = "Examples:\n  rigra explain pkg --index conv/index.toml\n  rigra explain --file package.json --index conv/index.toml"
+    )]
+    Explain {
+        #[arg(
+            help = "Rule id to explain",
+            required_unless_present = "file",
+            conflicts_with = "file"
+        )]
+        rule: Option<String>,
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "List the rules whose patterns match this file"
+        )]
+        file: Option<String>,
+        #[arg(
+            long,
+            help = "Repository root (default: $RIGRA_REPO_ROOT, else current dir)"
+        )]
+        repo_root: Option<String>,
+        #[arg(long, help = "Path to index.toml (required)")]
+        index: Option<String>,
+        #[arg(long, help = "Output mode: human|json|json-compact (default: human)")]
+        output: Option<String>,
+    },
+    /// Validate the policies an index references
+    #[command(
+        about = "Validate policies",
+        long_about = "Load every policy referenced by the index and report unreadable policies and regexes that do not compile, without reading any target file. Exits 1 when problems are found.",
+        after_help = "Examples:\n  rigra doctor --index conv/index.toml\n  rigra doctor --index conv/index.toml --output json"
+    )]
+    Doctor {
+        #[arg(
+            long,
+            help = "Repository root (default: $RIGRA_REPO_ROOT, else current dir)"
+        )]
+        repo_root: Option<String>,
+        #[arg(long, help = "Path to index.toml (required)")]
+        index: Option<String>,
+        #[arg(long, help = "Output mode: human|json|json-compact (default: human)")]
+        output: Option<String>,
+    },
+    /// Profile lint and format on the current repo
+    #[command(
+        hide = true,
+        about = "Benchmark lint/format",
+        long_about = "Run lint and format (never writing) N times each and report min/median/max wall time and files per second."
+    )]
+    Bench {
+        #[arg(
+            long,
+            help = "Repository root (default: $RIGRA_REPO_ROOT, else current dir)"
+        )]
+        repo_root: Option<String>,
+        #[arg(long, help = "Path to index.toml (required)")]
+        index: Option<String>,
+        #[arg(long, help = "Scope token for sync-related lint (e.g. repo, lib)")]
+        scope: Option<String>,
+        #[arg(long, value_name = "N", default_value_t = 5, help = "Runs per pass")]
+        iterations: usize,
     },
     /// Convention management (install/list/prune/path)
     Conv {
         #[command(subcommand)]
         cmd: ConvCmd,
     },
+    /// Policy file tools (fmt)
+    Policy {
+        #[command(subcommand)]
+        cmd: PolicyCmd,
+    },
+}
+
+#[derive(Subcommand)]
+/// Subcommands for `rigra policy`
+pub enum PolicyCmd {
+    /// Rewrite a TOML policy in canonical layout
+    #[command(
+        about = "Format a policy",
+        long_about = "Reorder a TOML policy canonically (top-level keys, checks, order, linebreak, formatting; kind and field first in each check, message and level last) while keeping comments. Prints the result unless --write or --check is set.",
+        after_help = "Examples:\n  rigra policy fmt conv/policy.toml\n  rigra policy fmt conv/policy.toml --write\n  rigra policy fmt conv/policy.toml --check"
+    )]
+    Fmt {
+        #[arg(help = "Policy TOML file")]
+        path: String,
+        #[arg(long, action = clap::ArgAction::SetTrue, help = "Rewrite the file in place")]
+        write: bool,
+        #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "write", help = "Exit non-zero if the file is not in canonical layout")]
+        check: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -97,16 +387,25 @@ pub enum ConvCmd {
     /// Install a convention into cache
     #[command(
         about = "Install convention",
-        long_about = "Install a convention archive into repo cache under .rigra/conv."
+        long_about = "Install a convention archive into the cache under .rigra/conv (or [conv].cacheDir)."
     )]
     Install {
-        #[arg(long, help = "Repository root (default: current dir)")]
+        #[arg(
+            long,
+            help = "Repository root (default: $RIGRA_REPO_ROOT, else current dir)"
+        )]
         repo_root: Option<String>,
         /// Optional source override: gh:owner/repo@tag or file:/abs/path
         source: Option<String>,
         /// Optional name@version override for cache key
         #[arg(long, help = "Override name@version used as cache folder key")]
         name: Option<String>,
+        #[arg(
+            long,
+            value_name = "DIR",
+            help = "Install under DIR instead of [conv].cacheDir or .rigra/conv"
+        )]
+        into: Option<String>,
     },
     /// List installed conventions
     #[command(
@@ -114,7 +413,10 @@ pub enum ConvCmd {
         long_about = "List installed convention cache entries."
     )]
     Ls {
-        #[arg(long, help = "Repository root (default: current dir)")]
+        #[arg(
+            long,
+            help = "Repository root (default: $RIGRA_REPO_ROOT, else current dir)"
+        )]
         repo_root: Option<String>,
     },
     /// Prune all convention cache
@@ -123,8 +425,26 @@ pub enum ConvCmd {
         long_about = "Remove all convention cache under .rigra/conv."
     )]
     Prune {
-        #[arg(long, help = "Repository root (default: current dir)")]
+        #[arg(
+            long,
+            help = "Repository root (default: $RIGRA_REPO_ROOT, else current dir)"
+        )]
+        repo_root: Option<String>,
+    },
+    /// List the indexes in a convention directory
+    #[command(
+        about = "List indexes",
+        long_about = "List the index files in a convention directory (conv:name@ver:dir/), as conv refs usable for --index. A file ref lists its directory.",
+        after_help = "Examples:\n  rigra conv indexes conv:myconv@v0.1.0:scopes/"
+    )]
+    Indexes {
+        #[arg(
+            long,
+            help = "Repository root (default: $RIGRA_REPO_ROOT, else current dir)"
+        )]
         repo_root: Option<String>,
+        #[arg(help = "Convention ref: conv:name@ver[:dir/]")]
+        conv: String,
     },
     /// Resolve a conv path (conv:name@ver[:subpath])
     #[command(
@@ -132,7 +452,10 @@ pub enum ConvCmd {
         long_about = "Resolve local cache path for a convention reference."
     )]
     Path {
-        #[arg(long, help = "Repository root (default: current dir)")]
+        #[arg(
+            long,
+            help = "Repository root (default: $RIGRA_REPO_ROOT, else current dir)"
+        )]
         repo_root: Option<String>,
         #[arg(help = "Convention ref: conv:name@ver[:subpath]")]
         conv: String,
@@ -1,7 +1,8 @@
 //! Configuration discovery and effective settings resolution.
 //!
-//! Rigra reads `rigra.toml` from the repository root (or closest
-//! ancestor) and merges it with CLI flags to produce an `Effective` config.
+//! Rigra reads `rigra.toml` (or `rigra.json5`) from the repository root (or
+//! closest ancestor) and merges it with CLI flags to produce an `Effective`
+//! config. When both exist, `rigra.toml` wins.
 //! Defaults:
 //! - `index`: `convention/index.toml`
 //! - `scope`: `repo`
@@ -9,6 +10,12 @@
 //! - `format.write|diff|check`: false
 //! - `format.strictLineBreak`: true
 //! - `format.linebreak.{between_groups,before_fields,in_fields}`: optional
+//! - `cache.dir`: `.rigra` (conv cache, sync checksums, default baseline)
+//! - `conv.cacheDir`: `<cache.dir>/conv`
+//! - `limits.maxFileBytes`: unset (no size limit)
+//! - `limits.streamArrays`: false
+//! - `ui.labels|icons`: per-severity overrides for human lint output
+//! - `scopes.<scope>`: scopes implied by `<scope>` (e.g. `lib = ["repo"]`)
 //!
 //! Overrides precedence: CLI > config file > defaults.
 
@@ -35,6 +42,88 @@ pub struct LineBreakCfg {
     pub in_fields: Option<std::collections::HashMap<String, String>>,     // keep|none
 }
 
+#[derive(Debug, Default, Deserialize, Clone)]
+/// Resource limits under `[limits]`.
+pub struct LimitsCfg {
+    /// Skip lint/format targets larger than this many bytes
+    #[serde(rename = "maxFileBytes")]
+    pub max_file_bytes: Option<u64>,
+    /// Stream top-level arrays for rules whose checks only use `$[*]` paths
+    #[serde(rename = "streamArrays")]
+    pub stream_arrays: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize, Clone)]
+/// Human output customization under `[ui]`.
+///
+/// Maps are keyed by severity (`error`, `warning`, `info`); unset entries
+/// keep the built-in `⟦error⟧`/`✖`-style glyphs.
+pub struct UiCfg {
+    #[serde(default)]
+    pub labels: std::collections::HashMap<String, String>,
+    #[serde(default)]
+    pub icons: std::collections::HashMap<String, String>,
+}
+
+#[derive(Debug, Default, Deserialize, Clone)]
+/// Cache/state location under `[cache]`.
+pub struct CacheCfg {
+    /// Base directory for all cache and state, relative to the repo root
+    pub dir: Option<String>,
+    /// Add the cache directory to the repo's `.gitignore` once rigra writes
+    /// under it (default true)
+    pub gitignore: Option<bool>,
+}
+
+/// Default cache/state directory, relative to the repository root.
+pub const DEFAULT_CACHE_DIR: &str = ".rigra";
+
+/// Environment variable used as the repository root when `--repo-root` is
+/// not passed.
+pub const REPO_ROOT_ENV: &str = "RIGRA_REPO_ROOT";
+
+/// Append `<cache dir>/` to `<repo>/.gitignore` unless an equivalent entry
+/// exists. Only applies to git repositories whose cache dir exists and lies
+/// inside the repo. Returns whether the file changed.
+pub fn ensure_gitignored(repo_root: &Path, cache_dir: &Path) -> Result<bool, String> {
+    if !repo_root.join(".git").exists() || !cache_dir.is_dir() {
+        return Ok(false);
+    }
+    let Ok(rel) = cache_dir.strip_prefix(repo_root) else {
+        return Ok(false);
+    };
+    let rel = rel.to_string_lossy().replace('\\', "/");
+    let rel = rel.trim_matches('/');
+    if rel.is_empty() {
+        return Ok(false);
+    }
+    let path = repo_root.join(".gitignore");
+    let existing = fs::read_to_string(&path).unwrap_or_default();
+    let covered = existing
+        .lines()
+        .map(|l| l.trim().trim_start_matches('/').trim_end_matches('/'))
+        .any(|l| l == rel);
+    if covered {
+        return Ok(false);
+    }
+    let mut out = existing;
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+    out.push_str(&format!("{}/\n", rel));
+    fs::write(&path, out)
+        .map_err(|e| format!("Failed to update '{}': {}", path.to_string_lossy(), e))?;
+    Ok(true)
+}
+
+/// Resolve the cache/state directory, defaulting to `<repo>/.rigra`.
+pub fn cache_dir_or_default(repo_root: &Path, cache_dir: Option<&Path>) -> PathBuf {
+    match cache_dir {
+        Some(d) => repo_root.join(d),
+        None => repo_root.join(DEFAULT_CACHE_DIR),
+    }
+}
+
 #[derive(Debug, Default, Deserialize, Clone)]
 /// Root configuration loaded from `rigra.toml`.
 pub struct RigletConfig {
@@ -48,6 +137,16 @@ pub struct RigletConfig {
     pub conv: Option<ConvCfg>,
     #[serde(default)]
     pub sync: Option<SyncCfg>,
+    #[serde(default)]
+    pub limits: Option<LimitsCfg>,
+    #[serde(default)]
+    pub cache: Option<CacheCfg>,
+    #[serde(default)]
+    pub ui: Option<UiCfg>,
+    /// Scope hierarchy under `[scopes]`: each scope lists the scopes it
+    /// implies, so `lib = ["repo"]` also enables `when = "repo"` rules
+    #[serde(default)]
+    pub scopes: Option<std::collections::HashMap<String, Vec<String>>>,
 }
 
 #[derive(Debug, Clone)]
@@ -56,6 +155,8 @@ pub struct Effective {
     pub repo_root: PathBuf,
     pub index: String,
     pub index_configured: bool,
+    /// Selected scope followed by every scope it implies through `[scopes]`,
+    /// comma-separated (`lib,repo`)
     pub scope: String,
     pub output: String,
     pub write: bool,
@@ -66,6 +167,13 @@ pub struct Effective {
     pub lb_before_fields: std::collections::HashMap<String, String>,
     pub lb_in_fields: std::collections::HashMap<String, String>,
     pub pattern_overrides: std::collections::HashMap<String, Vec<String>>, // id -> patterns
+    pub max_file_bytes: Option<u64>,
+    pub stream_arrays: bool,
+    pub ui: UiCfg,
+    /// Base directory for cache and state (`--cache-dir` > `[cache].dir` > `.rigra`)
+    pub cache_dir: PathBuf,
+    /// Keep the cache dir listed in `.gitignore` (`[cache].gitignore`)
+    pub gitignore_cache: bool,
 }
 
 #[derive(Debug, Default, Deserialize, Clone)]
@@ -78,11 +186,19 @@ pub struct ConvCfg {
     #[serde(rename = "autoInstall")]
     pub auto_install: Option<bool>,
     /// Package identifier with version, e.g. "@nazahex/conv-lib-ts-mono@v0.1.0" or "myconv@v0.1.0"
+    /// (resolved from the conv cache, else from `node_modules/<name>/<subpath>`)
     pub package: Option<String>,
     /// Single source of truth for installation: "gh:owner/repo@tag" or "file:/abs/path.tar.gz"
     pub source: Option<String>,
+    /// Ordered fallback sources, tried after `source` until one succeeds
+    #[serde(default)]
+    pub sources: Option<Vec<String>>,
     /// Optional default subpath inside archive (defaults to "index.toml")
     pub subpath: Option<String>,
+    /// Cache directory for installed conventions, relative to the repo root
+    /// (defaults to ".rigra/conv")
+    #[serde(rename = "cacheDir")]
+    pub cache_dir: Option<String>,
 }
 
 #[derive(Debug, Default, Deserialize, Clone)]
@@ -96,6 +212,9 @@ pub struct SyncCfg {
     /// Ignore specific sync IDs entirely
     #[serde(default)]
     pub ignore: Option<Vec<String>>, // [sync].ignore = ["id1","id2"]
+    /// Severity for every `sync:*` lint issue, overriding the sync policy
+    #[serde(rename = "lintLevel")]
+    pub lint_level: Option<String>,
 }
 
 #[derive(Debug, Default, Deserialize, Clone)]
@@ -108,6 +227,9 @@ pub struct SyncHooks {
 pub struct SyncClientCfg {
     pub target: Option<String>,
     pub merge: Option<SyncClientMergeCfg>,
+    /// Severity for this rule's sync lint issue; wins over `[sync].lintLevel`
+    #[serde(rename = "lintLevel")]
+    pub lint_level: Option<String>,
 }
 
 #[derive(Debug, Default, Deserialize, Clone)]
@@ -129,7 +251,7 @@ pub fn detect_repo_root(start: &Path) -> PathBuf {
     // Walk up to find config or .git; else return start
     let mut cur = start;
     loop {
-        if cur.join("rigra.toml").exists() {
+        if cur.join("rigra.toml").exists() || cur.join("rigra.json5").exists() {
             return cur.to_path_buf();
         }
         if cur.join(".git").exists() {
@@ -142,7 +264,7 @@ pub fn detect_repo_root(start: &Path) -> PathBuf {
     }
 }
 
-/// Load `RigletConfig` from `rigra.toml` if present.
+/// Load `RigletConfig` from `rigra.toml`, falling back to `rigra.json5`.
 pub fn load_config(root: &Path) -> Option<RigletConfig> {
     let toml_path = root.join("rigra.toml");
     if toml_path.exists() {
@@ -150,10 +272,19 @@ pub fn load_config(root: &Path) -> Option<RigletConfig> {
         let cfg: RigletConfig = toml::from_str(&s).ok()?;
         return Some(cfg);
     }
+    let json5_path = root.join("rigra.json5");
+    if json5_path.exists() {
+        let s = fs::read_to_string(&json5_path).ok()?;
+        return crate::json5::from_str(&s).ok();
+    }
     None
 }
 
 /// Resolve `Effective` by merging CLI flags, discovered config, and defaults.
+///
+/// Repo-root detection starts from `cli_repo_root`, else `RIGRA_REPO_ROOT`,
+/// else the current directory.
+#[allow(clippy::too_many_arguments)]
 pub fn resolve_effective(
     cli_repo_root: Option<&str>,
     cli_index: Option<&str>,
@@ -162,8 +293,10 @@ pub fn resolve_effective(
     cli_write: Option<bool>,
     cli_diff: Option<bool>,
     cli_check: Option<bool>,
+    cli_cache_dir: Option<&str>,
 ) -> Effective {
-    let start = PathBuf::from(cli_repo_root.unwrap_or("."));
+    let env_root = std::env::var(REPO_ROOT_ENV).ok().filter(|s| !s.is_empty());
+    let start = PathBuf::from(cli_repo_root.or(env_root.as_deref()).unwrap_or("."));
     let repo_root = detect_repo_root(&start);
     let cfg = load_config(&repo_root).unwrap_or_default();
 
@@ -220,25 +353,53 @@ pub fn resolve_effective(
         .map(|(id, ov)| (id, ov.patterns))
         .collect::<std::collections::HashMap<_, _>>();
 
+    let max_file_bytes = cfg.limits.as_ref().and_then(|l| l.max_file_bytes);
+    let stream_arrays = cfg
+        .limits
+        .as_ref()
+        .and_then(|l| l.stream_arrays)
+        .unwrap_or(false);
+    let ui = cfg.ui.clone().unwrap_or_default();
+    let cache_dir = cache_dir_or_default(
+        &repo_root,
+        cli_cache_dir
+            .map(|s| s.to_string())
+            .or_else(|| cfg.cache.as_ref().and_then(|c| c.dir.clone()))
+            .as_deref()
+            .map(Path::new),
+    );
+    let conv_cache = crate::conv::cache_root(&repo_root, &cache_dir);
+    let gitignore_cache = cfg.cache.as_ref().and_then(|c| c.gitignore).unwrap_or(true);
+
     // Conv config
     let conv_auto_install = cfg
         .conv
         .as_ref()
         .and_then(|c| c.auto_install)
         .unwrap_or(false);
-    let conv_source = cfg.conv.as_ref().and_then(|c| c.source.clone());
+    let conv_sources = cfg.conv.as_ref().map(conv_sources).unwrap_or_default();
 
     // Resolve conv index if specified using Option A: conv:name@ver[:subpath]
     if let Some(ref idx) = index_src {
         if let Some(cr) = crate::conv::parse_conv_ref(idx) {
-            let resolved = crate::conv::resolve_path(&repo_root, &cr);
+            let resolved = crate::conv::resolve_path(&conv_cache, &cr);
             // If not present, optionally auto-install from sources map
-            if !resolved.exists() && conv_auto_install {
-                if let Some(src) = conv_source.as_ref() {
-                    let name_ver = format!("{}@{}", cr.name, cr.ver);
-                    let _ = crate::conv::install(&repo_root, &name_ver, src);
-                }
+            if !resolved.exists() && conv_auto_install && !conv_sources.is_empty() {
+                let name_ver = format!("{}@{}", cr.name, cr.ver);
+                let _ = crate::conv::install_from(
+                    &cache_dir,
+                    &conv_cache,
+                    &name_ver,
+                    &cr.subpath,
+                    &conv_sources,
+                );
             }
+            // A directory ref (`dir/`) selects one of its indexes by scope
+            let resolved = if cr.subpath.ends_with('/') {
+                crate::conv::select_index(&resolved, &scope).unwrap_or(resolved)
+            } else {
+                resolved
+            };
             index = resolved
                 .strip_prefix(&repo_root)
                 .unwrap_or(resolved.as_path())
@@ -262,18 +423,26 @@ pub fn resolve_effective(
                         ver: ver.to_string(),
                         subpath,
                     };
-                    let resolved = crate::conv::resolve_path(&repo_root, &cr);
-                    if !resolved.exists() && conv_auto_install {
-                        if let Some(src) = conv_cfg.source.as_ref() {
-                            let mut src_str = src.clone();
-                            if src == "github" {
-                                if let Some((owner, repo)) = package_owner_repo(name) {
-                                    src_str = format!("gh:{}/{}@{}", owner, repo, ver);
-                                }
-                            }
-                            let _ = crate::conv::install(&repo_root, pkg, &src_str);
-                        }
+                    let resolved = crate::conv::resolve_path(&conv_cache, &cr);
+                    if !resolved.exists() && conv_auto_install && !conv_sources.is_empty() {
+                        let srcs: Vec<String> = conv_sources
+                            .iter()
+                            .map(|s| expand_github_source(s, pkg))
+                            .collect();
+                        let _ = crate::conv::install_from(
+                            &cache_dir,
+                            &conv_cache,
+                            pkg,
+                            &cr.subpath,
+                            &srcs,
+                        );
                     }
+                    // Node projects may ship the convention as a dependency instead
+                    let resolved = if resolved.exists() {
+                        resolved
+                    } else {
+                        node_modules_index(&repo_root, name, &cr.subpath).unwrap_or(resolved)
+                    };
                     index = resolved
                         .strip_prefix(&repo_root)
                         .unwrap_or(resolved.as_path())
@@ -285,6 +454,7 @@ pub fn resolve_effective(
         }
     }
 
+    let scope = expand_scope(&scope, &cfg.scopes.unwrap_or_default());
     Effective {
         repo_root,
         index,
@@ -299,14 +469,42 @@ pub fn resolve_effective(
         lb_before_fields,
         lb_in_fields,
         pattern_overrides,
+        max_file_bytes,
+        stream_arrays,
+        ui,
+        cache_dir,
+        gitignore_cache,
+    }
+}
+
+/// Expand `scope` with the scopes it implies, transitively, as a
+/// comma-separated list starting with `scope` itself. Cycles are ignored.
+pub fn expand_scope(
+    scope: &str,
+    implies: &std::collections::HashMap<String, Vec<String>>,
+) -> String {
+    let mut out: Vec<String> = vec![scope.to_string()];
+    let mut i = 0;
+    while i < out.len() {
+        for next in implies.get(&out[i]).into_iter().flatten() {
+            if !out.iter().any(|s| s.eq_ignore_ascii_case(next)) {
+                out.push(next.clone());
+            }
+        }
+        i += 1;
     }
+    out.join(",")
+}
+
+/// Index shipped by an installed npm package, `node_modules/<name>/<subpath>`,
+/// when it exists.
+pub fn node_modules_index(repo_root: &Path, name: &str, subpath: &str) -> Option<PathBuf> {
+    let p = repo_root.join("node_modules").join(name).join(subpath);
+    p.is_file().then_some(p)
 }
 
 pub fn rsplit_once_at(s: &str, ch: char) -> Option<(&str, &str)> {
-    let mut iter = s.rsplitn(2, ch);
-    let b = iter.next()?;
-    let a = iter.next()?;
-    Some((a, b))
+    s.rsplit_once(ch)
 }
 
 pub fn package_owner_repo(name: &str) -> Option<(String, String)> {
@@ -322,6 +520,27 @@ pub fn package_owner_repo(name: &str) -> Option<(String, String)> {
     }
 }
 
+/// Ordered install sources from `[conv]`: `source` first, then `sources`.
+pub fn conv_sources(conv: &ConvCfg) -> Vec<String> {
+    conv.source
+        .iter()
+        .chain(conv.sources.iter().flatten())
+        .cloned()
+        .collect()
+}
+
+/// Expand the `github` shorthand into `gh:owner/repo@ver` using `name@ver`.
+pub fn expand_github_source(src: &str, name_ver: &str) -> String {
+    if src != "github" {
+        return src.to_string();
+    }
+    rsplit_once_at(name_ver, '@')
+        .and_then(|(name, ver)| {
+            package_owner_repo(name).map(|(owner, repo)| format!("gh:{}/{}@{}", owner, repo, ver))
+        })
+        .unwrap_or_else(|| src.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -335,7 +554,6 @@ mod tests {
         let mut f = fs::File::create(root.join("rigra.toml")).unwrap();
         writeln!(
             f,
-            "{}",
             r#"
 index = "conventions/acme/index.toml"
 scope = "repo"
@@ -347,12 +565,157 @@ write = true
         .unwrap();
 
         // Resolve using explicit repo_root to avoid global CWD races
-        let eff = resolve_effective(root.to_str(), None, None, None, None, None, None);
+        let eff = resolve_effective(root.to_str(), None, None, None, None, None, None, None);
         assert_eq!(eff.index, "conventions/acme/index.toml");
         assert_eq!(eff.output, "json");
         assert!(eff.write);
     }
 
+    #[test]
+    fn test_scopes_expand_transitively() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::write(
+            root.join("rigra.toml"),
+            "scope = \"pkg\"\n[scopes]\npkg = [\"lib\"]\nlib = [\"repo\"]\nrepo = [\"pkg\"]\n",
+        )
+        .unwrap();
+        let eff = resolve_effective(root.to_str(), None, None, None, None, None, None, None);
+        assert_eq!(eff.scope, "pkg,lib,repo");
+        let eff = resolve_effective(
+            root.to_str(),
+            None,
+            Some("lib"),
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        assert_eq!(eff.scope, "lib,repo,pkg");
+        let eff = resolve_effective(
+            root.to_str(),
+            None,
+            Some("app"),
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        assert_eq!(eff.scope, "app");
+    }
+
+    #[test]
+    fn test_repo_root_env_applies_without_flag() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("rigra.toml"), "index = \"conv/index.toml\"\n").unwrap();
+        let other = tempdir().unwrap();
+        fs::write(other.path().join("rigra.toml"), "").unwrap();
+
+        // No other test resolves without a repo root, so the env is not shared
+        std::env::set_var(REPO_ROOT_ENV, root);
+        let from_env = resolve_effective(None, None, None, None, None, None, None, None);
+        let from_flag = resolve_effective(
+            other.path().to_str(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        std::env::remove_var(REPO_ROOT_ENV);
+
+        assert_eq!(from_env.repo_root, root);
+        assert_eq!(from_env.index, "conv/index.toml");
+        // The flag still wins
+        assert_eq!(from_flag.repo_root, other.path());
+        assert!(!from_flag.index_configured);
+    }
+
+    #[test]
+    fn test_ensure_gitignored_adds_entry_once() {
+        let td = tempfile::tempdir().unwrap();
+        let repo = td.path();
+        let cache = repo.join(DEFAULT_CACHE_DIR);
+        fs::create_dir_all(&cache).unwrap();
+        // Not a git repo: left alone
+        assert!(!ensure_gitignored(repo, &cache).unwrap());
+        assert!(!repo.join(".gitignore").exists());
+
+        fs::create_dir_all(repo.join(".git")).unwrap();
+        fs::write(repo.join(".gitignore"), "node_modules").unwrap();
+        assert!(ensure_gitignored(repo, &cache).unwrap());
+        assert!(!ensure_gitignored(repo, &cache).unwrap());
+        assert_eq!(
+            fs::read_to_string(repo.join(".gitignore")).unwrap(),
+            "node_modules\n.rigra/\n"
+        );
+        // Equivalent spellings count as present
+        fs::write(repo.join(".gitignore"), "/.rigra\n").unwrap();
+        assert!(!ensure_gitignored(repo, &cache).unwrap());
+    }
+
+    #[test]
+    fn test_cache_dir_precedence() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let eff = resolve_effective(root.to_str(), None, None, None, None, None, None, None);
+        assert_eq!(eff.cache_dir, root.join(".rigra"));
+
+        fs::write(root.join("rigra.toml"), "[cache]\ndir = \"tmp/cache\"\n").unwrap();
+        let eff = resolve_effective(root.to_str(), None, None, None, None, None, None, None);
+        assert_eq!(eff.cache_dir, root.join("tmp/cache"));
+
+        let cli = root.join("cli-cache");
+        let eff = resolve_effective(
+            root.to_str(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            cli.to_str(),
+        );
+        assert_eq!(eff.cache_dir, cli);
+    }
+
+    #[test]
+    fn test_detect_and_load_json5_with_comments() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::write(
+            root.join("rigra.json5"),
+            r#"
+// Team config authored in JSON5
+{
+  index: 'conventions/acme/index.toml',
+  output: "json", /* machine-readable */
+  format: { write: true, strictLineBreak: false },
+  limits: { maxFileBytes: 0x400 },
+  rules: { pkgjson: { patterns: ['package.json',] } },
+}
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(detect_repo_root(root), root.to_path_buf());
+        let eff = resolve_effective(root.to_str(), None, None, None, None, None, None, None);
+        assert_eq!(eff.index, "conventions/acme/index.toml");
+        assert_eq!(eff.output, "json");
+        assert!(eff.write);
+        assert!(!eff.strict_linebreak);
+        assert_eq!(eff.max_file_bytes, Some(1024));
+        assert_eq!(
+            eff.pattern_overrides.get("pkgjson"),
+            Some(&vec!["package.json".to_string()])
+        );
+    }
+
     #[test]
     fn test_precedence_and_linebreak_overrides_loaded() {
         let dir = tempdir().unwrap();
@@ -360,7 +723,6 @@ write = true
         let mut f = fs::File::create(root.join("rigra.toml")).unwrap();
         writeln!(
             f,
-            "{}",
             r#"
 index = "conventions/acme/index.toml"
 scope = "repo"
@@ -381,7 +743,16 @@ scripts = "keep"
         .unwrap();
 
         // CLI overrides write=false should take precedence over config write=true
-        let eff = resolve_effective(root.to_str(), None, None, None, Some(false), None, None);
+        let eff = resolve_effective(
+            root.to_str(),
+            None,
+            None,
+            None,
+            Some(false),
+            None,
+            None,
+            None,
+        );
         assert!(!eff.write);
         // Linebreak overrides should be loaded from config
         assert_eq!(eff.lb_between_groups, Some(false));
@@ -402,7 +773,6 @@ scripts = "keep"
         let mut f = fs::File::create(root.join("rigra.toml")).unwrap();
         writeln!(
             f,
-            "{}",
             r#"
 index = "conv:hyperedge@v0.1.0"
 scope = "repo"
@@ -411,7 +781,7 @@ output = "json"
         )
         .unwrap();
 
-        let eff = resolve_effective(root.to_str(), None, None, None, None, None, None);
+        let eff = resolve_effective(root.to_str(), None, None, None, None, None, None, None);
         assert!(eff.index_configured);
         // Should resolve to cache path with default index.toml
         let expected = root
@@ -442,21 +812,18 @@ output = "json"
         let mut f = fs::File::create(root.join("rigra.toml")).unwrap();
         writeln!(
             f,
-            "{}",
-            format!(
-                r#"
+            r#"
 [conv]
 autoInstall = true
 package = "myconv@v0.1.0"
 source = "file:{}"
                 "#,
-                tgz.to_string_lossy()
-            )
+            tgz.to_string_lossy()
         )
         .unwrap();
 
         // Resolve; should trigger auto-install and point to cache path
-        let eff = resolve_effective(root.to_str(), None, None, None, None, None, None);
+        let eff = resolve_effective(root.to_str(), None, None, None, None, None, None, None);
         let resolved = root.join(&eff.index);
         assert!(resolved.exists());
     }
@@ -468,7 +835,6 @@ source = "file:{}"
         let mut f = fs::File::create(root.join("rigra.toml")).unwrap();
         writeln!(
             f,
-            "{}",
             r#"
 [conv]
 autoInstall = false
@@ -478,7 +844,7 @@ source = "github"
         )
         .unwrap();
 
-        let eff = resolve_effective(root.to_str(), None, None, None, None, None, None);
+        let eff = resolve_effective(root.to_str(), None, None, None, None, None, None, None);
         assert!(eff.index_configured);
         let expected = root
             .join(".rigra/conv/@nazahex__conv-lib-ts-mono@v0.1.0/index.toml")
@@ -487,4 +853,31 @@ source = "github"
         assert_eq!(root.join(&eff.index).to_string_lossy(), expected);
         // No installation attempted since autoInstall=false; file won't exist.
     }
+
+    #[test]
+    fn test_conv_package_falls_back_to_node_modules_index() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::write(
+            root.join("rigra.toml"),
+            "[conv]\npackage = \"@nazahex/conv-lib-ts-mono@v0.1.0\"\nsubpath = \"conv/index.toml\"\n",
+        )
+        .unwrap();
+        let shipped = root.join("node_modules/@nazahex/conv-lib-ts-mono/conv");
+        fs::create_dir_all(&shipped).unwrap();
+        fs::write(shipped.join("index.toml"), "").unwrap();
+
+        let eff = resolve_effective(root.to_str(), None, None, None, None, None, None, None);
+        assert!(eff.index_configured);
+        assert_eq!(
+            eff.index,
+            "node_modules/@nazahex/conv-lib-ts-mono/conv/index.toml"
+        );
+        // An installed cache entry still wins
+        let cached = root.join(".rigra/conv/@nazahex__conv-lib-ts-mono@v0.1.0/conv");
+        fs::create_dir_all(&cached).unwrap();
+        fs::write(cached.join("index.toml"), "").unwrap();
+        let eff = resolve_effective(root.to_str(), None, None, None, None, None, None, None);
+        assert!(eff.index.starts_with(".rigra/conv/"));
+    }
 }
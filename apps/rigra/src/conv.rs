@@ -2,9 +2,14 @@
 //!
 //! Implements minimal functions to:
 //! - Parse `conv:` index strings (`conv:name@ver[:subpath]`)
-//! - Resolve cache path under `.rigra/conv/name@ver/subpath`
-//! - Install conventions from sources: `gh:owner/repo@tag` or `file:/abs/path`
+//! - Resolve cache path under `<cache dir>/conv/name@ver/subpath` (the cache
+//!   dir defaults to `.rigra`), or under `[conv].cacheDir` when configured
+//! - Resolve index-relative references that may point into another convention
+//! - Install conventions from sources: `gh:owner/repo@tag` or `file:/abs/path`,
+//!   optionally trying an ordered list of sources until one succeeds
 //! - List and prune cache
+//! - Enumerate the indexes in a convention directory (`conv:name@ver:dir/`)
+//!   and pick one per scope
 
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -35,14 +40,35 @@ pub fn parse_conv_ref(s: &str) -> Option<ConvRef> {
     })
 }
 
-pub fn cache_root(repo_root: &Path) -> PathBuf {
-    repo_root.join(".rigra").join("conv")
+/// Convention cache root: `[conv].cacheDir` from rigra config when set
+/// (relative to the repo root), else `<cache_dir>/conv`.
+pub fn cache_root(repo_root: &Path, cache_dir: &Path) -> PathBuf {
+    match crate::config::load_config(repo_root)
+        .and_then(|c| c.conv)
+        .and_then(|c| c.cache_dir)
+    {
+        Some(d) => repo_root.join(d),
+        None => cache_dir.join("conv"),
+    }
+}
+
+/// Path of a convention file inside the cache rooted at `cache`.
+pub fn resolve_path(cache: &Path, cr: &ConvRef) -> PathBuf {
+    cache.join(cache_key(&cr.name, &cr.ver)).join(&cr.subpath)
 }
 
-pub fn resolve_path(repo_root: &Path, cr: &ConvRef) -> PathBuf {
-    cache_root(repo_root)
-        .join(cache_key(&cr.name, &cr.ver))
-        .join(&cr.subpath)
+/// Resolve a path referenced from an index file.
+///
+/// `conv:name@ver:subpath` references resolve into the convention cache
+/// `cache`; anything else is taken relative to the index file's directory.
+pub fn resolve_index_ref(cache: &Path, idx_path: &Path, reference: &str) -> PathBuf {
+    match parse_conv_ref(reference) {
+        Some(cr) => resolve_path(cache, &cr),
+        None => idx_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(reference),
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -76,26 +102,120 @@ pub fn parse_source(s: &str) -> Option<Source> {
     None
 }
 
-/// Install a convention into repo cache.
+/// Install a convention trying each source in order until one succeeds.
+/// Every attempt and failure is logged to stderr.
+pub fn install_from(
+    cache_dir: &Path,
+    cache: &Path,
+    name_ver: &str,
+    subpath: &str,
+    sources: &[String],
+) -> Result<PathBuf, String> {
+    let mut failures = Vec::new();
+    for src in sources {
+        eprintln!(
+            "{} installing {} from {}",
+            crate::utils::note_prefix(),
+            name_ver,
+            src
+        );
+        match install(cache_dir, cache, name_ver, subpath, src) {
+            Ok(p) => return Ok(p),
+            Err(e) => {
+                eprintln!(
+                    "{} source {} failed: {}",
+                    crate::utils::warn_prefix(),
+                    src,
+                    e
+                );
+                failures.push(format!("{}: {}", src, e));
+            }
+        }
+    }
+    if failures.is_empty() {
+        return Err("no sources configured".to_string());
+    }
+    Err(format!("all sources failed ({})", failures.join("; ")))
+}
+
+/// Install a convention under the `cache` root (see `cache_root`); downloads
+/// are staged under `<cache_dir>/tmp`.
 /// Uses system `curl` and `tar` to keep binary small.
-pub fn install(repo_root: &Path, name_ver: &str, source_str: &str) -> Result<PathBuf, String> {
+///
+/// A fresh install fails, leaving no cache entry, when the archive lacks
+/// `subpath` (the index file, or a directory when it ends in `/`); the
+/// error lists the archive's top-level entries.
+pub fn install(
+    cache_dir: &Path,
+    cache: &Path,
+    name_ver: &str,
+    subpath: &str,
+    source_str: &str,
+) -> Result<PathBuf, String> {
     let src = parse_source(source_str).ok_or_else(|| "invalid source".to_string())?;
     let (name, ver) = name_ver
         .rsplit_once('@')
         .ok_or_else(|| "name must be in form name@version".to_string())?;
-    let dest_root = cache_root(repo_root).join(cache_key(name, ver));
+    let dest_root = cache.join(cache_key(name, ver));
     if dest_root.exists() {
         return Ok(dest_root);
     }
     fs::create_dir_all(&dest_root).map_err(|e| format!("create cache dir: {}", e))?;
+    let res = fetch(cache_dir, &dest_root, src)
+        .and_then(|root| verify_subpath(&root, subpath).map(|_| root));
+    if res.is_err() {
+        // Leave no half-populated cache entry behind so a fallback can retry
+        let _ = fs::remove_dir_all(&dest_root);
+    }
+    res
+}
+
+/// Check that an extracted convention contains `subpath`.
+fn verify_subpath(root: &Path, subpath: &str) -> Result<(), String> {
+    let target = root.join(subpath);
+    let found = if subpath.ends_with('/') {
+        target.is_dir()
+    } else {
+        target.is_file()
+    };
+    if found {
+        return Ok(());
+    }
+    let mut entries: Vec<String> = fs::read_dir(root)
+        .map(|rd| {
+            rd.flatten()
+                .map(|e| {
+                    let name = e.file_name().to_string_lossy().to_string();
+                    if e.path().is_dir() {
+                        format!("{}/", name)
+                    } else {
+                        name
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    entries.sort();
+    Err(format!(
+        "'{}' not found in archive (top-level entries: {})",
+        subpath,
+        if entries.is_empty() {
+            "none".to_string()
+        } else {
+            entries.join(", ")
+        }
+    ))
+}
+
+fn fetch(cache_dir: &Path, dest_root: &Path, src: Source) -> Result<PathBuf, String> {
+    let dest_root = dest_root.to_path_buf();
     match src {
         Source::Gh { owner, repo, tag } => {
             let url = format!(
                 "https://github.com/{}/{}/archive/refs/tags/{}.tar.gz",
                 owner, repo, tag
             );
-            let tmp = repo_root
-                .join(".rigra")
+            let tmp = cache_dir
                 .join("tmp")
                 .join(format!("{}-{}-{}.tar.gz", owner, repo, tag));
             let tmp_parent = tmp.parent().unwrap_or(Path::new("."));
@@ -143,10 +263,9 @@ pub fn install(repo_root: &Path, name_ver: &str, source_str: &str) -> Result<Pat
     }
 }
 
-pub fn list(repo_root: &Path) -> Vec<String> {
+pub fn list(cache: &Path) -> Vec<String> {
     let mut out = Vec::new();
-    let root = cache_root(repo_root);
-    if let Ok(rd) = fs::read_dir(root) {
+    if let Ok(rd) = fs::read_dir(cache) {
         for e in rd.flatten() {
             if let Ok(md) = e.metadata() {
                 if md.is_dir() {
@@ -161,10 +280,69 @@ pub fn list(repo_root: &Path) -> Vec<String> {
     out
 }
 
-pub fn prune(repo_root: &Path) -> Result<(), String> {
-    let root = cache_root(repo_root);
-    if root.exists() {
-        fs::remove_dir_all(&root).map_err(|e| format!("prune failed: {}", e))?;
+/// Directory part of a convention ref: the subpath itself when it ends in
+/// `/`, otherwise its parent (`index.toml` → the convention root).
+pub fn dir_subpath(cr: &ConvRef) -> &str {
+    if cr.subpath.ends_with('/') {
+        return &cr.subpath;
+    }
+    match cr.subpath.rfind('/') {
+        Some(i) => &cr.subpath[..=i],
+        None => "",
+    }
+}
+
+/// Index files in `dir`, relative to it and sorted: `*.toml` files and
+/// `<sub>/index.toml`, keeping only those with a top-level `rules`, `sync`
+/// or `include` key (so policies alongside them are skipped).
+pub fn list_indexes(dir: &Path) -> Result<Vec<String>, String> {
+    let rd = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read '{}': {}", dir.to_string_lossy(), e))?;
+    let mut out = Vec::new();
+    for e in rd.flatten() {
+        let name = e.file_name().to_string_lossy().to_string();
+        let rel = if e.path().is_dir() {
+            format!("{}/index.toml", name)
+        } else if name.ends_with(".toml") {
+            name
+        } else {
+            continue;
+        };
+        let is_index = fs::read_to_string(dir.join(&rel))
+            .ok()
+            .and_then(|s| toml::from_str::<toml::Table>(&s).ok())
+            .is_some_and(|t| {
+                ["rules", "sync", "include"]
+                    .iter()
+                    .any(|k| t.contains_key(*k))
+            });
+        if is_index {
+            out.push(rel);
+        }
+    }
+    out.sort();
+    Ok(out)
+}
+
+/// Pick the index for `scope` among `list_indexes(dir)`: `<scope>.toml`,
+/// then `<scope>/index.toml`, then `index.toml`, then the only one.
+pub fn select_index(dir: &Path, scope: &str) -> Option<PathBuf> {
+    let found = list_indexes(dir).ok()?;
+    let preferred = [
+        format!("{}.toml", scope),
+        format!("{}/index.toml", scope),
+        "index.toml".to_string(),
+    ];
+    preferred
+        .iter()
+        .find(|p| found.contains(p))
+        .or_else(|| found.first().filter(|_| found.len() == 1))
+        .map(|p| dir.join(p))
+}
+
+pub fn prune(cache: &Path) -> Result<(), String> {
+    if cache.exists() {
+        fs::remove_dir_all(cache).map_err(|e| format!("prune failed: {}", e))?;
     }
     Ok(())
 }
@@ -218,16 +396,57 @@ mod tests {
             ver: "v0".into(),
             subpath: "index.toml".into(),
         };
-        let p = resolve_path(root, &cr);
+        let cache = cache_root(root, &root.join(".rigra"));
+        assert_eq!(cache, root.join(".rigra/conv"));
+        let p = resolve_path(&cache, &cr);
         fs::create_dir_all(p.parent().unwrap()).unwrap();
         let mut f = fs::File::create(&p).unwrap();
-        writeln!(f, "{}", "# index").unwrap();
+        writeln!(f, "# index").unwrap();
 
-        let items = list(root);
+        let items = list(&cache);
         assert_eq!(items, vec!["hx@v0".to_string()]);
 
-        prune(root).unwrap();
-        assert!(list(root).is_empty());
+        prune(&cache).unwrap();
+        assert!(list(&cache).is_empty());
+    }
+
+    #[test]
+    fn test_list_and_select_indexes_in_directory() {
+        let dir = tempdir().unwrap();
+        let cache = dir.path();
+        let cr = parse_conv_ref("conv:multi@v1:scopes/").unwrap();
+        assert_eq!(dir_subpath(&cr), "scopes/");
+        let scopes = resolve_path(cache, &cr);
+        fs::create_dir_all(scopes.join("lib")).unwrap();
+        let index = "[[rules]]\nid = \"a\"\npatterns = [\"a.json\"]\npolicy = \"p.toml\"\n";
+        fs::write(scopes.join("repo.toml"), index).unwrap();
+        fs::write(scopes.join("lib/index.toml"), "sync = \"sync.toml\"\n").unwrap();
+        // Policies and other files are not indexes
+        fs::write(
+            scopes.join("p.toml"),
+            "[[checks]]\nkind = \"required\"\nfields = []\n",
+        )
+        .unwrap();
+        fs::write(scopes.join("README.md"), "# scopes").unwrap();
+
+        assert_eq!(
+            list_indexes(&scopes).unwrap(),
+            vec!["lib/index.toml", "repo.toml"]
+        );
+        assert_eq!(
+            select_index(&scopes, "repo"),
+            Some(scopes.join("repo.toml"))
+        );
+        assert_eq!(
+            select_index(&scopes, "lib"),
+            Some(scopes.join("lib/index.toml"))
+        );
+        assert_eq!(select_index(&scopes, "app"), None);
+
+        let file_ref = parse_conv_ref("conv:multi@v1:scopes/repo.toml").unwrap();
+        assert_eq!(dir_subpath(&file_ref), "scopes/");
+        assert_eq!(dir_subpath(&parse_conv_ref("conv:multi@v1").unwrap()), "");
+        assert!(list_indexes(&cache.join("missing")).is_err());
     }
 
     #[test]
@@ -250,9 +469,12 @@ mod tests {
         assert!(status.success());
 
         // Install into cache
+        let state = root.join(".rigra");
         let dest = install(
-            root,
+            &state,
+            &cache_root(root, &state),
             "myconv@v0.1.0",
+            "index.toml",
             &format!("file:{}", tgz.to_string_lossy()),
         )
         .unwrap();
@@ -260,6 +482,82 @@ mod tests {
         assert!(dest.join("nested/file.txt").exists());
     }
 
+    #[test]
+    fn test_install_with_missing_subpath_fails_and_lists_entries() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let staged = root.join("staged");
+        fs::create_dir_all(staged.join("scopes")).unwrap();
+        fs::write(staged.join("index.toml"), "# idx").unwrap();
+        let tgz = root.join("archive.tar.gz");
+        let status = std::process::Command::new("tar")
+            .current_dir(&staged)
+            .args(["-czf", tgz.to_str().unwrap(), "."])
+            .status()
+            .expect("tar exec");
+        assert!(status.success());
+
+        let state = root.join(".rigra");
+        let cache = cache_root(root, &state);
+        let src = format!("file:{}", tgz.to_string_lossy());
+        let err = install(&state, &cache, "myconv@v1", "conv/index.toml", &src).unwrap_err();
+        assert_eq!(
+            err,
+            "'conv/index.toml' not found in archive (top-level entries: index.toml, scopes/)"
+        );
+        // Nothing is cached, so a corrected retry installs
+        assert!(list(&cache).is_empty());
+        assert!(install(&state, &cache, "myconv@v1", "scopes/", &src).is_ok());
+    }
+
+    #[test]
+    fn test_install_from_falls_back_to_next_source() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let staged = root.join("staged");
+        fs::create_dir_all(&staged).unwrap();
+        fs::write(staged.join("index.toml"), "# idx").unwrap();
+        let tgz = root.join("archive.tar.gz");
+        let status = std::process::Command::new("tar")
+            .current_dir(&staged)
+            .args(["-czf", tgz.to_str().unwrap(), "."])
+            .status()
+            .expect("tar exec");
+        assert!(status.success());
+
+        let sources = vec![
+            format!("file:{}", root.join("missing.tar.gz").to_string_lossy()),
+            format!("file:{}", tgz.to_string_lossy()),
+        ];
+        let state = root.join(".rigra");
+        let cache = cache_root(root, &state);
+        let dest = install_from(&state, &cache, "myconv@v0.1.0", "index.toml", &sources).unwrap();
+        assert!(dest.join("index.toml").exists());
+
+        let bad = vec![format!(
+            "file:{}",
+            root.join("nope.tar.gz").to_string_lossy()
+        )];
+        let err = install_from(&state, &cache, "other@v1", "index.toml", &bad).unwrap_err();
+        assert!(err.contains("all sources failed"));
+        assert!(!list(&cache).contains(&"other@v1".to_string()));
+    }
+
+    #[test]
+    fn test_resolve_index_ref_relative_and_conv() {
+        let root = Path::new("/repo");
+        let idx = root.join("conv/index.toml");
+        let cache = root.join(".rigra/conv");
+        assert_eq!(
+            resolve_index_ref(&cache, &idx, "policies/pkg.toml"),
+            root.join("conv/policies/pkg.toml")
+        );
+        assert_eq!(
+            resolve_index_ref(&cache, &idx, "conv:other@v1:policies/pkg.toml"),
+            root.join(".rigra/conv/other@v1/policies/pkg.toml")
+        );
+    }
+
     #[test]
     fn test_parse_conv_ref_scoped_name_and_cache_key() {
         let cr = parse_conv_ref("conv:@nazahex/conv-lib-ts-mono@v0.1.0").unwrap();
@@ -269,4 +567,55 @@ mod tests {
         let s = p.to_string_lossy();
         assert!(s.contains("@nazahex__conv-lib-ts-mono@v0.1.0"));
     }
+
+    #[test]
+    fn test_install_into_configured_cache_dir_and_resolve() {
+        let dir = tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        let staged = dir.path().join("staged");
+        fs::create_dir_all(&repo).unwrap();
+        fs::create_dir_all(&staged).unwrap();
+        fs::write(staged.join("index.toml"), "# idx").unwrap();
+        let tgz = dir.path().join("archive.tar.gz");
+        let status = std::process::Command::new("tar")
+            .current_dir(&staged)
+            .args(["-czf", tgz.to_str().unwrap(), "."])
+            .status()
+            .expect("tar exec");
+        assert!(status.success());
+
+        // Shared cache outside the repo, as mounted on CI
+        fs::write(
+            repo.join("rigra.toml"),
+            "[conv]\ncacheDir = \"../shared-cache\"\n",
+        )
+        .unwrap();
+        let state = repo.join(".rigra");
+        let cache = cache_root(&repo, &state);
+        assert_eq!(cache, repo.join("../shared-cache"));
+
+        let dest = install(
+            &state,
+            &cache,
+            "myconv@v1",
+            "index.toml",
+            &format!("file:{}", tgz.to_string_lossy()),
+        )
+        .unwrap();
+        assert!(dest.starts_with(&cache));
+        assert!(!repo.join(".rigra/conv").exists());
+
+        let cr = parse_conv_ref("conv:myconv@v1").unwrap();
+        let idx = resolve_path(&cache, &cr);
+        assert_eq!(idx, cache.join("myconv@v1/index.toml"));
+        assert!(idx.exists());
+        assert_eq!(list(&cache), vec!["myconv@v1".to_string()]);
+    }
+
+    #[test]
+    fn test_cache_root_follows_cache_dir() {
+        let dir = tempdir().unwrap();
+        let shared = dir.path().join("shared");
+        assert_eq!(cache_root(dir.path(), &shared), shared.join("conv"));
+    }
 }
@@ -0,0 +1,117 @@
+//! Policy validation (`rigra doctor`).
+//!
+//! Loads every policy the index references and reports authoring errors —
+//! unreadable or unparsable policies, regexes that do not compile and
+//! number ranges no value can satisfy — without reading any target file,
+//! so mistakes surface even when no file matches a rule yet.
+
+use crate::models::index::Index;
+use crate::models::policy::Policy;
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// One problem found in a rule's policy.
+#[derive(Debug)]
+pub struct Problem {
+    pub rule: String,
+    pub policy: PathBuf,
+    pub message: String,
+}
+
+/// Validate the policies of every rule in the index at
+/// `repo_root/index_path`, includes resolved. Errors only when the index
+/// itself cannot be loaded.
+pub fn run(repo_root: &Path, index_path: &str, cache_dir: &Path) -> Result<Vec<Problem>, String> {
+    let idx_path = repo_root.join(index_path);
+    let s = fs::read_to_string(&idx_path).map_err(|e| {
+        format!(
+            "Failed to read index: {} — {}",
+            idx_path.to_string_lossy(),
+            e
+        )
+    })?;
+    let mut index: Index = toml::from_str(&s).map_err(|e| {
+        format!(
+            "Failed to parse index TOML: {} — {}",
+            idx_path.to_string_lossy(),
+            e
+        )
+    })?;
+    index.resolve_includes(&idx_path)?;
+    let conv_cache = crate::conv::cache_root(repo_root, cache_dir);
+    let mut problems = Vec::new();
+    for ri in index.rules {
+        let policy = crate::conv::resolve_index_ref(&conv_cache, &idx_path, &ri.policy);
+        let parsed = fs::read_to_string(&policy)
+            .map_err(|e| format!("Policy file not found: {}", e))
+            .and_then(|s| {
+                Policy::parse(&policy, &s).map_err(|e| format!("Failed to parse policy: {}", e))
+            });
+        let messages = match parsed {
+            Ok(p) => {
+                let mut found = invalid_regexes(&p);
+                found.extend(p.checks.iter().filter_map(|c| c.impossible_range()));
+                found
+            }
+            Err(e) => vec![e],
+        };
+        problems.extend(messages.into_iter().map(|message| Problem {
+            rule: ri.id.clone(),
+            policy: policy.clone(),
+            message,
+        }));
+    }
+    Ok(problems)
+}
+
+/// Describe every regex in `policy` that fails to compile, in check order.
+pub fn invalid_regexes(policy: &Policy) -> Vec<String> {
+    let mut out = Vec::new();
+    for chk in &policy.checks {
+        for re in chk.regexes() {
+            if let Err(e) = Regex::new(re) {
+                out.push(format!(
+                    "Invalid regex '{}' in {} check: {}",
+                    re,
+                    chk.kind(),
+                    e
+                ));
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_regexes_lists_each_bad_pattern() {
+        let policy: Policy = toml::from_str(
+            r#"
+[[checks]]
+kind = "pattern"
+field = "$.name"
+regex = "^[a-z]+$"
+
+[[checks]]
+kind = "notPattern"
+field = "$.version"
+regex = "(dev"
+
+[[checks]]
+kind = "patternSet"
+field = "$.license"
+allow = ["MIT", "[Apache"]
+deny = ["GPL"]
+"#,
+        )
+        .unwrap();
+        let got = invalid_regexes(&policy);
+        assert_eq!(got.len(), 2, "{:?}", got);
+        assert!(got[0].starts_with("Invalid regex '(dev' in notPattern check"));
+        assert!(got[1].starts_with("Invalid regex '[Apache' in patternSet check"));
+    }
+}
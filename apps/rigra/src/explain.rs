@@ -0,0 +1,274 @@
+//! Rule introspection (`rigra explain`).
+//!
+//! Lists index rules with their effective patterns, resolved policy and the
+//! checks that policy runs (kind, fields, message and effective level),
+//! either for one rule id or for every rule whose patterns match a given
+//! file.
+
+use crate::models::index::Index;
+use crate::models::policy::Policy;
+use glob::{MatchOptions, Pattern};
+use serde_json::Value as Json;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// One index rule as lint would run it.
+#[derive(Debug)]
+pub struct RuleExplain {
+    pub rule: String,
+    /// Patterns in effect (rigra.toml overrides win over the index)
+    pub patterns: Vec<String>,
+    pub policy: PathBuf,
+    /// Check kinds in declaration order
+    pub checks: Vec<String>,
+    /// Each check in declaration order
+    pub details: Vec<CheckExplain>,
+    /// Key order settings, when the policy lints order
+    pub order_detail: Option<OrderExplain>,
+    /// Whether the policy lints key order
+    pub order: bool,
+    /// Whether the policy lints formatting
+    pub formatting: bool,
+    /// Why the policy could not be loaded, if it couldn't
+    pub error: Option<String>,
+}
+
+/// One policy check as declared, with its level resolved.
+#[derive(Debug)]
+pub struct CheckExplain {
+    pub kind: String,
+    /// Target fields (`field`, `fields` or both sides of `compare`)
+    pub fields: Vec<String>,
+    pub message: Option<String>,
+    /// Check level, else the policy `defaultLevel`, else `error`
+    pub level: String,
+}
+
+/// A policy's `[order]` section with its level resolved.
+#[derive(Debug)]
+pub struct OrderExplain {
+    pub top: Vec<Vec<String>>,
+    pub message: Option<String>,
+    pub level: String,
+}
+
+/// Load every rule of the index at `repo_root/index_path`, includes resolved.
+pub fn load(
+    repo_root: &Path,
+    index_path: &str,
+    cache_dir: &Path,
+    patterns_override: &HashMap<String, Vec<String>>,
+) -> Result<Vec<RuleExplain>, String> {
+    let idx_path = repo_root.join(index_path);
+    let s = fs::read_to_string(&idx_path).map_err(|e| {
+        format!(
+            "Failed to read index: {} — {}",
+            idx_path.to_string_lossy(),
+            e
+        )
+    })?;
+    let mut index: Index = toml::from_str(&s).map_err(|e| {
+        format!(
+            "Failed to parse index TOML: {} — {}",
+            idx_path.to_string_lossy(),
+            e
+        )
+    })?;
+    index.resolve_includes(&idx_path)?;
+    let conv_cache = crate::conv::cache_root(repo_root, cache_dir);
+    Ok(index
+        .rules
+        .into_iter()
+        .map(|ri| {
+            let policy = crate::conv::resolve_index_ref(&conv_cache, &idx_path, &ri.policy);
+            let parsed = fs::read_to_string(&policy)
+                .map_err(|e| format!("Policy file not found: {}", e))
+                .and_then(|s| Policy::parse(&policy, &s));
+            let patterns = patterns_override
+                .get(&ri.id)
+                .cloned()
+                .unwrap_or(ri.patterns);
+            let mut out = RuleExplain {
+                rule: ri.id,
+                patterns,
+                policy,
+                checks: Vec::new(),
+                details: Vec::new(),
+                order_detail: None,
+                order: false,
+                formatting: false,
+                error: None,
+            };
+            match parsed {
+                Ok(p) => {
+                    out.checks = p.checks.iter().map(|c| c.kind().to_string()).collect();
+                    let default_level = p.default_level.as_deref().unwrap_or("error");
+                    out.details = crate::policy_diff::load(&out.policy)
+                        .ok()
+                        .and_then(|j| j.get("checks").and_then(|c| c.as_array()).cloned())
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|c| check_explain(c, default_level))
+                        .collect();
+                    out.order = p.order.is_some();
+                    out.order_detail = p.order.map(|o| OrderExplain {
+                        top: o.top,
+                        message: o.message,
+                        level: o.level.unwrap_or_else(|| default_level.to_string()),
+                    });
+                    out.formatting = p.formatting.is_some_and(|f| f.check);
+                }
+                Err(e) => out.error = Some(e),
+            }
+            out
+        })
+        .collect())
+}
+
+/// Describe one check from the generic JSON view of its policy.
+fn check_explain(c: &Json, default_level: &str) -> CheckExplain {
+    let text = |k: &str| c.get(k).and_then(|v| v.as_str()).map(String::from);
+    let fields = match (c.get("field"), c.get("fields")) {
+        (Some(Json::String(f)), _) => vec![f.clone()],
+        (_, Some(Json::Array(fs))) => fs
+            .iter()
+            .map(|f| {
+                f.as_str()
+                    .map(String::from)
+                    .unwrap_or_else(|| f.to_string())
+            })
+            .collect(),
+        (_, Some(Json::Object(fs))) => fs.keys().cloned().collect(),
+        _ => ["left", "right"].iter().filter_map(|k| text(k)).collect(),
+    };
+    CheckExplain {
+        kind: text("kind").unwrap_or_default(),
+        fields,
+        message: text("message"),
+        level: text("level").unwrap_or_else(|| default_level.to_string()),
+    }
+}
+
+/// Keep the rules with at least one pattern matching `file`, which may be
+/// absolute or relative to `repo_root`.
+pub fn matching_file(rules: Vec<RuleExplain>, repo_root: &Path, file: &str) -> Vec<RuleExplain> {
+    let rel = relative_to(repo_root, Path::new(file));
+    rules
+        .into_iter()
+        .filter(|r| patterns_match(&r.patterns, &rel))
+        .collect()
+}
+
+/// Whether any of the index `patterns` matches `rel`, a path relative to
+/// the repository root.
+pub fn patterns_match(patterns: &[String], rel: &Path) -> bool {
+    let opts = MatchOptions {
+        require_literal_separator: true,
+        ..MatchOptions::new()
+    };
+    patterns.iter().any(|p| {
+        Pattern::new(p.trim_start_matches("./")).is_ok_and(|pat| pat.matches_path_with(rel, opts))
+    })
+}
+
+/// `file` relative to `root` with `.` components dropped. Absolute paths
+/// are compared after canonicalizing both sides (e.g. symlinked tmp dirs).
+pub fn relative_to(root: &Path, file: &Path) -> PathBuf {
+    let canonical_rel = || {
+        let root = fs::canonicalize(root).ok()?;
+        let file = fs::canonicalize(file).ok()?;
+        file.strip_prefix(&root).ok().map(Path::to_path_buf)
+    };
+    let rel = match file.strip_prefix(root) {
+        Ok(r) => r.to_path_buf(),
+        Err(_) if file.is_absolute() => canonical_rel().unwrap_or_else(|| file.to_path_buf()),
+        Err(_) => file.to_path_buf(),
+    };
+    rel.components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_lists_matching_rules_and_check_kinds() {
+        let td = tempfile::tempdir().unwrap();
+        let d = td.path();
+        fs::create_dir_all(d.join("conv")).unwrap();
+        fs::write(
+            d.join("conv/index.toml"),
+            r#"
+[[rules]]
+id = "pkg"
+patterns = ["package.json", "packages/*/package.json"]
+policy = "pkg.toml"
+
+[[rules]]
+id = "ts"
+patterns = ["tsconfig.json"]
+policy = "ts.toml"
+"#,
+        )
+        .unwrap();
+        fs::write(
+            d.join("conv/pkg.toml"),
+            r#"
+defaultLevel = "warning"
+
+[order]
+top = [["name"]]
+level = "info"
+
+[[checks]]
+kind = "required"
+fields = ["name"]
+
+[[checks]]
+kind = "pattern"
+field = "name"
+regex = "^[a-z-]+$"
+message = "name must be kebab-case"
+level = "error"
+"#,
+        )
+        .unwrap();
+        let rules = load(d, "conv/index.toml", &d.join(".rigra"), &HashMap::new()).unwrap();
+        assert_eq!(rules.len(), 2);
+
+        let hits = matching_file(rules, d, "./package.json");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].rule, "pkg");
+        assert_eq!(hits[0].checks, vec!["required", "pattern"]);
+        assert!(hits[0].order && hits[0].error.is_none());
+        let d0 = &hits[0].details;
+        assert_eq!(
+            (d0[0].fields.clone(), d0[0].level.as_str()),
+            (vec!["name".to_string()], "warning")
+        );
+        assert_eq!(d0[1].message.as_deref(), Some("name must be kebab-case"));
+        assert_eq!(d0[1].level, "error");
+        let ord = hits[0].order_detail.as_ref().unwrap();
+        assert_eq!(
+            (ord.top.clone(), ord.level.as_str()),
+            (vec![vec!["name".to_string()]], "info")
+        );
+
+        let rules = load(d, "conv/index.toml", &d.join(".rigra"), &HashMap::new()).unwrap();
+        let abs = d.join("packages/a/package.json");
+        assert_eq!(matching_file(rules, d, &abs.to_string_lossy()).len(), 1);
+
+        // Overrides replace index patterns; unreadable policies are reported
+        let ov = HashMap::from([("ts".to_string(), vec!["*.json".to_string()])]);
+        let rules = load(d, "conv/index.toml", &d.join(".rigra"), &ov).unwrap();
+        let hits = matching_file(rules, d, "package.json");
+        assert_eq!(hits.len(), 2);
+        assert!(hits[1].error.is_some());
+        // `*` does not cross directories
+        let rules = load(d, "conv/index.toml", &d.join(".rigra"), &ov).unwrap();
+        assert!(matching_file(rules, d, "src/app.json").is_empty());
+    }
+}
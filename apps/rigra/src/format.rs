@@ -1,7 +1,11 @@
 //! JSON formatter for policy-driven ordering and line breaks.
 //!
+//! YAML targets are reordered through the same JSON value model and
+//! re-emitted as block-style YAML.
+//!
 //! This module applies two deterministic passes to JSON objects:
-//! - Key ordering based on the policy's `order.top`/`order.sub`.
+//! - Key ordering based on the policy's `order.top`/`order.sub`; a `"*"`
+//!   entry in `order.top` marks where the remaining keys go.
 //! - Line-break adjustments governed by `linebreak` rules when
 //!   `strictLineBreak` is enabled (config default: true).
 //!
@@ -18,14 +22,17 @@
 //!   no blank line.
 
 use crate::models::index::Index;
-use crate::models::policy::{LineBreakRule, Policy};
-use crate::models::RunError;
+use crate::models::policy::{
+    Collation, LineBreakRule, LineBreakSpec, OrderSpec, Policy, RestOrder,
+};
+use crate::models::{RuleTiming, RunError};
 // colorization handled via utils::error_prefix for errors
 use rayon::prelude::*;
 use serde_json::{Map, Value as Json};
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
+use std::time::Instant;
 
 pub struct FormatResult {
     pub file: String,
@@ -45,11 +52,26 @@ pub struct FormatResult {
 ///   - `in_fields`: preserve/remove blank lines between entries inside specific
 ///     object fields using the original file as reference when `Keep`.
 ///
+/// YAML targets (by extension or the rule's `format`) are parsed into the
+/// same value model, reordered, and re-emitted in block style; line-break
+/// rules do not apply to them, and comments are not preserved. JSON5 and
+/// TOML targets are left untouched.
+///
 /// Returns one `FormatResult` per matched file. When `write` is false and
 /// `capture_old` is true, results include a pretty-printed preview and original.
+/// Files larger than `max_file_bytes` are left untouched with a warning.
+/// With `assume_sorted`, files whose top-level keys already follow the policy
+/// order are reported unchanged without being reserialized.
+/// When `stats` is given, per-rule wall-clock time is appended to it.
+/// With `serial`, files are processed one at a time on the calling thread
+/// instead of in parallel, for reproducing ordering-sensitive bugs.
+/// `cache_dir` locates installed conventions (default `<repo>/.rigra`).
+/// A non-empty `rules` limits the run to those rule ids (`--rule`).
+#[allow(clippy::too_many_arguments)]
 pub fn run_format(
     repo_root: &str,
     index_path: &str,
+    cache_dir: Option<&std::path::Path>,
     write: bool,
     capture_old: bool,
     strict_linebreak: bool,
@@ -57,6 +79,11 @@ pub fn run_format(
     lb_before_fields_override: &std::collections::HashMap<String, String>,
     lb_in_fields_override: &std::collections::HashMap<String, String>,
     patterns_override: &std::collections::HashMap<String, Vec<String>>,
+    max_file_bytes: Option<u64>,
+    assume_sorted: bool,
+    serial: bool,
+    mut stats: Option<&mut Vec<RuleTiming>>,
+    rules: &[String],
 ) -> (Vec<FormatResult>, Vec<RunError>) {
     let root = PathBuf::from(repo_root);
     let idx_path = root.join(index_path);
@@ -65,13 +92,10 @@ pub fn run_format(
         Ok(s) => s,
         Err(e) => {
             eprintln!(
-                "{} {}",
+                "{} Failed to read index: {} — {}. Pass --index or configure rigra.toml.",
                 crate::utils::error_prefix(),
-                format!(
-                    "Failed to read index: {} — {}. Pass --index or configure rigra.toml.",
-                    idx_path.to_string_lossy(),
-                    e
-                )
+                idx_path.to_string_lossy(),
+                e
             );
             errors.push(RunError {
                 message: format!(
@@ -83,17 +107,14 @@ pub fn run_format(
             return (Vec::new(), errors);
         }
     };
-    let index: Index = match toml::from_str(&idx_str) {
+    let mut index: Index = match toml::from_str(&idx_str) {
         Ok(ix) => ix,
         Err(e) => {
             eprintln!(
-                "{} {}",
+                "{} Failed to parse index TOML: {} — {}",
                 crate::utils::error_prefix(),
-                format!(
-                    "Failed to parse index TOML: {} — {}",
-                    idx_path.to_string_lossy(),
-                    e
-                )
+                idx_path.to_string_lossy(),
+                e
             );
             errors.push(RunError {
                 message: format!(
@@ -105,22 +126,28 @@ pub fn run_format(
             return (Vec::new(), errors);
         }
     };
+    if let Err(msg) = index.resolve_includes(&idx_path) {
+        eprintln!("{} {}", crate::utils::error_prefix(), msg);
+        errors.push(RunError { message: msg });
+        return (Vec::new(), errors);
+    }
+    index.retain_rules(rules);
 
     let mut results = Vec::new();
+    let cache_dir = crate::config::cache_dir_or_default(&root, cache_dir);
+    let conv_cache = crate::conv::cache_root(&root, &cache_dir);
     // Cache policies across rules by path to avoid repeated I/O and parse when shared
     let mut policy_cache: HashMap<PathBuf, Policy> = HashMap::new();
     for ri in index.rules {
+        let started = Instant::now();
         // Load policy for this rule to discover per-target ordering rules
-        let pol_path = idx_path
-            .parent()
-            .unwrap_or_else(|| std::path::Path::new("."))
-            .join(&ri.policy);
+        let pol_path = crate::conv::resolve_index_ref(&conv_cache, &idx_path, &ri.policy);
         let policy: Option<&Policy> = if let Some(p) = policy_cache.get(&pol_path) {
             Some(p)
         } else {
             match fs::read_to_string(&pol_path)
                 .ok()
-                .and_then(|s| toml::from_str::<Policy>(&s).ok())
+                .and_then(|s| Policy::parse(&pol_path, &s).ok())
             {
                 Some(p) => {
                     policy_cache.insert(pol_path.clone(), p);
@@ -143,12 +170,11 @@ pub fn run_format(
                 Ok(it) => it,
                 Err(e) => {
                     eprintln!(
-                        "{} {}",
+                        "{} Invalid glob pattern for rule '{}': {} — {}",
                         crate::utils::error_prefix(),
-                        format!(
-                            "Invalid glob pattern for rule '{}': {} — {}",
-                            ri.id, pattern, e
-                        )
+                        ri.id,
+                        pattern,
+                        e
                     );
                     errors.push(RunError {
                         message: format!(
@@ -159,167 +185,568 @@ pub fn run_format(
                     continue;
                 }
             };
-            for entry in itr {
-                if let Ok(path) = entry {
-                    targets.push(path);
-                }
+            for path in itr.flatten() {
+                targets.push(path);
             }
         }
 
         // Process targets in parallel for throughput; gather deterministic order by file path
         let ord_opt = policy.and_then(|p| p.order.as_ref()).cloned();
-        let rule_results: Vec<FormatResult> = targets
-            .par_iter()
-            .map(|path| {
-                let data = match fs::read_to_string(path) {
-                    Ok(s) => s,
-                    Err(_) => {
-                        return FormatResult {
-                            file: path.to_string_lossy().to_string(),
-                            changed: false,
-                            preview: None,
-                            original: None,
-                        }
-                    }
+        let process = |path: &PathBuf| -> FormatResult {
+            if let Some(size) = crate::utils::oversized(path, max_file_bytes) {
+                eprintln!(
+                    "{} Skipping '{}': {} bytes exceeds limits.maxFileBytes ({})",
+                    crate::utils::warn_prefix(),
+                    path.to_string_lossy(),
+                    size,
+                    max_file_bytes.unwrap_or_default()
+                );
+                return FormatResult {
+                    file: path.to_string_lossy().to_string(),
+                    changed: false,
+                    preview: None,
+                    original: None,
                 };
-                let mut json: Json = match serde_json::from_str(&data) {
+            }
+            let data = match fs::read_to_string(path) {
+                Ok(s) => s,
+                Err(_) => {
+                    return FormatResult {
+                        file: path.to_string_lossy().to_string(),
+                        changed: false,
+                        preview: None,
+                        original: None,
+                    }
+                }
+            };
+            // YAML targets share the JSON value model; JSON5/TOML are not rewritten
+            let format = crate::utils::target_format(path, ri.format.as_deref());
+            let parsed: Option<Json> = match format {
+                "json" => serde_json::from_str(&data).ok(),
+                "yaml" => serde_yaml::from_str(&data).ok(),
+                _ => None,
+            };
+            let json: Json = match parsed {
+                Some(v) => v,
+                None => {
+                    return FormatResult {
+                        file: path.to_string_lossy().to_string(),
+                        changed: false,
+                        preview: None,
+                        original: None,
+                    }
+                }
+            };
+            if let Some(ord) = ord_opt.as_ref() {
+                if assume_sorted
+                    && keys_in_order(&json, &ord.top, &ord.sub, ord.collation, ord.rest)
+                    && arrays_sorted_by(&json, &ord.sort_array_by, ord.collation)
+                {
+                    // Fast path: trust the file's layout once its keys are ordered
+                    return FormatResult {
+                        file: path.to_string_lossy().to_string(),
+                        changed: false,
+                        preview: None,
+                        original: if capture_old { Some(data) } else { None },
+                    };
+                }
+                // Apply ordering and line breaks, then compare to original
+                let rendered = render_target(
+                    json,
+                    format,
+                    &data,
+                    ord,
+                    policy.and_then(|p| p.linebreak.as_ref()),
+                    strict_linebreak,
+                    lb_between_groups_override,
+                    lb_before_fields_override,
+                    lb_in_fields_override,
+                );
+                let s = match rendered {
                     Ok(v) => v,
-                    Err(_) => {
-                        return FormatResult {
-                            file: path.to_string_lossy().to_string(),
-                            changed: false,
-                            preview: None,
-                            original: None,
-                        }
+                    Err(e) => {
+                        eprintln!(
+                            "{} Failed to serialize {} for '{}': {} — skipping formatting",
+                            crate::utils::error_prefix(),
+                            format.to_uppercase(),
+                            path.to_string_lossy(),
+                            e
+                        );
+                        data.clone()
                     }
                 };
-                if let Some(ord) = ord_opt.as_ref() {
-                    // Apply ordering (mutates json), then render and compare to original
-                    let _ = apply_order_from(&mut json, &ord.top, &ord.sub);
-                    let mut s = match serde_json::to_string_pretty(&json) {
-                        Ok(v) => v,
-                        Err(e) => {
+                let changed = s.trim_end() != data.trim_end();
+                if write {
+                    if changed {
+                        if let Err(e) = fs::write(path, s.clone()) {
                             eprintln!(
-                                "{} {}",
+                                "{} Failed to write formatted file '{}': {}",
                                 crate::utils::error_prefix(),
-                                format!(
-                                    "Failed to serialize JSON for '{}': {} — skipping formatting",
-                                    path.to_string_lossy(),
-                                    e
-                                )
+                                path.to_string_lossy(),
+                                e
                             );
-                            data.clone()
                         }
-                    };
-                    if strict_linebreak {
-                        let between = lb_between_groups_override
-                            .or(policy
-                                .and_then(|p| p.linebreak.as_ref())
-                                .and_then(|lb| lb.between_groups))
-                            .unwrap_or(false);
-                        let fields = merge_linebreak_fields(
-                            policy
-                                .and_then(|p| p.linebreak.as_ref())
-                                .map(|lb| &lb.before_fields),
-                            lb_before_fields_override,
-                        );
-                        let in_fields = merge_linebreak_fields(
-                            policy
-                                .and_then(|p| p.linebreak.as_ref())
-                                .map(|lb| &lb.in_fields),
-                            lb_in_fields_override,
-                        );
-                        s = apply_linebreaks(s, &ord.top, between, &fields);
-                        let keep_map = compute_in_field_keep_map(&data, &in_fields);
-                        s = apply_in_field_linebreaks(s, &in_fields, &keep_map);
-                    }
-                    let changed = s.trim_end() != data.trim_end();
-                    if write {
-                        if changed {
-                            if let Err(e) = fs::write(path, s.clone()) {
-                                eprintln!(
-                                    "{} {}",
-                                    crate::utils::error_prefix(),
-                                    format!(
-                                        "Failed to write formatted file '{}': {}",
-                                        path.to_string_lossy(),
-                                        e
-                                    )
-                                );
-                            }
-                        }
-                        return FormatResult {
-                            file: path.to_string_lossy().to_string(),
-                            changed,
-                            preview: None,
-                            original: if capture_old { Some(data) } else { None },
-                        };
-                    } else {
-                        return FormatResult {
-                            file: path.to_string_lossy().to_string(),
-                            changed,
-                            preview: if changed { Some(s) } else { None },
-                            original: if capture_old { Some(data) } else { None },
-                        };
                     }
+                    return FormatResult {
+                        file: path.to_string_lossy().to_string(),
+                        changed,
+                        preview: None,
+                        original: if capture_old { Some(data) } else { None },
+                    };
+                } else {
+                    return FormatResult {
+                        file: path.to_string_lossy().to_string(),
+                        changed,
+                        preview: if changed { Some(s) } else { None },
+                        original: if capture_old { Some(data) } else { None },
+                    };
                 }
-                // No order applies
-                FormatResult {
-                    file: path.to_string_lossy().to_string(),
-                    changed: false,
-                    preview: None,
-                    original: if capture_old { Some(data) } else { None },
-                }
-            })
-            .collect();
+            }
+            // No order applies
+            FormatResult {
+                file: path.to_string_lossy().to_string(),
+                changed: false,
+                preview: None,
+                original: if capture_old { Some(data) } else { None },
+            }
+        };
+        let rule_results: Vec<FormatResult> = if serial {
+            targets.iter().map(process).collect()
+        } else {
+            targets.par_iter().map(process).collect()
+        };
 
         let mut rule_results = rule_results;
         rule_results.sort_by(|a, b| a.file.cmp(&b.file));
         results.extend(rule_results);
+        if let Some(s) = stats.as_deref_mut() {
+            s.push(RuleTiming {
+                rule: ri.id.clone(),
+                elapsed: started.elapsed(),
+            });
+        }
     }
     (results, errors)
 }
 
-/// Reorder an object according to top-level groups and sub-field orders.
+/// Top-level keys of `r` as `rigra format` would leave them: from the
+/// preview when the file changes, else from the original (`capture_old`).
+/// `None` for skipped files and non-object documents.
+pub fn emitted_order(r: &FormatResult) -> Option<Vec<String>> {
+    let text = r.preview.as_deref().or(r.original.as_deref())?;
+    let json: Json = serde_json::from_str(text)
+        .ok()
+        .or_else(|| serde_yaml::from_str(text).ok())?;
+    json.as_object().map(|o| o.keys().cloned().collect())
+}
+
+/// Format one buffer (e.g. piped from an editor) as `run_format` would
+/// format a file on disk, without reading targets or writing anything.
+///
+/// The rule is the first whose patterns match `filename` (relative to
+/// `repo_root` or absolute); without `filename` the index must have exactly
+/// one rule. Input that no rule or order applies to is passed through
+/// unchanged. The result's `preview` always holds the output text.
+#[allow(clippy::too_many_arguments)]
+pub fn format_stdin(
+    repo_root: &str,
+    index_path: &str,
+    cache_dir: Option<&std::path::Path>,
+    filename: Option<&str>,
+    data: &str,
+    strict_linebreak: bool,
+    lb_between_groups_override: Option<bool>,
+    lb_before_fields_override: &HashMap<String, String>,
+    lb_in_fields_override: &HashMap<String, String>,
+    patterns_override: &HashMap<String, Vec<String>>,
+) -> Result<FormatResult, String> {
+    let root = PathBuf::from(repo_root);
+    let idx_path = root.join(index_path);
+    let idx_str = fs::read_to_string(&idx_path).map_err(|e| {
+        format!(
+            "Failed to read index: {} — {}",
+            idx_path.to_string_lossy(),
+            e
+        )
+    })?;
+    let mut index: Index = toml::from_str(&idx_str).map_err(|e| {
+        format!(
+            "Failed to parse index TOML: {} — {}",
+            idx_path.to_string_lossy(),
+            e
+        )
+    })?;
+    index.resolve_includes(&idx_path)?;
+    let unchanged = || FormatResult {
+        file: filename.unwrap_or("<stdin>").to_string(),
+        changed: false,
+        preview: Some(data.to_string()),
+        original: Some(data.to_string()),
+    };
+    let rule = match filename {
+        Some(f) => {
+            let rel = crate::explain::relative_to(&root, std::path::Path::new(f));
+            index.rules.into_iter().find(|ri| {
+                let patterns = patterns_override.get(&ri.id).unwrap_or(&ri.patterns);
+                crate::explain::patterns_match(patterns, &rel)
+            })
+        }
+        None if index.rules.len() == 1 => index.rules.pop(),
+        None => {
+            return Err(format!(
+                "Pass --stdin-filename to pick a rule: the index has {} rules",
+                index.rules.len()
+            ))
+        }
+    };
+    let Some(ri) = rule else {
+        eprintln!(
+            "{} No rule matches '{}'; passing input through unchanged",
+            crate::utils::note_prefix(),
+            filename.unwrap_or_default()
+        );
+        return Ok(unchanged());
+    };
+    let cache_dir = crate::config::cache_dir_or_default(&root, cache_dir);
+    let conv_cache = crate::conv::cache_root(&root, &cache_dir);
+    let pol_path = crate::conv::resolve_index_ref(&conv_cache, &idx_path, &ri.policy);
+    let policy = fs::read_to_string(&pol_path)
+        .map_err(|e| format!("Policy file not found: {}", e))
+        .and_then(|s| Policy::parse(&pol_path, &s))?;
+    let Some(ord) = policy.order.as_ref() else {
+        return Ok(unchanged());
+    };
+    let format = crate::utils::target_format(
+        std::path::Path::new(filename.unwrap_or("")),
+        ri.format.as_deref(),
+    );
+    let json: Json = match format {
+        "json" => serde_json::from_str(data).map_err(|e| e.to_string()),
+        "yaml" => serde_yaml::from_str(data).map_err(|e| e.to_string()),
+        _ => return Ok(unchanged()),
+    }
+    .map_err(|e| format!("Failed to parse stdin as {}: {}", format.to_uppercase(), e))?;
+    let s = render_target(
+        json,
+        format,
+        data,
+        ord,
+        policy.linebreak.as_ref(),
+        strict_linebreak,
+        lb_between_groups_override,
+        lb_before_fields_override,
+        lb_in_fields_override,
+    )?;
+    Ok(FormatResult {
+        file: filename.unwrap_or("<stdin>").to_string(),
+        changed: s.trim_end() != data.trim_end(),
+        preview: Some(s),
+        original: Some(data.to_string()),
+    })
+}
+
+/// Reorder a parsed target per `ord` and render it as `run_format` writes
+/// it: block YAML, or pretty JSON with the line-break passes applied when
+/// `strict_linebreak` is set. `original` is the target's source text.
+#[allow(clippy::too_many_arguments)]
+fn render_target(
+    mut json: Json,
+    format: &str,
+    original: &str,
+    ord: &OrderSpec,
+    linebreak: Option<&LineBreakSpec>,
+    strict_linebreak: bool,
+    lb_between_groups_override: Option<bool>,
+    lb_before_fields_override: &HashMap<String, String>,
+    lb_in_fields_override: &HashMap<String, String>,
+) -> Result<String, String> {
+    apply_order_from(&mut json, &ord.top, &ord.sub, ord.collation, ord.rest);
+    sort_arrays_by(&mut json, &ord.sort_array_by, ord.collation);
+    if format == "yaml" {
+        return serde_yaml::to_string(&json).map_err(|e| e.to_string());
+    }
+    let s = serde_json::to_string_pretty(&json).map_err(|e| e.to_string())?;
+    // Line-break rules address JSON text; YAML keeps the emitter's layout
+    if !strict_linebreak {
+        return Ok(s);
+    }
+    let groups = linebreak_groups(&json, &ord.top, ord.collation, ord.rest);
+    Ok(apply_policy_linebreaks(
+        s,
+        original,
+        &groups,
+        linebreak,
+        lb_between_groups_override,
+        lb_before_fields_override,
+        lb_in_fields_override,
+    ))
+}
+
+/// Render `json` the way `rigra format` would under `policy` alone: ordering,
+/// pretty-printing, and strict line breaks without rigra.toml overrides.
 ///
-/// Returns true if the order changed. Remaining keys not listed in `top` or
-/// `sub` are appended in lexicographic order for determinism.
+/// Used by lint's `formatting` precondition; `original` is the source text
+/// consulted for `keep` in-field line breaks.
+pub fn render_for_policy(original: &str, json: &Json, policy: &Policy) -> Option<String> {
+    let mut json = json.clone();
+    let top: Vec<Vec<String>> = match policy.order.as_ref() {
+        Some(ord) => {
+            apply_order_from(&mut json, &ord.top, &ord.sub, ord.collation, ord.rest);
+            sort_arrays_by(&mut json, &ord.sort_array_by, ord.collation);
+            linebreak_groups(&json, &ord.top, ord.collation, ord.rest)
+        }
+        None => Vec::new(),
+    };
+    let pretty = serde_json::to_string_pretty(&json).ok()?;
+    Some(apply_policy_linebreaks(
+        pretty,
+        original,
+        &top,
+        policy.linebreak.as_ref(),
+        None,
+        &HashMap::new(),
+        &HashMap::new(),
+    ))
+}
+
+/// Apply the policy `linebreak` rules, merged with runtime overrides, to
+/// pretty-printed output.
+fn apply_policy_linebreaks(
+    pretty: String,
+    original: &str,
+    top: &[Vec<String>],
+    linebreak: Option<&LineBreakSpec>,
+    between_override: Option<bool>,
+    before_fields_override: &HashMap<String, String>,
+    in_fields_override: &HashMap<String, String>,
+) -> String {
+    let between = between_override
+        .or(linebreak.and_then(|lb| lb.between_groups))
+        .unwrap_or(false);
+    let fields = merge_linebreak_fields(
+        linebreak.map(|lb| &lb.before_fields),
+        before_fields_override,
+    );
+    let in_fields = merge_linebreak_fields(linebreak.map(|lb| &lb.in_fields), in_fields_override);
+    let s = apply_linebreaks(pretty, top, between, &fields);
+    let keep_map = compute_in_field_keep_map(original, &in_fields);
+    apply_in_field_linebreaks(s, &in_fields, &keep_map)
+}
+
+/// Entry in an `order.top` group (or a `sub` list) that stands for every
+/// key not listed elsewhere.
+pub const ORDER_WILDCARD: &str = "*";
+
+/// Keys of `obj` that `top` does not place, sorted by `collation` (or as
+/// found, with `RestOrder::Preserve`). They go where the wildcard sits, or
+/// after the last group when there is none.
+fn remaining_keys(
+    obj: &Map<String, Json>,
+    top: &[Vec<String>],
+    collation: Collation,
+    rest: RestOrder,
+) -> Vec<String> {
+    let in_top: HashSet<&str> = top.iter().flatten().map(String::as_str).collect();
+    let mut unlisted: Vec<&str> = obj
+        .keys()
+        .map(String::as_str)
+        .filter(|k| !in_top.contains(k))
+        .collect();
+    if rest == RestOrder::Sort {
+        collation.sort(&mut unlisted);
+    }
+    unlisted.into_iter().map(String::from).collect()
+}
+
+/// Key order `rigra format` gives `obj`: the `top` groups in order, with the
+/// remaining keys at the wildcard (or at the end).
+pub(crate) fn ordered_keys(
+    obj: &Map<String, Json>,
+    top: &[Vec<String>],
+    collation: Collation,
+    rest: RestOrder,
+) -> Vec<String> {
+    let mut remaining = Some(remaining_keys(obj, top, collation, rest));
+    let mut placed: HashSet<&str> = HashSet::new();
+    let mut out: Vec<String> = Vec::with_capacity(obj.len());
+    for key in top.iter().flatten() {
+        if key == ORDER_WILDCARD {
+            out.extend(remaining.take().unwrap_or_default());
+        } else if obj.contains_key(key) && placed.insert(key.as_str()) {
+            out.push(key.clone());
+        }
+    }
+    out.extend(remaining.unwrap_or_default());
+    out
+}
+
+/// `top` as line-break groups for `json`: a wildcard entry is replaced by
+/// the keys it stands for, so the blank line lands before the first of them.
+fn linebreak_groups(
+    json: &Json,
+    top: &[Vec<String>],
+    collation: Collation,
+    rest: RestOrder,
+) -> Vec<Vec<String>> {
+    let wildcard = top.iter().flatten().any(|k| k == ORDER_WILDCARD);
+    let (Json::Object(obj), true) = (json, wildcard) else {
+        return top.to_vec();
+    };
+    let mut remaining = Some(remaining_keys(obj, top, collation, rest));
+    top.iter()
+        .map(|group| {
+            group
+                .iter()
+                .flat_map(|k| match k.as_str() {
+                    ORDER_WILDCARD => remaining.take().unwrap_or_default(),
+                    _ => vec![k.clone()],
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Reorder the keys of `obj` as `ordered_keys` would; true if they moved.
+fn reorder_object(
+    obj: &mut Map<String, Json>,
+    top: &[Vec<String>],
+    collation: Collation,
+    rest: RestOrder,
+) -> bool {
+    let order = ordered_keys(obj, top, collation, rest);
+    if obj.keys().eq(order.iter()) {
+        return false;
+    }
+    let mut new_obj = Map::new();
+    for key in order {
+        if let Some(v) = obj.remove(&key) {
+            new_obj.insert(key, v);
+        }
+    }
+    *obj = new_obj;
+    true
+}
+
+/// `sub` entries sorted by path, so nested orders apply and report
+/// deterministically.
+fn sorted_sub(sub: &HashMap<String, Vec<String>>) -> Vec<(&String, &Vec<String>)> {
+    let mut entries: Vec<_> = sub.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries
+}
+
+/// Reorder an object according to top-level groups and nested sub orders.
+///
+/// Returns true if the order changed. Keys not listed in `top` are placed
+/// at its `"*"` wildcard entry, or appended, sorted by `collation` for
+/// determinism unless `rest` preserves their order. Each `sub` entry maps a
+/// path (`repository`, `jest.coverageThreshold`) to the order of that
+/// object's own keys, with the same rules for the keys it does not list;
+/// paths that do not resolve to an object are skipped.
 fn apply_order_from(
     json: &mut Json,
-    top: &Vec<Vec<String>>,
-    sub: &std::collections::HashMap<String, Vec<String>>,
+    top: &[Vec<String>],
+    sub: &HashMap<String, Vec<String>>,
+    collation: Collation,
+    rest: RestOrder,
 ) -> bool {
-    let mut changed = false;
-    if let Json::Object(obj) = json {
-        let mut new_obj = Map::new();
-        for keys in top.iter() {
-            for key in keys {
-                if let Some(v) = obj.remove(key) {
-                    new_obj.insert(key.clone(), v);
-                    changed = true;
-                }
-            }
+    let Json::Object(obj) = json else {
+        return false;
+    };
+    let mut changed = reorder_object(obj, top, collation, rest);
+    for (path, keys) in sorted_sub(sub) {
+        if let Some(Json::Object(nested)) = crate::utils::get_json_path_mut(json, path) {
+            changed |= reorder_object(nested, std::slice::from_ref(keys), collation, rest);
         }
-        for keys in sub.values() {
-            for key in keys {
-                if let Some(v) = obj.remove(key) {
-                    new_obj.insert(key.clone(), v);
-                    changed = true;
-                }
-            }
+    }
+    changed
+}
+
+/// Paths (`$.repository`) of `sub` objects in `json` whose keys do not
+/// follow their listed order, sorted.
+pub(crate) fn sub_order_mismatches(
+    json: &Json,
+    sub: &HashMap<String, Vec<String>>,
+    collation: Collation,
+    rest: RestOrder,
+) -> Vec<String> {
+    sorted_sub(sub)
+        .into_iter()
+        .filter_map(|(path, keys)| {
+            let Some(Json::Object(obj)) = crate::utils::get_json_path(json, path) else {
+                return None;
+            };
+            let expected = ordered_keys(obj, std::slice::from_ref(keys), collation, rest);
+            (!obj.keys().eq(expected.iter()))
+                .then(|| format!("$.{}", path.trim_start_matches('$').trim_start_matches('.')))
+        })
+        .collect()
+}
+
+/// Whether an object's keys, and those of its `sub` objects, already follow
+/// the order `apply_order_from` would produce. Compares key names only; no
+/// values are moved.
+fn keys_in_order(
+    json: &Json,
+    top: &[Vec<String>],
+    sub: &HashMap<String, Vec<String>>,
+    collation: Collation,
+    rest: RestOrder,
+) -> bool {
+    let Json::Object(obj) = json else {
+        return true;
+    };
+    obj.keys()
+        .eq(ordered_keys(obj, top, collation, rest).iter())
+        && sub_order_mismatches(json, sub, collation, rest).is_empty()
+}
+
+/// `items` stably sorted by the string value of `field`, compared by
+/// `collation`; elements lacking a string `field` keep their relative order
+/// after the rest.
+fn sorted_by_field(items: &[Json], field: &str, collation: Collation) -> Vec<Json> {
+    let mut out = items.to_vec();
+    out.sort_by(|a, b| {
+        match (
+            a.get(field).and_then(Json::as_str),
+            b.get(field).and_then(Json::as_str),
+        ) {
+            (Some(x), Some(y)) => collation.cmp(x, y),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
         }
-        let mut rest: Vec<_> = obj.iter().map(|(k, _)| k.clone()).collect();
-        rest.sort();
-        for key in rest {
-            if let Some(v) = obj.remove(&key) {
-                new_obj.insert(key.clone(), v);
+    });
+    out
+}
+
+/// Apply `order.sortArrayBy`: sort each array found at a path by its
+/// elements' field. Paths that do not resolve to an array are skipped.
+/// Returns true if any array changed.
+fn sort_arrays_by(json: &mut Json, by: &HashMap<String, String>, collation: Collation) -> bool {
+    let mut changed = false;
+    for (path, field) in by {
+        if let Some(Json::Array(items)) = crate::utils::get_json_path_mut(json, path) {
+            let sorted = sorted_by_field(items, field, collation);
+            if *items != sorted {
+                *items = sorted;
+                changed = true;
             }
         }
-        *obj = new_obj;
     }
     changed
 }
 
+/// Whether every `order.sortArrayBy` array is already sorted.
+fn arrays_sorted_by(json: &Json, by: &HashMap<String, String>, collation: Collation) -> bool {
+    by.iter().all(
+        |(path, field)| match crate::utils::get_json_path(json, path) {
+            Some(Json::Array(items)) => *items == sorted_by_field(items, field, collation),
+            _ => true,
+        },
+    )
+}
+
 /// Merge policy-provided field rules with CLI/config overrides.
 ///
 /// Override values accept `"keep"` or anything else treated as `None`.
@@ -338,8 +765,9 @@ fn merge_linebreak_fields(
     out
 }
 
-/// Scan the original source to determine which child keys had a blank
-/// line before them inside objects configured with `Keep`.
+/// Scan the original source to determine which direct child keys had a blank
+/// line before them inside objects configured with `Keep`. Keys and blank
+/// lines deeper inside a child's own value are not attributed to the field.
 ///
 /// Returns a map `field -> {child keys}` used to reinsert single blank
 /// lines in the pretty-printed output.
@@ -367,37 +795,26 @@ fn compute_in_field_keep_map(
     let mut prev_blank = false;
     for line in original.lines() {
         let trimmed = line.trim_start();
-        if active.is_none() && trimmed.starts_with('"') {
-            if let Some(p1) = trimmed.find('"') {
-                let rest = &trimmed[p1 + 1..];
-                if let Some(p2) = rest.find('"') {
-                    let key = &rest[..p2];
-                    if targets.contains(&key.to_string()) && trimmed.contains(": {") {
-                        active = Some(key.to_string());
-                        depth = 0;
-                        prev_blank = false;
-                    }
+        if active.is_none() {
+            if let Some((key, value)) = split_entry(trimmed) {
+                if targets.contains(&key.to_string()) && value.starts_with('{') {
+                    active = Some(key.to_string());
+                    depth = 0;
+                    prev_blank = false;
                 }
             }
         }
         if let Some(ref fld) = active {
-            for ch in trimmed.chars() {
-                if ch == '{' {
-                    depth += 1;
-                } else if ch == '}' {
-                    depth -= 1;
-                }
-            }
-            if depth == 1 && trimmed.starts_with('"') && !trimmed.contains("\": {") {
-                if prev_blank {
-                    // record child key for which a blank line preceded it in the original
-                    if let Some(p1) = trimmed.find('"') {
-                        let rest = &trimmed[p1 + 1..];
-                        if let Some(p2) = rest.find('"') {
-                            let child = rest[..p2].to_string();
-                            result.entry(fld.clone()).or_default().insert(child);
-                        }
-                    }
+            // Direct children start at depth 1, whatever their value spans
+            let line_depth = depth;
+            depth += brace_delta(trimmed);
+            if line_depth == 1 && prev_blank {
+                // record child key for which a blank line preceded it in the original
+                if let Some((child, _)) = split_entry(trimmed) {
+                    result
+                        .entry(fld.clone())
+                        .or_default()
+                        .insert(child.to_string());
                 }
             }
             if depth <= 0 && trimmed.contains('}') {
@@ -418,7 +835,7 @@ fn compute_in_field_keep_map(
 ///   when it is the first key of a subsequent group.
 fn apply_linebreaks(
     pretty: String,
-    groups: &Vec<Vec<String>>,
+    groups: &[Vec<String>],
     between_groups: bool,
     field_rules: &std::collections::HashMap<String, LineBreakRule>,
 ) -> String {
@@ -436,51 +853,23 @@ fn apply_linebreaks(
     let mut depth: i32 = 0; // track object depth; top-level keys at depth==1
     for line in pretty.lines() {
         let trimmed = line.trim_start();
-        if depth == 1 && trimmed.starts_with('"') {
-            if let Some(pos) = trimmed.find('"') {
-                let rest = &trimmed[pos + 1..];
-                if let Some(end) = rest.find('"') {
-                    let key = &rest[..end];
-                    if group_first_keys.contains(key) {
-                        if seen_first {
-                            match field_rules.get(key).copied() {
-                                Some(LineBreakRule::None) => {
-                                    if let Some(last) = out.last() {
-                                        if last.is_empty() {
-                                            out.pop();
-                                        }
-                                    }
-                                }
-                                Some(LineBreakRule::Keep) | None => {
-                                    // Ensure exactly one blank line before group-first key
-                                    if let Some(last) = out.last() {
-                                        if last.is_empty() {
-                                            // already one blank; if there are multiple, collapse to one
-                                            if out.len() >= 2 && out[out.len() - 2].is_empty() {
-                                                out.pop();
-                                            }
-                                        } else {
-                                            out.push(String::new());
-                                        }
-                                    }
-                                }
-                            }
-                        } else {
-                            seen_first = true;
+        if depth == 1 {
+            if let Some((key, _)) = split_entry(trimmed) {
+                if group_first_keys.contains(key) {
+                    if seen_first {
+                        match field_rules.get(key).copied() {
+                            Some(LineBreakRule::None) => strip_blank_lines(&mut out),
+                            Some(LineBreakRule::Keep) | None => single_blank_line(&mut out),
                         }
+                    } else {
+                        seen_first = true;
                     }
                 }
             }
         }
         out.push(line.to_string());
         // update depth after processing current line
-        for ch in trimmed.chars() {
-            if ch == '{' {
-                depth += 1;
-            } else if ch == '}' {
-                depth -= 1;
-            }
-        }
+        depth += brace_delta(trimmed);
     }
     out.join("\n")
 }
@@ -504,29 +893,21 @@ fn apply_in_field_linebreaks(
     for line in pretty.lines() {
         let trimmed = line.trim_start();
 
-        if active_field.is_none() && trimmed.starts_with('"') {
-            if let Some(pos) = trimmed.find('"') {
-                let rest = &trimmed[pos + 1..];
-                if let Some(end) = rest.find('"') {
-                    let key = &rest[..end];
-                    if in_field_rules.contains_key(key) && trimmed.contains(": {") {
-                        active_field = Some((key.to_string(), false));
-                        brace_depth = 0;
-                    }
+        if active_field.is_none() {
+            if let Some((key, value)) = split_entry(trimmed) {
+                if in_field_rules.contains_key(key) && value.starts_with('{') {
+                    active_field = Some((key.to_string(), false));
+                    brace_depth = 0;
                 }
             }
         }
 
+        let entry = split_entry(trimmed);
         if let Some((ref fld, ref mut seen_first)) = active_field {
-            // Update depth with this line's braces
-            for ch in trimmed.chars() {
-                if ch == '{' {
-                    brace_depth += 1;
-                } else if ch == '}' {
-                    brace_depth -= 1;
-                }
-            }
-            if brace_depth == 1 && trimmed.starts_with('"') && !trimmed.contains("\": {") {
+            // Update depth with this line's braces; direct children start at 1
+            let line_depth = brace_depth;
+            brace_depth += brace_delta(trimmed);
+            if line_depth == 1 && entry.is_some() {
                 if !*seen_first {
                     // first entry: just mark seen, no blank line
                     *seen_first = true;
@@ -535,47 +916,19 @@ fn apply_in_field_linebreaks(
                         .get(fld)
                         .copied()
                         .unwrap_or(LineBreakRule::Keep);
-                    // Determine current child key
-                    let mut child_key: Option<String> = None;
-                    if let Some(p1) = trimmed.find('"') {
-                        let rest = &trimmed[p1 + 1..];
-                        if let Some(p2) = rest.find('"') {
-                            child_key = Some(rest[..p2].to_string());
-                        }
-                    }
+                    let child_key = entry.map(|(key, _)| key);
                     match rule {
                         LineBreakRule::Keep => {
                             let should_have_blank = child_key
-                                .as_ref()
                                 .and_then(|ck| keep_map.get(fld).map(|set| set.contains(ck)))
                                 .unwrap_or(false);
                             if should_have_blank {
-                                // ensure exactly one blank line
-                                if let Some(last) = out.last() {
-                                    if last.is_empty() {
-                                        if out.len() >= 2 && out[out.len() - 2].is_empty() {
-                                            out.pop();
-                                        }
-                                    } else {
-                                        out.push(String::new());
-                                    }
-                                }
+                                single_blank_line(&mut out);
                             } else {
-                                // ensure none
-                                if let Some(last) = out.last() {
-                                    if last.is_empty() {
-                                        out.pop();
-                                    }
-                                }
-                            }
-                        }
-                        LineBreakRule::None => {
-                            if let Some(last) = out.last() {
-                                if last.is_empty() {
-                                    out.pop();
-                                }
+                                strip_blank_lines(&mut out);
                             }
                         }
+                        LineBreakRule::None => strip_blank_lines(&mut out),
                     }
                 }
             }
@@ -587,15 +940,80 @@ fn apply_in_field_linebreaks(
 
         out.push(line.to_string());
 
-        if let Some(_) = active_field.as_ref() {
-            if brace_depth <= 0 && (trimmed == "}" || trimmed == "}," || trimmed.ends_with('}')) {
-                active_field = None;
-            }
+        if active_field.as_ref().is_some()
+            && brace_depth <= 0
+            && (trimmed == "}" || trimmed == "}," || trimmed.ends_with('}'))
+        {
+            active_field = None;
         }
     }
     out.join("\n")
 }
 
+/// Net change in object depth across `line`. Braces inside string literals
+/// (including escaped quotes) are not counted.
+fn brace_delta(line: &str) -> i32 {
+    let mut delta = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for ch in line.chars() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if ch == '\\' {
+                escaped = true;
+            } else if ch == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match ch {
+            '"' => in_string = true,
+            '{' => delta += 1,
+            '}' => delta -= 1,
+            _ => {}
+        }
+    }
+    delta
+}
+
+/// Split a `"key": value` line into its raw key and the value text after the
+/// colon. Returns `None` for lines that are not object entries, such as
+/// string elements of an array.
+fn split_entry(trimmed: &str) -> Option<(&str, &str)> {
+    let body = trimmed.strip_prefix('"')?;
+    let mut escaped = false;
+    for (i, ch) in body.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if ch == '\\' {
+            escaped = true;
+        } else if ch == '"' {
+            let value = body[i + 1..].trim_start().strip_prefix(':')?;
+            return Some((&body[..i], value.trim_start()));
+        }
+    }
+    None
+}
+
+/// Drop every blank line at the end of `out`.
+fn strip_blank_lines(out: &mut Vec<String>) {
+    while out.last().is_some_and(|l| l.is_empty()) {
+        out.pop();
+    }
+}
+
+/// Leave exactly one blank line at the end of `out`, however many were there.
+///
+/// Both line-break passes settle on this state (or on none) so that running
+/// them again over their own output changes nothing.
+fn single_blank_line(out: &mut Vec<String>) {
+    strip_blank_lines(out);
+    if !out.is_empty() {
+        out.push(String::new());
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -604,26 +1022,215 @@ mod tests {
     use std::collections::{HashMap, HashSet};
 
     #[test]
-    fn test_apply_order_top_then_sub_then_rest() {
+    fn test_apply_order_top_then_rest_then_nested_sub() {
         let mut json = json!({
             "z": 1,
-            "b": 2,
+            "repository": {"url": "u", "type": "git", "directory": "d"},
             "a": 3,
             "name": "n",
+            "jest": {"coverageThreshold": {"lines": 1, "global": 2, "branches": 3}},
             "version": "v"
         });
         let mut sub = HashMap::new();
-        sub.insert("meta".to_string(), vec!["version".to_string()]);
+        sub.insert("repository".to_string(), vec!["type".to_string()]);
+        sub.insert(
+            "$.jest.coverageThreshold".to_string(),
+            vec!["global".to_string()],
+        );
+        // Not an object: skipped
+        sub.insert("version".to_string(), vec!["x".to_string()]);
         let order = OrderSpec {
             top: vec![vec!["name".into()]],
             sub,
             message: None,
             level: None,
+            collation: Collation::Byte,
+            rest: RestOrder::Sort,
+            sort_array_by: HashMap::new(),
         };
-        let changed = apply_order_from(&mut json, &order.top, &order.sub);
+        let mismatches = sub_order_mismatches(&json, &order.sub, order.collation, order.rest);
+        assert_eq!(mismatches, vec!["$.jest.coverageThreshold", "$.repository"]);
+        let changed = apply_order_from(
+            &mut json,
+            &order.top,
+            &order.sub,
+            order.collation,
+            order.rest,
+        );
         assert!(changed);
         let keys: Vec<_> = json.as_object().unwrap().keys().cloned().collect();
-        assert_eq!(keys, vec!["name", "version", "a", "b", "z"]);
+        assert_eq!(
+            keys,
+            vec!["name", "a", "jest", "repository", "version", "z"]
+        );
+        let repo: Vec<_> = json["repository"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
+        assert_eq!(repo, vec!["type", "directory", "url"]);
+        let cov: Vec<_> = json["jest"]["coverageThreshold"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
+        assert_eq!(cov, vec!["global", "branches", "lines"]);
+        assert!(keys_in_order(
+            &json,
+            &order.top,
+            &order.sub,
+            order.collation,
+            order.rest
+        ));
+    }
+
+    #[test]
+    fn test_sort_array_by_field_puts_missing_last() {
+        let mut json = json!({
+            "contributors": [
+                {"name": "zoe"},
+                {"email": "x@y"},
+                {"name": "adam", "email": "a@b"},
+                {"name": 3},
+                {"name": "mia"}
+            ],
+            "keywords": "not-an-array"
+        });
+        let mut by = HashMap::new();
+        by.insert("$.contributors".to_string(), "name".to_string());
+        by.insert("keywords".to_string(), "name".to_string());
+        by.insert("missing".to_string(), "name".to_string());
+        assert!(!arrays_sorted_by(&json, &by, Collation::Byte));
+        assert!(sort_arrays_by(&mut json, &by, Collation::Byte));
+        assert_eq!(
+            json["contributors"],
+            json!([
+                {"name": "adam", "email": "a@b"},
+                {"name": "mia"},
+                {"name": "zoe"},
+                {"email": "x@y"},
+                {"name": 3}
+            ])
+        );
+        assert_eq!(json["keywords"], "not-an-array");
+        assert!(arrays_sorted_by(&json, &by, Collation::Byte));
+        assert!(!sort_arrays_by(&mut json, &by, Collation::Byte));
+    }
+
+    #[test]
+    fn test_rest_keys_byte_vs_unicode_collation() {
+        let src = json!({"zeta": 1, "Émile": 2, "apple": 3, "éclair": 4, "Alpha": 5, "eagle": 6});
+        let sorted = |collation: Collation| {
+            let mut json = src.clone();
+            apply_order_from(&mut json, &[], &HashMap::new(), collation, RestOrder::Sort);
+            assert!(keys_in_order(
+                &json,
+                &[],
+                &HashMap::new(),
+                collation,
+                RestOrder::Sort
+            ));
+            json.as_object()
+                .unwrap()
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            sorted(Collation::Byte),
+            vec!["Alpha", "apple", "eagle", "zeta", "Émile", "éclair"]
+        );
+        assert_eq!(
+            sorted(Collation::Unicode),
+            vec!["Alpha", "apple", "eagle", "éclair", "Émile", "zeta"]
+        );
+        // Ties on the folded key fall back to accents, then case
+        let mut keys = vec!["Resume", "résumé", "resume"];
+        Collation::Unicode.sort(&mut keys);
+        assert_eq!(keys, vec!["Resume", "resume", "résumé"]);
+    }
+
+    #[test]
+    fn test_rest_preserve_keeps_unlisted_keys_in_place() {
+        let mut json = json!({
+            "zeta": 1,
+            "meta": {"b": 1, "version": "v", "a": 2},
+            "apple": 2,
+            "name": "n",
+            "mid": 3
+        });
+        let top = vec![vec!["name".to_string()], vec!["*".to_string()]];
+        let mut sub = HashMap::new();
+        sub.insert("meta".to_string(), vec!["version".to_string()]);
+        let changed = apply_order_from(&mut json, &top, &sub, Collation::Byte, RestOrder::Preserve);
+        assert!(changed);
+        let keys: Vec<_> = json.as_object().unwrap().keys().cloned().collect();
+        assert_eq!(keys, vec!["name", "zeta", "meta", "apple", "mid"]);
+        let meta: Vec<_> = json["meta"].as_object().unwrap().keys().cloned().collect();
+        assert_eq!(meta, vec!["version", "b", "a"]);
+        assert!(keys_in_order(
+            &json,
+            &top,
+            &sub,
+            Collation::Byte,
+            RestOrder::Preserve
+        ));
+        assert!(!keys_in_order(
+            &json,
+            &top,
+            &sub,
+            Collation::Byte,
+            RestOrder::Sort
+        ));
+    }
+
+    #[test]
+    fn test_wildcard_groups_unlisted_keys_with_blank_lines() {
+        let policy: Policy = toml::from_str(
+            r#"
+[order]
+top = [["name", "version"], ["*"], ["scripts"]]
+
+[linebreak]
+between_groups = true
+"#,
+        )
+        .unwrap();
+        let json = json!({
+            "scripts": {},
+            "license": "MIT",
+            "version": "1.0.0",
+            "author": "a",
+            "name": "x"
+        });
+        let out = render_for_policy("", &json, &policy).unwrap();
+        assert_eq!(
+            out,
+            "{\n  \"name\": \"x\",\n  \"version\": \"1.0.0\",\n\n  \"author\": \"a\",\n  \"license\": \"MIT\",\n\n  \"scripts\": {}\n}"
+        );
+        let ord = policy.order.as_ref().unwrap();
+        let sorted: Json = serde_json::from_str(&out).unwrap();
+        assert!(keys_in_order(
+            &sorted,
+            &ord.top,
+            &ord.sub,
+            ord.collation,
+            ord.rest
+        ));
+        assert!(!keys_in_order(
+            &json,
+            &ord.top,
+            &ord.sub,
+            ord.collation,
+            ord.rest
+        ));
+
+        // No unlisted keys: the wildcard group is empty and adds no blank line
+        let pinned = json!({"scripts": {}, "name": "x"});
+        let out = render_for_policy("", &pinned, &policy).unwrap();
+        assert_eq!(out, "{\n  \"name\": \"x\",\n\n  \"scripts\": {}\n}");
     }
 
     #[test]
@@ -709,4 +1316,144 @@ mod tests {
         let out = apply_in_field_linebreaks(pretty, &rules, &keep_map);
         assert!(out.contains("\"build\": \"echo build\",\n\n    \"test\""));
     }
+
+    #[test]
+    fn test_linebreak_passes_are_idempotent() {
+        let policy: Policy = toml::from_str(
+            r#"
+[order]
+top = [["name", "version"], ["scripts", "test"], ["*"]]
+
+[linebreak]
+between_groups = true
+before_fields = { version = "keep", test = "none" }
+in_fields = { scripts = "keep", config = "none" }
+"#,
+        )
+        .unwrap();
+        let inputs = [
+            // Blank lines inside a keep field, some of them repeated
+            "{\n  \"scripts\": {\n    \"build\": \"b\",\n\n\n    \"test\": \"t\",\n\n    \"lint\": \"l\"\n  },\n  \"name\": \"x\",\n  \"version\": \"1\"\n}",
+            // `test` is both a group member at the top and a child of scripts
+            "{\n  \"test\": true,\n  \"name\": \"x\",\n  \"scripts\": {\n    \"name\": \"n\",\n\n    \"test\": \"t\"\n  }\n}",
+            // A nested object reusing an in-field name, and a none field
+            "{\n  \"config\": {\n    \"a\": 1,\n\n    \"b\": 2\n  },\n  \"pkg\": {\n    \"scripts\": {\n      \"x\": 1,\n\n      \"y\": 2\n    }\n  },\n  \"scripts\": {}\n}",
+            // Already compact input with no blank lines at all
+            "{\"version\":\"1\",\"name\":\"x\",\"scripts\":{\"a\":\"1\",\"b\":\"2\"},\"z\":null}",
+        ];
+        for input in inputs {
+            let first =
+                render_for_policy(input, &serde_json::from_str(input).unwrap(), &policy).unwrap();
+            let second = render_for_policy(
+                first.as_str(),
+                &serde_json::from_str(&first).unwrap(),
+                &policy,
+            )
+            .unwrap();
+            assert_eq!(first, second, "second pass changed output for {input:?}");
+            assert!(!first.contains("\n\n\n"), "{first}");
+        }
+    }
+
+    #[test]
+    fn test_linebreak_passes_collapse_repeated_blank_lines() {
+        let pretty = "{\n  \"name\": \"x\",\n\n\n\n  \"scripts\": {\n    \"a\": \"1\",\n\n\n    \"b\": \"2\"\n  }\n}".to_string();
+        let groups = vec![vec!["name".to_string()], vec!["scripts".to_string()]];
+        let mut in_rules: HashMap<String, LineBreakRule> = HashMap::new();
+        in_rules.insert("scripts".to_string(), LineBreakRule::Keep);
+        let keep_map = compute_in_field_keep_map(&pretty, &in_rules);
+        let once = apply_in_field_linebreaks(
+            apply_linebreaks(pretty, &groups, true, &HashMap::new()),
+            &in_rules,
+            &keep_map,
+        );
+        assert_eq!(
+            once,
+            "{\n  \"name\": \"x\",\n\n  \"scripts\": {\n    \"a\": \"1\",\n\n    \"b\": \"2\"\n  }\n}"
+        );
+        let twice = apply_in_field_linebreaks(
+            apply_linebreaks(once.clone(), &groups, true, &HashMap::new()),
+            &in_rules,
+            &compute_in_field_keep_map(&once, &in_rules),
+        );
+        assert_eq!(once, twice);
+    }
+
+    #[test]
+    fn test_linebreak_depth_ignores_braces_in_strings() {
+        // An unbalanced brace in a value must not hide the next top-level key
+        let pretty =
+            "{\n  \"emoji\": \"{shrug\",\n  \"note\": \"a: {b} \\\"}\\\"\",\n  \"scripts\": {}\n}"
+                .to_string();
+        let groups = vec![
+            vec!["emoji".to_string(), "note".to_string()],
+            vec!["scripts".to_string()],
+        ];
+        let out = apply_linebreaks(pretty, &groups, true, &HashMap::new());
+        assert!(out.contains("\",\n\n  \"scripts\": {}"), "{out}");
+        assert_eq!(out.matches("\n\n").count(), 1);
+
+        // Array elements that happen to equal a group-first key are not keys
+        let pretty = "{\n  \"files\": [\n    \"scripts\"\n  ],\n  \"scripts\": {}\n}".to_string();
+        let groups = vec![vec!["files".to_string()], vec!["scripts".to_string()]];
+        let out = apply_linebreaks(pretty, &groups, true, &HashMap::new());
+        assert_eq!(
+            out,
+            "{\n  \"files\": [\n    \"scripts\"\n  ],\n\n  \"scripts\": {}\n}"
+        );
+    }
+
+    #[test]
+    fn test_in_field_linebreaks_ignore_braces_and_colons_in_strings() {
+        let original = "{\n  \"scripts\": {\n    \"a\": \"echo }\",\n    \"b\": \"x: {\",\n\n    \"c\": \"echo \\\"{\\\"\"\n  },\n  \"other\": {\n    \"d\": 1,\n\n    \"e\": 2\n  }\n}";
+        let pretty = "{\n  \"scripts\": {\n    \"a\": \"echo }\",\n    \"b\": \"x: {\",\n    \"c\": \"echo \\\"{\\\"\"\n  },\n  \"other\": {\n    \"d\": 1,\n    \"e\": 2\n  }\n}".to_string();
+        let mut rules: HashMap<String, LineBreakRule> = HashMap::new();
+        rules.insert("scripts".to_string(), LineBreakRule::Keep);
+        let keep_map = compute_in_field_keep_map(original, &rules);
+        assert_eq!(
+            keep_map.get("scripts"),
+            Some(&HashSet::from(["c".to_string()]))
+        );
+        let out = apply_in_field_linebreaks(pretty, &rules, &keep_map);
+        assert!(out.contains("\"b\": \"x: {\",\n\n    \"c\""), "{out}");
+        // The field closes where it should, leaving `other` untouched
+        assert!(out.contains("\"d\": 1,\n    \"e\": 2"), "{out}");
+        assert_eq!(out.matches("\n\n").count(), 1);
+    }
+
+    #[test]
+    fn test_in_field_keep_tracks_direct_children_past_nested_values() {
+        let original = r#"{
+  "scripts": {
+    "a": "1",
+
+    "nested": {
+      "p": 1,
+
+      "q": 2
+    },
+    "list": [
+      "x",
+
+      "y"
+    ],
+
+    "b": "2"
+  }
+}"#;
+        let json: Json = serde_json::from_str(original).unwrap();
+        let pretty = serde_json::to_string_pretty(&json).unwrap();
+        let mut rules: HashMap<String, LineBreakRule> = HashMap::new();
+        rules.insert("scripts".to_string(), LineBreakRule::Keep);
+        let keep_map = compute_in_field_keep_map(original, &rules);
+        assert_eq!(
+            keep_map.get("scripts"),
+            Some(&HashSet::from(["nested".to_string(), "b".to_string()]))
+        );
+        let out = apply_in_field_linebreaks(pretty, &rules, &keep_map);
+        assert_eq!(
+            out,
+            "{\n  \"scripts\": {\n    \"a\": \"1\",\n\n    \"nested\": {\n      \"p\": 1,\n      \"q\": 2\n    },\n    \"list\": [\n      \"x\",\n      \"y\"\n    ],\n\n    \"b\": \"2\"\n  }\n}"
+        );
+    }
 }
@@ -0,0 +1,350 @@
+//! Minimal JSON5 reader for config and policy files.
+//!
+//! Parses JSON5 text into a `serde_json::Value` so it can be deserialized
+//! into the same structs used for TOML. Supports comments, unquoted keys,
+//! single-quoted strings, trailing commas, hex integers, explicit `+` signs,
+//! and leading/trailing decimal points. `Infinity` and `NaN` are rejected
+//! because JSON values cannot represent them.
+
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Number, Value as Json};
+
+/// Parse JSON5 text and deserialize it into `T`.
+pub fn from_str<T: DeserializeOwned>(s: &str) -> Result<T, String> {
+    let v = parse(s)?;
+    serde_json::from_value(v).map_err(|e| e.to_string())
+}
+
+/// Parse JSON5 text into a JSON value.
+pub fn parse(s: &str) -> Result<Json, String> {
+    let mut p = Parser {
+        chars: s.chars().collect(),
+        pos: 0,
+    };
+    p.skip_ws()?;
+    let v = p.value()?;
+    p.skip_ws()?;
+    if p.pos < p.chars.len() {
+        return Err(p.err("unexpected trailing content"));
+    }
+    Ok(v)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek();
+        if c.is_some() {
+            self.pos += 1;
+        }
+        c
+    }
+
+    fn err(&self, msg: &str) -> String {
+        let end = self.pos.min(self.chars.len());
+        let line = self.chars[..end].iter().filter(|c| **c == '\n').count() + 1;
+        format!("{} at line {}", msg, line)
+    }
+
+    /// Skip whitespace, `//` line comments, and `/* */` block comments.
+    fn skip_ws(&mut self) -> Result<(), String> {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() || c == '\u{feff}' => self.pos += 1,
+                Some('/') => match self.chars.get(self.pos + 1) {
+                    Some('/') => {
+                        while let Some(c) = self.bump() {
+                            if c == '\n' {
+                                break;
+                            }
+                        }
+                    }
+                    Some('*') => {
+                        self.pos += 2;
+                        loop {
+                            match self.bump() {
+                                Some('*') if self.peek() == Some('/') => {
+                                    self.pos += 1;
+                                    break;
+                                }
+                                Some(_) => {}
+                                None => return Err(self.err("unterminated comment")),
+                            }
+                        }
+                    }
+                    _ => return Ok(()),
+                },
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        match self.peek() {
+            Some('{') => self.object(),
+            Some('[') => self.array(),
+            Some(q @ ('"' | '\'')) => {
+                self.pos += 1;
+                self.string(q).map(Json::String)
+            }
+            Some(c) if c.is_ascii_digit() || matches!(c, '+' | '-' | '.') => self.number(),
+            Some(c) if is_ident_start(c) => {
+                let word = self.ident();
+                match word.as_str() {
+                    "true" => Ok(Json::Bool(true)),
+                    "false" => Ok(Json::Bool(false)),
+                    "null" => Ok(Json::Null),
+                    "Infinity" | "NaN" => Err(self.err("Infinity/NaN cannot be represented")),
+                    _ => Err(self.err(&format!("unexpected identifier '{}'", word))),
+                }
+            }
+            Some(c) => Err(self.err(&format!("unexpected character '{}'", c))),
+            None => Err(self.err("unexpected end of input")),
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, String> {
+        self.pos += 1;
+        let mut map = Map::new();
+        loop {
+            self.skip_ws()?;
+            if self.peek() == Some('}') {
+                self.pos += 1;
+                return Ok(Json::Object(map));
+            }
+            let key = match self.peek() {
+                Some(q @ ('"' | '\'')) => {
+                    self.pos += 1;
+                    self.string(q)?
+                }
+                Some(c) if is_ident_start(c) => self.ident(),
+                _ => return Err(self.err("expected object key")),
+            };
+            self.skip_ws()?;
+            if self.bump() != Some(':') {
+                return Err(self.err("expected ':' after object key"));
+            }
+            self.skip_ws()?;
+            let v = self.value()?;
+            map.insert(key, v);
+            self.skip_ws()?;
+            match self.bump() {
+                Some(',') => {}
+                Some('}') => return Ok(Json::Object(map)),
+                _ => return Err(self.err("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Json, String> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        loop {
+            self.skip_ws()?;
+            if self.peek() == Some(']') {
+                self.pos += 1;
+                return Ok(Json::Array(items));
+            }
+            items.push(self.value()?);
+            self.skip_ws()?;
+            match self.bump() {
+                Some(',') => {}
+                Some(']') => return Ok(Json::Array(items)),
+                _ => return Err(self.err("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn ident(&mut self) -> String {
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if is_ident_char(c)) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    /// Read a string body after the opening `quote`.
+    fn string(&mut self, quote: char) -> Result<String, String> {
+        let mut out = String::new();
+        loop {
+            match self.bump() {
+                None => return Err(self.err("unterminated string")),
+                Some(c) if c == quote => return Ok(out),
+                Some('\n') => return Err(self.err("unescaped newline in string")),
+                Some('\\') => match self.bump() {
+                    Some('n') => out.push('\n'),
+                    Some('r') => out.push('\r'),
+                    Some('t') => out.push('\t'),
+                    Some('b') => out.push('\u{8}'),
+                    Some('f') => out.push('\u{c}'),
+                    Some('v') => out.push('\u{b}'),
+                    Some('0') => out.push('\0'),
+                    Some('x') => {
+                        let code = self.hex(2)?;
+                        out.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+                    }
+                    Some('u') => {
+                        let hi = self.hex(4)?;
+                        let code = if (0xD800..0xDC00).contains(&hi)
+                            && self.peek() == Some('\\')
+                            && self.chars.get(self.pos + 1) == Some(&'u')
+                        {
+                            self.pos += 2;
+                            let lo = self.hex(4)?;
+                            0x10000 + ((hi - 0xD800) << 10) + (lo.wrapping_sub(0xDC00) & 0x3FF)
+                        } else {
+                            hi
+                        };
+                        out.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+                    }
+                    // Line continuation
+                    Some('\r') => {
+                        if self.peek() == Some('\n') {
+                            self.pos += 1;
+                        }
+                    }
+                    Some('\n' | '\u{2028}' | '\u{2029}') => {}
+                    Some(c) => out.push(c),
+                    None => return Err(self.err("unterminated string")),
+                },
+                Some(c) => out.push(c),
+            }
+        }
+    }
+
+    fn hex(&mut self, n: usize) -> Result<u32, String> {
+        let end = self.pos + n;
+        if end > self.chars.len() {
+            return Err(self.err("truncated hex escape"));
+        }
+        let s: String = self.chars[self.pos..end].iter().collect();
+        self.pos = end;
+        u32::from_str_radix(&s, 16).map_err(|_| self.err("invalid hex escape"))
+    }
+
+    fn number(&mut self) -> Result<Json, String> {
+        let neg = match self.peek() {
+            Some('-') => {
+                self.pos += 1;
+                true
+            }
+            Some('+') => {
+                self.pos += 1;
+                false
+            }
+            _ => false,
+        };
+        if matches!(self.peek(), Some(c) if is_ident_start(c)) {
+            let word = self.ident();
+            return Err(match word.as_str() {
+                "Infinity" | "NaN" => self.err("Infinity/NaN cannot be represented"),
+                _ => self.err(&format!("unexpected identifier '{}'", word)),
+            });
+        }
+        if self.peek() == Some('0') && matches!(self.chars.get(self.pos + 1), Some('x' | 'X')) {
+            self.pos += 2;
+            let start = self.pos;
+            while matches!(self.peek(), Some(c) if c.is_ascii_hexdigit()) {
+                self.pos += 1;
+            }
+            let digits: String = self.chars[start..self.pos].iter().collect();
+            let v = i64::from_str_radix(&digits, 16).map_err(|_| self.err("invalid hex number"))?;
+            return Ok(Json::from(if neg { -v } else { v }));
+        }
+        let start = self.pos;
+        self.digits();
+        if self.peek() == Some('.') {
+            self.pos += 1;
+            self.digits();
+        }
+        if matches!(self.peek(), Some('e' | 'E')) {
+            self.pos += 1;
+            if matches!(self.peek(), Some('+' | '-')) {
+                self.pos += 1;
+            }
+            self.digits();
+        }
+        let raw: String = self.chars[start..self.pos].iter().collect();
+        // Normalize JSON5-only forms (".5", "5.", "5.e3") into JSON syntax
+        let mut text = raw.replace(".e", ".0e").replace(".E", ".0E");
+        if text.starts_with('.') {
+            text.insert(0, '0');
+        }
+        if text.ends_with('.') {
+            text.push('0');
+        }
+        if neg {
+            text.insert(0, '-');
+        }
+        serde_json::from_str::<Number>(&text)
+            .map(Json::Number)
+            .map_err(|_| self.err(&format!("invalid number '{}'", raw)))
+    }
+
+    fn digits(&mut self) {
+        while matches!(self.peek(), Some(c) if c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+    }
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == '$'
+}
+
+fn is_ident_char(c: char) -> bool {
+    is_ident_start(c) || c.is_alphanumeric()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_json5_syntax() {
+        let v = parse(
+            r#"
+// leading comment
+{
+  unquoted: 'single',
+  "quoted": "a\tb", /* block */
+  hex: 0x1F,
+  frac: .5,
+  trail: 5.,
+  plus: +3,
+  list: [1, 2,],
+}
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            v,
+            json!({
+                "unquoted": "single",
+                "quoted": "a\tb",
+                "hex": 31,
+                "frac": 0.5,
+                "trail": 5.0,
+                "plus": 3,
+                "list": [1, 2]
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_json5_rejects_invalid_input() {
+        assert!(parse("{a: Infinity}").is_err());
+        assert!(parse("{a: 1} extra").is_err());
+        assert!(parse("{a: 'open}").is_err());
+        assert!(parse("/* never closed").is_err());
+    }
+}
@@ -6,22 +6,40 @@
 //! High-level modules:
 //! - `cli`: CLI argument parsing (binary uses this).
 //! - `config`: Discovery and effective configuration resolution.
+//! - `doctor`: Upfront validation of the policies an index references.
+//! - `explain`: Which rules, policies and checks apply to a rule id or file.
 //! - `format`: Deterministic JSON formatting including ordering and line breaks.
+//! - `json5`: Minimal JSON5 reader for `rigra.json5` and `*.json5` policies.
 //! - `lint`: Policy-driven validation, including order lint with message/level.
+//! - `locate`: Line/column positions of JSON paths in source text.
 //! - `sync`: Template synchronization with scope gating.
 //! - `models`: Data models for index, policy, and lint output structs.
 //! - `output`: Human/JSON printers for lint/format/sync.
+//! - `policy_diff`: Structural comparison of two policy files.
+//! - `policy_fmt`: Comment-preserving canonical layout for TOML policies.
+//! - `stream`: Incremental reader for large top-level JSON arrays.
 //! - `utils`: Supporting helpers.
 //! - `checks`: Implementation of policy checks.
+//! - `bench`: Timing of read-only lint/format passes (`rigra bench`).
+//! - `baseline`: Accepted-issue baselines for incremental adoption.
 //!
 //! Note: All documentation comments are written in English by convention.
+pub mod baseline;
+pub mod bench;
 pub mod checks;
 pub mod cli;
 pub mod config;
+pub mod conv;
+pub mod doctor;
+pub mod explain;
 pub mod format;
+pub mod json5;
 pub mod lint;
+pub mod locate;
 pub mod models;
 pub mod output;
+pub mod policy_diff;
+pub mod policy_fmt;
+pub mod stream;
 pub mod sync;
 pub mod utils;
-pub mod conv;
@@ -1,21 +1,33 @@
 //! Lint runner for policy checks and order validation.
 //!
 //! Produces a `LintResult` with issues and a summary. Order lint uses
-//! `policy.order` with optional `message` and `level` per policy.
+//! `policy.order` with optional `message` and `level` per policy; the
+//! opt-in `policy.formatting` check flags files `rigra format` would change.
+//!
+//! Targets are JSON unless the rule's `format` or the file extension says
+//! JSON5, YAML or TOML; those are converted to JSON values for the checks.
+//! Order lint covers JSON and YAML, formatting lint stays JSON-only.
+//! In TOML, tables become objects and arrays of tables (`[[bin]]`) become
+//! arrays of objects, so check paths read like `$.bin[0].name`.
+//!
+//! `run_lint_fix` (`lint --fix`) rewrites JSON targets to resolve key order
+//! and fixable checks before reporting what remains.
 
-use crate::checks::run_checks;
+use crate::checks::run_checks_with_level;
 use crate::models::index::{Index, RuleIndex};
-use crate::models::policy::Policy;
-use crate::models::sync_policy::SyncPolicy;
-use crate::models::{Issue, LintResult, RunError, Summary};
+use crate::models::policy::{Check, Policy};
+use crate::models::{Issue, LintResult, RuleTiming, RunError, Summary};
+use crate::stream::{for_each_array_element, StreamError};
 use crate::sync;
 use glob::glob;
 // owo_colors imported elsewhere for printing; not needed here after centralizing error prefix
 use rayon::prelude::*;
 use serde_json::Value as Json;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
 /// Run lint across files matched by the index.
 ///
@@ -24,11 +36,143 @@ use std::path::{Path, PathBuf};
 ///
 /// Severity accounting contributes to the final summary; `level = "error"`
 /// affects the error count and typical CI exit behavior upstream.
+///
+/// Files larger than `max_file_bytes` are not parsed; a warning issue naming
+/// the size is reported instead. When `stats` is given, per-rule wall-clock
+/// time is appended to it; rules are timed as a whole around their parallel
+/// file processing.
+///
+/// Rules themselves also run in parallel. Their issues are concatenated in
+/// index order, each rule's sorted by file then message, so the result is
+/// the same as a sequential run.
+///
+/// Checks on `$[*]` paths run once per element of a top-level array. With
+/// `stream_arrays`, rules made only of such checks (and no order/formatting)
+/// read arrays incrementally instead of parsing the whole document.
+///
+/// `cache_dir` locates installed conventions and sync state (default
+/// `<repo>/.rigra`). A non-empty `rules` limits the run to those index rule
+/// and sync rule ids (`--rule`).
+#[allow(clippy::too_many_arguments)]
 pub fn run_lint(
     repo_root: &str,
     index_path: &str,
+    cache_dir: Option<&Path>,
+    scope: &str,
+    patterns_override: &std::collections::HashMap<String, Vec<String>>,
+    max_file_bytes: Option<u64>,
+    stream_arrays: bool,
+    stats: Option<&mut Vec<RuleTiming>>,
+    rules: &[String],
+) -> (LintResult, Vec<RunError>) {
+    lint_with(
+        repo_root,
+        index_path,
+        cache_dir,
+        scope,
+        patterns_override,
+        max_file_bytes,
+        stream_arrays,
+        stats,
+        false,
+        None,
+        rules,
+    )
+}
+
+/// `run_lint` that first resolves fixable findings (`lint --fix`).
+///
+/// JSON targets with fixable issues are rewritten: arrays under fixable
+/// checks (`sortedArray`) are sorted and the policy key order is applied,
+/// rendered as `rigra format` would. Issues that no longer occur after the
+/// rewrite are moved to `LintResult::fixed`, and `summary.fixed` counts them;
+/// `issues` holds what remains.
+#[allow(clippy::too_many_arguments)]
+pub fn run_lint_fix(
+    repo_root: &str,
+    index_path: &str,
+    cache_dir: Option<&Path>,
+    scope: &str,
+    patterns_override: &std::collections::HashMap<String, Vec<String>>,
+    max_file_bytes: Option<u64>,
+    stream_arrays: bool,
+    stats: Option<&mut Vec<RuleTiming>>,
+    rules: &[String],
+) -> (LintResult, Vec<RunError>) {
+    let (mut res, errors) = lint_with(
+        repo_root,
+        index_path,
+        cache_dir,
+        scope,
+        patterns_override,
+        max_file_bytes,
+        stream_arrays,
+        stats,
+        true,
+        None,
+        rules,
+    );
+    res.summary.fixed = Some(res.fixed.len());
+    (res, errors)
+}
+
+/// `run_lint` (`run_lint_fix` when `fix`) restricted to `files`, paths
+/// relative to `repo_root` or absolute (`lint --files-from`).
+///
+/// Each rule still globs its patterns but only checks the listed targets,
+/// with its own policy; sync lint only reports listed targets. Listed files
+/// no rule matches are simply not checked.
+#[allow(clippy::too_many_arguments)]
+pub fn run_lint_files(
+    repo_root: &str,
+    index_path: &str,
+    cache_dir: Option<&Path>,
     scope: &str,
     patterns_override: &std::collections::HashMap<String, Vec<String>>,
+    max_file_bytes: Option<u64>,
+    stream_arrays: bool,
+    stats: Option<&mut Vec<RuleTiming>>,
+    fix: bool,
+    files: &[String],
+    rules: &[String],
+) -> (LintResult, Vec<RunError>) {
+    let root = Path::new(repo_root);
+    let only: HashSet<PathBuf> = files
+        .iter()
+        .map(|f| crate::explain::relative_to(root, Path::new(f)))
+        .collect();
+    let (mut res, errors) = lint_with(
+        repo_root,
+        index_path,
+        cache_dir,
+        scope,
+        patterns_override,
+        max_file_bytes,
+        stream_arrays,
+        stats,
+        fix,
+        Some(&only),
+        rules,
+    );
+    if fix {
+        res.summary.fixed = Some(res.fixed.len());
+    }
+    (res, errors)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn lint_with(
+    repo_root: &str,
+    index_path: &str,
+    cache_dir: Option<&Path>,
+    scope: &str,
+    patterns_override: &std::collections::HashMap<String, Vec<String>>,
+    max_file_bytes: Option<u64>,
+    stream_arrays: bool,
+    mut stats: Option<&mut Vec<RuleTiming>>,
+    fix: bool,
+    only: Option<&HashSet<PathBuf>>,
+    rules: &[String],
 ) -> (LintResult, Vec<RunError>) {
     let root = PathBuf::from(repo_root);
     let idx_path = root.join(index_path);
@@ -50,19 +194,24 @@ pub fn run_lint(
                             "Index file not found. Looked at '{}'. Pass --index or add rigra.toml.",
                             idx_path.to_string_lossy()
                         ),
+                        line: None,
+                        col: None,
                     }],
                     summary: Summary {
                         errors: 1,
                         warnings: 0,
                         infos: 0,
                         files: 0,
+                        fixed: None,
                     },
+                    fixed: Vec::new(),
+                    resolved: Vec::new(),
                 },
                 errors,
             );
         }
     };
-    let index: Index = match toml::from_str(&idx_str) {
+    let mut index: Index = match toml::from_str(&idx_str) {
         Ok(ix) => ix,
         Err(_) => {
             errors.push(RunError {
@@ -76,149 +225,425 @@ pub fn run_lint(
                         severity: "error".into(),
                         path: "$".into(),
                         message: "Index file is not valid TOML".into(),
+                        line: None,
+                        col: None,
                     }],
                     summary: Summary {
                         errors: 1,
                         warnings: 0,
                         infos: 0,
                         files: 0,
+                        fixed: None,
                     },
+                    fixed: Vec::new(),
+                    resolved: Vec::new(),
                 },
                 errors,
             );
         }
     };
+    if let Err(msg) = index.resolve_includes(&idx_path) {
+        errors.push(RunError {
+            message: msg.clone(),
+        });
+        return (
+            LintResult {
+                issues: vec![Issue {
+                    file: idx_path.to_string_lossy().to_string(),
+                    rule: "load-index".into(),
+                    severity: "error".into(),
+                    path: "$".into(),
+                    message: msg,
+                    line: None,
+                    col: None,
+                }],
+                summary: Summary {
+                    errors: 1,
+                    warnings: 0,
+                    infos: 0,
+                    files: 0,
+                    fixed: None,
+                },
+                fixed: Vec::new(),
+                resolved: Vec::new(),
+            },
+            errors,
+        );
+    }
+    index.retain_rules(rules);
 
     let mut issues: Vec<Issue> = Vec::new();
     let mut files_count: usize = 0;
+    let cache_dir = crate::config::cache_dir_or_default(&root, cache_dir);
+    let conv_cache = crate::conv::cache_root(&root, &cache_dir);
 
     // Cache policies across rules by path to avoid repeated I/O and parse when shared
-    let mut policy_cache: HashMap<PathBuf, Policy> = HashMap::new();
-    for ri in index.rules {
-        lint_rule(
+    let policy_cache: PolicyCache = Mutex::new(HashMap::new());
+    // Rules run in parallel; results are merged in index order so output
+    // matches a sequential run. Under `fix` rules may rewrite the same
+    // target, so they run one after another and each fix reads the last.
+    let mut fixed: Vec<Issue> = Vec::new();
+    let run_rule = |ri: RuleIndex| {
+        let started = Instant::now();
+        let rule = ri.id.clone();
+        let (found, resolved, files) = lint_rule(
             &root,
             &idx_path,
+            &conv_cache,
             ri,
-            &mut issues,
-            &mut files_count,
-            &mut policy_cache,
+            &policy_cache,
             patterns_override,
+            max_file_bytes,
+            stream_arrays,
+            fix,
+            only,
         );
+        let elapsed = started.elapsed();
+        (found, resolved, files, RuleTiming { rule, elapsed })
+    };
+    let indexed = std::mem::take(&mut index.rules);
+    let per_rule: Vec<(Vec<Issue>, Vec<Issue>, usize, RuleTiming)> = if fix {
+        indexed.into_iter().map(run_rule).collect()
+    } else {
+        indexed.into_par_iter().map(run_rule).collect()
+    };
+    for (found, resolved, files, timing) in per_rule {
+        issues.extend(found);
+        fixed.extend(resolved);
+        files_count += files;
+        if let Some(s) = stats.as_deref_mut() {
+            s.push(timing);
+        }
     }
 
-    // Evaluate sync status into lint using external policy
-    if let Some(sync_ref) = index.sync_ref.as_ref() {
-        let pol_path = idx_path
-            .parent()
-            .unwrap_or_else(|| Path::new("."))
-            .join(sync_ref);
-        if let Ok(pol_str) = fs::read_to_string(&pol_path) {
-            if let Ok(policy) = toml::from_str::<SyncPolicy>(&pol_str) {
-                let defaults = policy.lint.unwrap_or_default();
-                for rule in policy.sync {
-                    if !is_rule_enabled(&rule.when, scope) {
-                        continue;
-                    }
-                    // src resolved relative to index
-                    let src = idx_path.parent().unwrap().join(&rule.source);
-                    // apply client target override
-                    let client_cfg = crate::config::load_config(&root).unwrap_or_default();
-                    let dst_target = client_cfg
-                        .sync
-                        .as_ref()
-                        .and_then(|s| s.config.as_ref())
-                        .and_then(|m| m.get(&rule.id))
-                        .and_then(|c| c.target.clone())
-                        .unwrap_or_else(|| rule.target.clone());
-                    let dst = root.join(&dst_target);
-                    let (_w, would_write) = sync::apply_sync(
-                        &root,
-                        &rule,
-                        &src,
-                        &dst,
-                        client_cfg
-                            .sync
-                            .as_ref()
-                            .and_then(|s| s.config.as_ref())
-                            .and_then(|m| m.get(&rule.id)),
-                        false,
-                        Some(&mut errors),
-                    );
-                    if would_write {
-                        let sev = rule
-                            .level
-                            .clone()
-                            .or(defaults.level.clone())
-                            .unwrap_or_else(|| "info".to_string());
-                        let msg = rule
-                            .message
-                            .clone()
-                            .or(defaults.message.clone())
-                            .unwrap_or_else(|| {
-                                "Not synced yet. Please run rigra sync.".to_string()
-                            });
-                        issues.push(Issue {
-                            file: dst.to_string_lossy().to_string(),
-                            rule: format!("sync:{}", rule.id),
-                            severity: sev,
-                            path: "$".into(),
-                            message: msg,
-                        });
-                    }
-                }
+    // Evaluate sync status into lint using external policy and any
+    // policies inherited through `include`
+    let own_policy = match index.sync_ref.as_ref() {
+        Some(r) => {
+            sync::read_sync_policy(&idx_path.parent().unwrap_or_else(|| Path::new(".")).join(r))
+                .ok()
+        }
+        None => None,
+    };
+    if own_policy.is_some() || !index.included_sync.is_empty() {
+        let mut policy = own_policy.unwrap_or_default();
+        match sync::included_sync_rules(&idx_path, &index, &policy.sync) {
+            Ok(rules) => policy.sync.extend(rules),
+            Err(msg) => errors.push(RunError { message: msg }),
+        }
+        let defaults = policy.lint.unwrap_or_default();
+        for rule in policy.sync {
+            if !rules.is_empty() && !rules.contains(&rule.id) {
+                continue;
+            }
+            if !is_rule_enabled(&rule.when, scope) {
+                continue;
+            }
+            // src resolved relative to index
+            let src = idx_path.parent().unwrap().join(&rule.source);
+            // apply client target override
+            let client_cfg = crate::config::load_config(&root).unwrap_or_default();
+            let dst_target = client_cfg
+                .sync
+                .as_ref()
+                .and_then(|s| s.config.as_ref())
+                .and_then(|m| m.get(&rule.id))
+                .and_then(|c| c.target.clone())
+                .unwrap_or_else(|| rule.target.clone());
+            let dst = root.join(&dst_target);
+            if only.is_some_and(|o| !o.contains(&crate::explain::relative_to(&root, &dst))) {
+                continue;
+            }
+            let (_w, would_write) = sync::apply_sync(
+                &cache_dir,
+                &rule,
+                &src,
+                &dst,
+                client_cfg
+                    .sync
+                    .as_ref()
+                    .and_then(|s| s.config.as_ref())
+                    .and_then(|m| m.get(&rule.id)),
+                false,
+                Some(&mut errors),
+            );
+            if would_write {
+                // Client overrides (per rule, then [sync].lintLevel) beat the policy
+                let client_sync = client_cfg.sync.as_ref();
+                let sev = client_sync
+                    .and_then(|s| s.config.as_ref())
+                    .and_then(|m| m.get(&rule.id))
+                    .and_then(|c| c.lint_level.clone())
+                    .or_else(|| client_sync.and_then(|s| s.lint_level.clone()))
+                    .or_else(|| rule.level.clone())
+                    .or(defaults.level.clone())
+                    .unwrap_or_else(|| "info".to_string());
+                let msg = rule
+                    .message
+                    .clone()
+                    .or(defaults.message.clone())
+                    .unwrap_or_else(|| "Not synced yet. Please run rigra sync.".to_string());
+                issues.push(Issue {
+                    file: dst.to_string_lossy().to_string(),
+                    rule: format!("sync:{}", rule.id),
+                    severity: sev,
+                    path: "$".into(),
+                    message: msg,
+                    line: None,
+                    col: None,
+                });
             }
         }
     }
 
+    let summary = summarize(&issues, files_count);
+    (
+        LintResult {
+            issues,
+            summary,
+            fixed,
+            resolved: Vec::new(),
+        },
+        errors,
+    )
+}
+
+/// Raise every sync drift issue (`sync:<id>`) to error severity, whatever
+/// its configured level, and recount the summary (`lint --fail-on-sync-drift`).
+pub fn escalate_sync_drift(mut res: LintResult) -> LintResult {
+    for is in res.issues.iter_mut() {
+        if is.rule.starts_with("sync:") {
+            is.severity = "error".into();
+        }
+    }
+    let fixed = res.summary.fixed;
+    res.summary = summarize(&res.issues, res.summary.files);
+    res.summary.fixed = fixed;
+    res
+}
+
+/// Count issues by severity into a `Summary`.
+pub fn summarize(issues: &[Issue], files: usize) -> Summary {
     let mut errs = 0usize;
     let mut warns = 0usize;
     let mut infos = 0usize;
-    for is in &issues {
+    for is in issues {
         match is.severity.as_str() {
             "error" => errs += 1,
             "warning" => warns += 1,
             _ => infos += 1,
         }
     }
-    (
-        LintResult {
-            issues,
-            summary: Summary {
-                errors: errs,
-                warnings: warns,
-                infos,
-                files: files_count,
-            },
-        },
-        errors,
-    )
+    Summary {
+        errors: errs,
+        warnings: warns,
+        infos,
+        files,
+        fixed: None,
+    }
 }
 fn is_rule_enabled(when: &str, scope: &str) -> bool {
     let w = when.trim();
     if w.is_empty() || w == "*" || w.eq_ignore_ascii_case("any") || w.eq_ignore_ascii_case("all") {
         return true;
     }
-    w.split(|c| c == ',' || c == '|')
-        .map(|s| s.trim())
-        .any(|tok| !tok.is_empty() && tok.eq_ignore_ascii_case(scope))
+    w.split([',', '|']).map(|s| s.trim()).any(|tok| {
+        !tok.is_empty()
+            && scope
+                .split(',')
+                .any(|active| tok.eq_ignore_ascii_case(active.trim()))
+    })
+}
+
+/// Run element-scoped checks against array element `i`, reporting paths
+/// as `$[i].field`.
+fn element_issues(
+    checks: &[Check],
+    i: usize,
+    el: &Json,
+    path: &Path,
+    root: &Path,
+    rule_id: &str,
+    default_level: Option<&str>,
+) -> Vec<Issue> {
+    run_checks_with_level(checks, el, path, root, rule_id, default_level)
+        .into_iter()
+        .map(|mut is| {
+            is.path = format!("$[{}]{}", i, is.path.strip_prefix('$').unwrap_or(&is.path));
+            is
+        })
+        .collect()
+}
+
+/// Parse a lint target into a JSON value; unparsable files yield `None`
+/// and are skipped like invalid JSON.
+fn parse_target(data: &str, format: &str) -> Option<Json> {
+    match format {
+        "json5" => crate::json5::parse(data).ok(),
+        "yaml" => serde_yaml::from_str(data).ok(),
+        "toml" => toml::from_str::<toml::Value>(data)
+            .ok()
+            .and_then(|v| serde_json::to_value(v).ok()),
+        _ => serde_json::from_str(data).ok(),
+    }
+}
+
+/// Issues for one parsed target: the policy checks, per-element checks on a
+/// top-level array, the order precondition for JSON and YAML and, for JSON,
+/// the formatting precondition.
+#[allow(clippy::too_many_arguments)]
+fn target_issues(
+    path: &Path,
+    root: &Path,
+    data: &str,
+    json: &Json,
+    format: &str,
+    rule_id: &str,
+    policy: &Policy,
+    root_checks: &[Check],
+    element_checks: &[Check],
+    default_level: Option<&str>,
+) -> Vec<Issue> {
+    let mut file_issues: Vec<Issue> = Vec::new();
+    let mut found = run_checks_with_level(root_checks, json, path, root, rule_id, default_level);
+    file_issues.append(&mut found);
+    if let (Json::Array(items), false) = (json, element_checks.is_empty()) {
+        for (i, el) in items.iter().enumerate() {
+            file_issues.extend(element_issues(
+                element_checks,
+                i,
+                el,
+                path,
+                root,
+                rule_id,
+                default_level,
+            ));
+        }
+    }
+    if matches!(format, "json" | "json5") {
+        crate::locate::annotate(&mut file_issues, data);
+    }
+    if !matches!(format, "json" | "yaml") {
+        return file_issues;
+    }
+    if let Some(fmt) = policy
+        .formatting
+        .as_ref()
+        .filter(|f| f.check && format == "json")
+    {
+        let formatted = crate::format::render_for_policy(data, json, policy);
+        if formatted.is_some_and(|s| s.trim_end() != data.trim_end()) {
+            file_issues.push(Issue {
+                file: path.to_string_lossy().to_string(),
+                rule: rule_id.to_string(),
+                severity: fmt
+                    .level
+                    .clone()
+                    .or(policy.default_level.clone())
+                    .unwrap_or_else(|| "error".to_string()),
+                path: "$".to_string(),
+                message: fmt
+                    .message
+                    .clone()
+                    .unwrap_or_else(|| "File is not formatted; run rigra format".to_string()),
+                line: None,
+                col: None,
+            });
+        }
+    }
+    if let Some(ord) = policy.order.as_ref() {
+        if let Json::Object(obj) = &json {
+            let expected = crate::format::ordered_keys(obj, &ord.top, ord.collation, ord.rest);
+            let mut unordered: Vec<String> = Vec::new();
+            if !obj.keys().eq(expected.iter()) {
+                unordered.push("$".to_string());
+            }
+            // Nested `order.sub` objects are reported at their own path
+            unordered.extend(crate::format::sub_order_mismatches(
+                json,
+                &ord.sub,
+                ord.collation,
+                ord.rest,
+            ));
+            for at in unordered {
+                file_issues.push(Issue {
+                    file: path.to_string_lossy().to_string(),
+                    rule: rule_id.to_string(),
+                    severity: ord
+                        .level
+                        .clone()
+                        .or(policy.default_level.clone())
+                        .unwrap_or_else(|| "error".to_string()),
+                    path: at,
+                    message: ord
+                        .message
+                        .clone()
+                        .unwrap_or_else(|| "Object key order does not match policy".to_string()),
+                    line: None,
+                    col: None,
+                });
+            }
+        }
+    }
+    file_issues
+}
+
+/// Rewrite a JSON target for `lint --fix`: sort the arrays of fixable
+/// checks and apply the policy key order, rendered as `rigra format` would.
+/// `None` when nothing fixable is off.
+fn fix_target(
+    data: &str,
+    json: &Json,
+    policy: &Policy,
+    root_checks: &[Check],
+    element_checks: &[Check],
+) -> Option<String> {
+    let mut fixed = json.clone();
+    let mut changed = crate::checks::apply_fixes(root_checks, &mut fixed);
+    if let Json::Array(items) = &mut fixed {
+        for el in items {
+            changed |= crate::checks::apply_fixes(element_checks, el);
+        }
+    }
+    if let (Some(ord), Json::Object(obj)) = (policy.order.as_ref(), &fixed) {
+        let expected = crate::format::ordered_keys(obj, &ord.top, ord.collation, ord.rest);
+        changed |= !obj.keys().eq(expected.iter())
+            || !crate::format::sub_order_mismatches(&fixed, &ord.sub, ord.collation, ord.rest)
+                .is_empty();
+    }
+    if !changed {
+        return None;
+    }
+    crate::format::render_for_policy(data, &fixed, policy)
 }
 
-/// Lint a single indexed rule against its targets, collecting issues.
+/// Parsed policies shared by rules running in parallel, keyed by path.
+type PolicyCache = Mutex<HashMap<PathBuf, Arc<Policy>>>;
+
+/// Lint a single indexed rule against its targets, returning its issues
+/// (sorted by file then message), the issues `fix` resolved by rewriting
+/// targets, and the number of files checked. With `only`, targets outside
+/// that set of repo-relative paths are skipped.
+#[allow(clippy::too_many_arguments)]
 fn lint_rule(
-    root: &PathBuf,
-    idx_path: &PathBuf,
+    root: &Path,
+    idx_path: &Path,
+    conv_cache: &Path,
     ri: RuleIndex,
-    issues: &mut Vec<Issue>,
-    files_count: &mut usize,
-    policy_cache: &mut HashMap<PathBuf, Policy>,
+    policy_cache: &PolicyCache,
     patterns_override: &std::collections::HashMap<String, Vec<String>>,
-) {
-    let pol_path = idx_path
-        .parent()
-        .unwrap_or_else(|| Path::new("."))
-        .join(&ri.policy);
-    let policy: &Policy = if let Some(p) = policy_cache.get(&pol_path) {
-        p
+    max_file_bytes: Option<u64>,
+    stream_arrays: bool,
+    fix: bool,
+    only: Option<&HashSet<PathBuf>>,
+) -> (Vec<Issue>, Vec<Issue>, usize) {
+    let mut issues: Vec<Issue> = Vec::new();
+    // Policies may live in another installed convention (`conv:name@ver:path`)
+    let pol_path = crate::conv::resolve_index_ref(conv_cache, idx_path, &ri.policy);
+    // The lock is held while loading so a shared policy is parsed once
+    let mut cache = policy_cache.lock().unwrap_or_else(|e| e.into_inner());
+    let policy: Arc<Policy> = if let Some(p) = cache.get(&pol_path) {
+        Arc::clone(p)
     } else {
         let pol_str = match fs::read_to_string(&pol_path) {
             Ok(s) => s,
@@ -233,19 +658,17 @@ fn lint_rule(
                         ri.id,
                         pol_path.to_string_lossy()
                     ),
+                    line: None,
+                    col: None,
                 });
-                return;
+                return (issues, Vec::new(), 0);
             }
         };
-        match toml::from_str::<Policy>(&pol_str) {
+        match Policy::parse(&pol_path, &pol_str) {
             Ok(p) => {
-                // Insert and then fetch without unwrap to avoid panic
-                policy_cache.insert(pol_path.clone(), p);
-                if let Some(pref) = policy_cache.get(&pol_path) {
-                    pref
-                } else {
-                    return;
-                }
+                let p = Arc::new(p);
+                cache.insert(pol_path.clone(), Arc::clone(&p));
+                p
             }
             Err(_) => {
                 issues.push(Issue {
@@ -253,12 +676,20 @@ fn lint_rule(
                     rule: ri.id.clone(),
                     severity: "error".into(),
                     path: "$".into(),
-                    message: "Policy file is not valid TOML".into(),
+                    message: if crate::utils::is_json5(&pol_path) {
+                        "Policy file is not valid JSON5".into()
+                    } else {
+                        "Policy file is not valid TOML".into()
+                    },
+                    line: None,
+                    col: None,
                 });
-                return;
+                return (issues, Vec::new(), 0);
             }
         }
     };
+    drop(cache);
+    let policy: &Policy = &policy;
 
     // Choose patterns: override from rigra.toml if available, otherwise index defaults
     let use_patterns: Vec<String> = patterns_override
@@ -273,74 +704,154 @@ fn lint_rule(
             Ok(it) => it,
             Err(e) => {
                 eprintln!(
-                    "{} {}",
+                    "{} Invalid glob pattern for rule '{}': {} — {}",
                     crate::utils::error_prefix(),
-                    format!(
-                        "Invalid glob pattern for rule '{}': {} — {}",
-                        ri.id, pattern, e
-                    )
+                    ri.id,
+                    pattern,
+                    e
                 );
                 continue;
             }
         };
-        for entry in itr {
-            if let Ok(p) = entry {
-                targets.push(p);
-            }
+        for p in itr.flatten() {
+            targets.push(p);
         }
     }
+    if let Some(only) = only {
+        targets.retain(|p| only.contains(&crate::explain::relative_to(root, p)));
+    }
 
-    let mut per_file: Vec<(Vec<Issue>, usize)> = targets
+    // Split checks: `$[*]` element paths run per array element, the rest on the root
+    let mut root_checks: Vec<Check> = Vec::new();
+    let mut element_checks: Vec<Check> = Vec::new();
+    for c in &policy.checks {
+        match c.for_elements() {
+            Some(e) => element_checks.push(e),
+            None => root_checks.push(c.clone()),
+        }
+    }
+    let default_level = policy.default_level.as_deref();
+    // Only order and fixable checks can be resolved by `fix`
+    let fix = fix && (policy.order.is_some() || policy.checks.iter().any(Check::fixable));
+    let streamable = stream_arrays
+        && !fix
+        && ri.format.as_deref().is_none_or(|f| f == "json")
+        && root_checks.is_empty()
+        && !element_checks.is_empty()
+        && policy.order.is_none()
+        && !policy.formatting.as_ref().is_some_and(|f| f.check);
+    let mut per_file: Vec<(Vec<Issue>, Vec<Issue>, usize)> = targets
         .par_iter()
         .map(|path| {
+            if let Some(size) = crate::utils::oversized(path, max_file_bytes) {
+                let issue = Issue {
+                    file: path.to_string_lossy().to_string(),
+                    rule: ri.id.clone(),
+                    severity: "warning".into(),
+                    path: "$".into(),
+                    message: format!(
+                        "File skipped: {} bytes exceeds limits.maxFileBytes ({})",
+                        size,
+                        max_file_bytes.unwrap_or_default()
+                    ),
+                    line: None,
+                    col: None,
+                };
+                return (vec![issue], Vec::new(), 0);
+            }
+            let format = crate::utils::target_format(path, ri.format.as_deref());
+            if streamable && format == "json" {
+                if let Ok(file) = fs::File::open(path) {
+                    let mut file_issues = Vec::new();
+                    let streamed = for_each_array_element(file, |i, el| {
+                        file_issues.extend(element_issues(
+                            &element_checks,
+                            i,
+                            &el,
+                            path,
+                            root,
+                            &ri.id,
+                            default_level,
+                        ));
+                    });
+                    match streamed {
+                        Ok(_) => return (file_issues, Vec::new(), 1),
+                        // Not an array: fall back to a full parse below
+                        Err(StreamError::NotArray) => {}
+                        Err(_) => return (Vec::new(), Vec::new(), 0),
+                    }
+                }
+            }
             let data = match fs::read_to_string(path) {
                 Ok(s) => s,
-                Err(_) => return (Vec::new(), 0),
+                Err(_) => return (Vec::new(), Vec::new(), 0),
             };
-            let json: Json = match serde_json::from_str(&data) {
-                Ok(v) => v,
-                Err(_) => return (Vec::new(), 0),
+            let Some(json) = parse_target(&data, format) else {
+                return (Vec::new(), Vec::new(), 0);
             };
-            let mut file_issues: Vec<Issue> = Vec::new();
-            let mut found = run_checks(&policy.checks, &json, path, &ri.id);
-            file_issues.append(&mut found);
-            if let Some(ord) = policy.order.as_ref() {
-                if let Json::Object(obj) = &json {
-                    let actual: Vec<String> = obj.keys().cloned().collect();
-                    let mut expected: Vec<String> = Vec::new();
-                    for group in &ord.top {
-                        for key in group {
-                            if obj.contains_key(key.as_str()) {
-                                expected.push(key.clone());
-                            }
-                        }
-                    }
-                    let mut rest: Vec<String> = obj
-                        .keys()
-                        .filter(|k| !expected.contains(k))
-                        .cloned()
-                        .collect();
-                    rest.sort();
-                    expected.extend(rest);
-                    if expected != actual {
-                        file_issues.push(Issue {
-                            file: path.to_string_lossy().to_string(),
-                            rule: ri.id.clone(),
-                            severity: ord.level.clone().unwrap_or_else(|| "error".to_string()),
-                            path: "$".to_string(),
-                            message: ord.message.clone().unwrap_or_else(|| {
-                                "Object key order does not match policy".to_string()
-                            }),
-                        });
-                    }
-                }
+            let mut file_issues = target_issues(
+                path,
+                root,
+                &data,
+                &json,
+                format,
+                &ri.id,
+                policy,
+                &root_checks,
+                &element_checks,
+                default_level,
+            );
+            let fixed_text = if fix && format == "json" {
+                fix_target(&data, &json, policy, &root_checks, &element_checks)
+            } else {
+                None
+            };
+            let Some(text) = fixed_text.filter(|t| t.trim_end() != data.trim_end()) else {
+                return (file_issues, Vec::new(), 1);
+            };
+            if let Err(e) = fs::write(path, &text) {
+                eprintln!(
+                    "{} Failed to write fixed file '{}': {}",
+                    crate::utils::error_prefix(),
+                    path.to_string_lossy(),
+                    e
+                );
+                return (file_issues, Vec::new(), 1);
             }
-            (file_issues, 1)
+            let Some(json) = parse_target(&text, format) else {
+                return (file_issues, Vec::new(), 1);
+            };
+            let remaining = target_issues(
+                path,
+                root,
+                &text,
+                &json,
+                format,
+                &ri.id,
+                policy,
+                &root_checks,
+                &element_checks,
+                default_level,
+            );
+            // Positions move with the rewrite, so compare without them
+            let key = |i: &Issue| (i.rule.clone(), i.path.clone(), i.message.clone());
+            let left: std::collections::HashSet<_> = remaining.iter().map(key).collect();
+            file_issues.retain(|i| !left.contains(&key(i)));
+            (remaining, file_issues, 1)
         })
         .collect();
     // Deterministic ordering of issues by file then message
-    let mut combined: Vec<Issue> = per_file.iter_mut().flat_map(|(v, _)| v.drain(..)).collect();
+    let mut combined: Vec<Issue> = per_file
+        .iter_mut()
+        .flat_map(|(v, _, _)| v.drain(..))
+        .collect();
     combined.sort_by(|a, b| a.file.cmp(&b.file).then(a.message.cmp(&b.message)));
-    *files_count += per_file.iter().map(|(_, c)| *c).sum::<usize>();
     issues.extend(combined);
+    let mut fixed: Vec<Issue> = per_file
+        .iter_mut()
+        .flat_map(|(_, f, _)| f.drain(..))
+        .collect();
+    fixed.sort_by(|a, b| a.file.cmp(&b.file).then(a.message.cmp(&b.message)));
+    let files = per_file.iter().map(|(_, _, c)| *c).sum::<usize>();
+    (issues, fixed, files)
 }
@@ -0,0 +1,293 @@
+//! Source positions for lint issue paths.
+//!
+//! Checks run on parsed values and report JSON paths (`$.a.b[0]`). This
+//! module scans the original JSON/JSON5 text to map such a path back to a
+//! 1-based line and column: the key of an object member, or the start of an
+//! array element. A path that does not exist (e.g. a missing required field)
+//! resolves to its nearest existing ancestor.
+
+use crate::models::Issue;
+
+/// One step of a JSON path.
+#[derive(Debug, PartialEq)]
+enum Seg {
+    Key(String),
+    Index(usize),
+}
+
+/// Fill `line`/`col` on every issue that lacks them, using `text` as the
+/// source the issues were produced from.
+pub fn annotate(issues: &mut [Issue], text: &str) {
+    let mut sc = Scanner::new(text);
+    for is in issues.iter_mut().filter(|i| i.line.is_none()) {
+        let Some(segs) = parse_path(&is.path) else {
+            continue;
+        };
+        if let Some((line, col)) = sc.find(&segs) {
+            is.line = Some(line);
+            is.col = Some(col);
+        }
+    }
+}
+
+/// Split `$.a.b[0]["c.d"]` into segments; `None` if it is not a `$` path.
+fn parse_path(path: &str) -> Option<Vec<Seg>> {
+    let mut rest = path.strip_prefix('$')?;
+    let mut segs = Vec::new();
+    while !rest.is_empty() {
+        if let Some(r) = rest.strip_prefix("[\"") {
+            let end = r.find("\"]")?;
+            segs.push(Seg::Key(r[..end].to_string()));
+            rest = &r[end + 2..];
+        } else if let Some(r) = rest.strip_prefix('[') {
+            let end = r.find(']')?;
+            segs.push(Seg::Index(r[..end].parse().ok()?));
+            rest = &r[end + 1..];
+        } else {
+            let r = rest.strip_prefix('.').unwrap_or(rest);
+            let end = r.find(['.', '[']).unwrap_or(r.len());
+            if end > 0 {
+                segs.push(Seg::Key(r[..end].to_string()));
+            }
+            rest = &r[end..];
+        }
+    }
+    Some(segs)
+}
+
+/// Tolerant JSON/JSON5 scanner: it only tracks structure, never validates.
+struct Scanner {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Scanner {
+    fn new(text: &str) -> Self {
+        Scanner {
+            chars: text.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    /// Position of the deepest segment of `segs` present in the text.
+    fn find(&mut self, segs: &[Seg]) -> Option<(usize, usize)> {
+        self.pos = 0;
+        self.skip_ws();
+        self.peek()?;
+        let mut best = self.pos;
+        for seg in segs {
+            let found = match (seg, self.peek()) {
+                (Seg::Key(k), Some('{')) => self.enter_member(k),
+                (Seg::Index(n), Some('[')) => self.enter_element(*n),
+                _ => None,
+            };
+            match found {
+                Some(at) => best = at,
+                None => break,
+            }
+        }
+        Some(self.line_col(best))
+    }
+
+    /// Move to the value of member `key`; returns the key's offset.
+    fn enter_member(&mut self, key: &str) -> Option<usize> {
+        self.pos += 1;
+        loop {
+            self.skip_ws();
+            match self.peek()? {
+                '}' => return None,
+                ',' => {
+                    self.pos += 1;
+                    continue;
+                }
+                _ => {}
+            }
+            let start = self.pos;
+            let name = self.read_key();
+            self.skip_ws();
+            if self.peek()? != ':' {
+                return None;
+            }
+            self.pos += 1;
+            self.skip_ws();
+            if name == key {
+                return Some(start);
+            }
+            self.skip_value();
+        }
+    }
+
+    /// Move to element `n` of an array; returns its offset.
+    fn enter_element(&mut self, n: usize) -> Option<usize> {
+        self.pos += 1;
+        let mut i = 0;
+        loop {
+            self.skip_ws();
+            match self.peek()? {
+                ']' => return None,
+                ',' => {
+                    self.pos += 1;
+                    continue;
+                }
+                _ => {}
+            }
+            if i == n {
+                return Some(self.pos);
+            }
+            self.skip_value();
+            i += 1;
+        }
+    }
+
+    fn read_key(&mut self) -> String {
+        match self.peek() {
+            Some(q @ ('"' | '\'')) => self.read_string(q),
+            _ => {
+                let start = self.pos;
+                while self
+                    .peek()
+                    .is_some_and(|c| c != ':' && !c.is_whitespace() && c != '/')
+                {
+                    self.pos += 1;
+                }
+                self.chars[start..self.pos].iter().collect()
+            }
+        }
+    }
+
+    /// Read a quoted string starting at the opening quote `q`.
+    fn read_string(&mut self, q: char) -> String {
+        self.pos += 1;
+        let mut out = String::new();
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            match c {
+                _ if c == q => break,
+                '\\' => {
+                    let Some(e) = self.peek() else { break };
+                    self.pos += 1;
+                    match e {
+                        'n' => out.push('\n'),
+                        't' => out.push('\t'),
+                        'r' => out.push('\r'),
+                        'b' => out.push('\u{8}'),
+                        'f' => out.push('\u{c}'),
+                        'u' => {
+                            let hex: String = self.chars.iter().skip(self.pos).take(4).collect();
+                            if let Some(ch) =
+                                u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32)
+                            {
+                                out.push(ch);
+                                self.pos += 4;
+                            }
+                        }
+                        other => out.push(other),
+                    }
+                }
+                _ => out.push(c),
+            }
+        }
+        out
+    }
+
+    fn skip_value(&mut self) {
+        match self.peek() {
+            Some(q @ ('"' | '\'')) => {
+                self.read_string(q);
+            }
+            Some(open @ ('{' | '[')) => {
+                let close = if open == '{' { '}' } else { ']' };
+                self.pos += 1;
+                loop {
+                    self.skip_ws();
+                    match self.peek() {
+                        None => return,
+                        Some(c) if c == close => {
+                            self.pos += 1;
+                            return;
+                        }
+                        Some(',' | ':') => self.pos += 1,
+                        Some(_) => self.skip_value(),
+                    }
+                }
+            }
+            _ => {
+                let start = self.pos;
+                while self
+                    .peek()
+                    .is_some_and(|c| !matches!(c, ',' | '}' | ']' | ':') && !c.is_whitespace())
+                {
+                    self.pos += 1;
+                }
+                // Always make progress, even on a stray closing bracket
+                if self.pos == start && self.peek().is_some() {
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    /// Skip whitespace and `//` / `/* */` comments.
+    fn skip_ws(&mut self) {
+        loop {
+            while self.peek().is_some_and(char::is_whitespace) {
+                self.pos += 1;
+            }
+            let next = self.chars.get(self.pos + 1).copied();
+            match (self.peek(), next) {
+                (Some('/'), Some('/')) => {
+                    while self.peek().is_some_and(|c| c != '\n') {
+                        self.pos += 1;
+                    }
+                }
+                (Some('/'), Some('*')) => {
+                    self.pos += 2;
+                    while self.pos < self.chars.len()
+                        && !(self.chars[self.pos] == '*'
+                            && self.chars.get(self.pos + 1) == Some(&'/'))
+                    {
+                        self.pos += 1;
+                    }
+                    self.pos = (self.pos + 2).min(self.chars.len());
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn line_col(&self, offset: usize) -> (usize, usize) {
+        let before = &self.chars[..offset];
+        let line = before.iter().filter(|c| **c == '\n').count() + 1;
+        let col = offset - before.iter().rposition(|c| *c == '\n').map_or(0, |i| i + 1) + 1;
+        (line, col)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(text: &str, path: &str) -> Option<(usize, usize)> {
+        Scanner::new(text).find(&parse_path(path)?)
+    }
+
+    #[test]
+    fn test_position_of_members_elements_and_missing_paths() {
+        let text = "{\n  \"name\": \"x\",\n  // comment: {\"files\": 1}\n  \"files\": [\n    \"a\",\n    {\"b.c\": true}\n  ],\n  'bare': { key: 1 }\n}\n";
+        assert_eq!(position(text, "$"), Some((1, 1)));
+        assert_eq!(position(text, "$.name"), Some((2, 3)));
+        assert_eq!(position(text, "$.files"), Some((4, 3)));
+        assert_eq!(position(text, "$.files[1]"), Some((6, 5)));
+        assert_eq!(position(text, "$.files[1][\"b.c\"]"), Some((6, 6)));
+        assert_eq!(position(text, "$.bare.key"), Some((8, 13)));
+        // Missing paths fall back to the nearest existing ancestor
+        assert_eq!(position(text, "$.files[5]"), Some((4, 3)));
+        assert_eq!(position(text, "$.version"), Some((1, 1)));
+        assert_eq!(position(text, "name"), None);
+        assert_eq!(position("", "$.a"), None);
+    }
+}
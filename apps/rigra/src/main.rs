@@ -1,14 +1,23 @@
 //! Rigra CLI binary entry point.
 //! Delegates to modules for lint/format/sync and prints results.
 
+mod baseline;
+mod bench;
 mod checks;
 mod cli;
 mod config;
 mod conv;
+mod doctor;
+mod explain;
 mod format;
+mod json5;
 mod lint;
+mod locate;
 mod models;
 mod output;
+mod policy_diff;
+mod policy_fmt;
+mod stream;
 mod sync;
 mod utils;
 
@@ -17,11 +26,30 @@ use clap::Parser;
 use cli::{Cli, Commands};
 // Colorization centralized in utils; no direct owo_colors usage here
 use std::fs;
+use std::io::IsTerminal;
 
 fn main() {
     // Early help handling to avoid surprises; prints long help and exits
     // Rely on Clap's auto help; no early manual printing
     let cli = Cli::parse();
+    let no_ignore = cli.no_ignore;
+    let mut stats = cli.stats.then(Vec::new);
+    let explain_exit = cli.explain_exit;
+    // Global --dry-run: every command previews instead of writing
+    let dry_run = cli.dry_run;
+    let cache_dir = cli.cache_dir.clone();
+    // Exit non-zero (1, or 3 for --max-warnings) for a failing check,
+    // optionally explaining why
+    let fail = |cause: output::ExitCause| -> ! {
+        if explain_exit {
+            eprintln!(
+                "{} {}",
+                crate::utils::info_prefix(),
+                output::compose_exit_trailer(&cause)
+            );
+        }
+        std::process::exit(cause.code());
+    };
     match cli.cmd {
         Commands::Version => {
             println!("{}", env!("CARGO_PKG_VERSION"));
@@ -31,6 +59,15 @@ fn main() {
             scope,
             output,
             index,
+            baseline,
+            baseline_update,
+            diff_baseline,
+            fix,
+            flat,
+            files_from,
+            fail_on_sync_drift,
+            rule,
+            max_warnings,
         } => {
             let eff = config::resolve_effective(
                 repo_root.as_deref(),
@@ -40,41 +77,40 @@ fn main() {
                 None,
                 None,
                 None,
+                cache_dir.as_deref(),
             );
             // Require index to be configured (no default)
             if !eff.index_configured {
                 eprintln!(
-                    "{} {}",
-                    crate::utils::error_prefix(),
-                    "Index is not configured. Pass --index or add rigra.toml."
+                    "{} Index is not configured. Pass --index or add rigra.toml.",
+                    crate::utils::error_prefix()
                 );
                 std::process::exit(2);
             }
             // Friendly note if no rigra config was found
             if config::load_config(&eff.repo_root).is_none() {
                 eprintln!(
-                    "{} {}",
-                    crate::utils::note_prefix(),
-                    "No rigra.toml found; using defaults."
+                    "{} No rigra.toml found; using defaults.",
+                    crate::utils::note_prefix()
                 );
             }
             // Friendly error if index file is missing
             let idx_path = eff.repo_root.join(&eff.index);
             if !idx_path.exists() {
                 eprintln!(
-                    "{} {}",
+                    "{} Index file not found: {} (pass --index or configure rigra.toml)",
                     crate::utils::error_prefix(),
-                    format!(
-                        "Index file not found: {} (pass --index or configure rigra.toml)",
-                        idx_path.to_string_lossy()
-                    )
+                    idx_path.to_string_lossy()
                 );
                 std::process::exit(2);
             }
+            require_rule_ids(&eff, &rule, true, true);
             // Emit single top info when default patterns from index are used (no overrides in rigra.toml)
-            if eff.output != "json" {
+            if !output::is_json(&eff.output) {
                 if let Ok(s) = fs::read_to_string(&idx_path) {
-                    if let Ok(ix) = toml::from_str::<Index>(&s) {
+                    if let Ok(mut ix) = toml::from_str::<Index>(&s) {
+                        // Include errors surface from the run itself
+                        let _ = ix.resolve_includes(&idx_path);
                         let mut pat_set: std::collections::BTreeSet<String> =
                             std::collections::BTreeSet::new();
                         for r in ix.rules.iter() {
@@ -88,34 +124,141 @@ fn main() {
                             let joined =
                                 format!("[{}]", pat_set.into_iter().collect::<Vec<_>>().join(", "));
                             eprintln!(
-                                "{} {}",
+                                "{} Using default patterns: {}",
                                 crate::utils::info_prefix(),
-                                format!("Using default patterns: {}", joined)
+                                joined
                             );
                         }
                     }
                 }
             }
             let repo_root_str = eff.repo_root.to_string_lossy().to_string();
-            let (result, errors) = lint::run_lint(
-                &repo_root_str,
-                &eff.index,
-                &eff.scope,
-                &eff.pattern_overrides,
-            );
-            output::print_lint(&result, &eff.output, &errors);
+            let run = if fix && !dry_run {
+                lint::run_lint_fix
+            } else {
+                lint::run_lint
+            };
+            let listed = files_from.as_deref().map(read_file_list);
+            // Baseline entries outside the files and rules linted stay as they are
+            let scope = baseline::Scope::new(&eff.repo_root, listed.as_deref(), &rule);
+            let (result, errors) = match listed.as_deref() {
+                Some(files) => {
+                    note_unmatched_files(&eff, files);
+                    lint::run_lint_files(
+                        &repo_root_str,
+                        &eff.index,
+                        Some(&eff.cache_dir),
+                        &eff.scope,
+                        &eff.pattern_overrides,
+                        eff.max_file_bytes,
+                        eff.stream_arrays,
+                        stats.as_mut(),
+                        fix && !dry_run,
+                        files,
+                        &rule,
+                    )
+                }
+                None => run(
+                    &repo_root_str,
+                    &eff.index,
+                    Some(&eff.cache_dir),
+                    &eff.scope,
+                    &eff.pattern_overrides,
+                    eff.max_file_bytes,
+                    eff.stream_arrays,
+                    stats.as_mut(),
+                    &rule,
+                ),
+            };
+            let result = if fail_on_sync_drift {
+                lint::escalate_sync_drift(result)
+            } else {
+                result
+            };
+            let baseline_path = baseline
+                .map(|p| eff.repo_root.join(p))
+                .or_else(|| baseline_update.then(|| eff.cache_dir.join(baseline::DEFAULT_FILE)));
+            let result = match baseline_path {
+                Some(bp) => {
+                    let mut bl = baseline::load(&bp).unwrap_or_else(|e| {
+                        eprintln!("{} {}", crate::utils::error_prefix(), e);
+                        std::process::exit(2);
+                    });
+                    if baseline_update && !dry_run {
+                        let (next, st) =
+                            baseline::update(&bl, &eff.repo_root, &result.issues, &scope);
+                        if let Err(e) = baseline::save(&bp, &next) {
+                            eprintln!("{} {}", crate::utils::error_prefix(), e);
+                            std::process::exit(2);
+                        }
+                        if bp.starts_with(&eff.cache_dir) {
+                            gitignore_cache(&eff);
+                        }
+                        eprintln!(
+                            "{} Baseline updated: {} added, {} kept, {} pruned ({})",
+                            crate::utils::info_prefix(),
+                            st.added,
+                            st.kept,
+                            st.pruned,
+                            bp.to_string_lossy()
+                        );
+                        bl = next;
+                    }
+                    baseline::filter(result, &eff.repo_root, &bl)
+                }
+                None => result,
+            };
+            let result = match diff_baseline {
+                Some(p) => {
+                    let bl = baseline::load(&eff.repo_root.join(p)).unwrap_or_else(|e| {
+                        eprintln!("{} {}", crate::utils::error_prefix(), e);
+                        std::process::exit(2);
+                    });
+                    baseline::diff(result, &eff.repo_root, &bl, &scope)
+                }
+                None => result,
+            };
+            output::print_lint(&result, &eff.output, &errors, &eff.ui, flat);
+            if let Some(t) = stats.as_ref() {
+                output::print_stats(t);
+            }
             if result.summary.errors > 0 {
-                std::process::exit(1);
+                fail(output::ExitCause::LintErrors(result.summary.errors));
+            }
+            if let Some(max) = max_warnings.filter(|m| result.summary.warnings > *m) {
+                eprintln!(
+                    "{} Warning threshold exceeded: {} warnings (--max-warnings {})",
+                    crate::utils::error_prefix(),
+                    result.summary.warnings,
+                    max
+                );
+                fail(output::ExitCause::TooManyWarnings {
+                    warnings: result.summary.warnings,
+                    max,
+                });
             }
         }
         Commands::Format {
             repo_root,
             write,
             diff,
+            diff_context,
+            // Human output only ever lists changed files
+            changed_only: _,
             check,
             output,
             index,
+            assume_sorted,
+            serial,
+            fix_suggest,
+            emit_order,
+            stdin,
+            stdin_filename,
+            rule,
         } => {
+            if fix_suggest.as_deref().is_some_and(|t| t != "-") {
+                refuse_dry_run(dry_run, "--fix-suggest FILE writes a patch file");
+            }
             let eff = config::resolve_effective(
                 repo_root.as_deref(),
                 index.as_deref(),
@@ -124,38 +267,78 @@ fn main() {
                 if write { Some(true) } else { None },
                 if diff { Some(true) } else { None },
                 if check { Some(true) } else { None },
+                cache_dir.as_deref(),
             );
             if !eff.index_configured {
                 eprintln!(
-                    "{} {}",
-                    crate::utils::error_prefix(),
-                    "Index is not configured. Pass --index or add rigra.toml."
+                    "{} Index is not configured. Pass --index or add rigra.toml.",
+                    crate::utils::error_prefix()
                 );
                 std::process::exit(2);
             }
             if config::load_config(&eff.repo_root).is_none() {
                 eprintln!(
-                    "{} {}",
-                    crate::utils::note_prefix(),
-                    "No rigra.toml found; using defaults."
+                    "{} No rigra.toml found; using defaults.",
+                    crate::utils::note_prefix()
                 );
             }
             let idx_path = eff.repo_root.join(&eff.index);
             if !idx_path.exists() {
                 eprintln!(
-                    "{} {}",
+                    "{} Index file not found: {} (pass --index or configure rigra.toml)",
                     crate::utils::error_prefix(),
-                    format!(
-                        "Index file not found: {} (pass --index or configure rigra.toml)",
-                        idx_path.to_string_lossy()
-                    )
+                    idx_path.to_string_lossy()
                 );
                 std::process::exit(2);
             }
+            // Editor integrations: stdin in, formatted text out, disk untouched
+            if stdin {
+                let mut data = String::new();
+                if let Err(e) = std::io::Read::read_to_string(&mut std::io::stdin(), &mut data) {
+                    eprintln!(
+                        "{} Failed to read stdin: {}",
+                        crate::utils::error_prefix(),
+                        e
+                    );
+                    std::process::exit(2);
+                }
+                let result = format::format_stdin(
+                    &eff.repo_root.to_string_lossy(),
+                    &eff.index,
+                    Some(&eff.cache_dir),
+                    stdin_filename.as_deref(),
+                    &data,
+                    eff.strict_linebreak,
+                    eff.lb_between_groups,
+                    &eff.lb_before_fields,
+                    &eff.lb_in_fields,
+                    &eff.pattern_overrides,
+                )
+                .unwrap_or_else(|e| {
+                    eprintln!("{} {}", crate::utils::error_prefix(), e);
+                    std::process::exit(2);
+                });
+                if eff.check {
+                    if result.changed {
+                        fail(output::ExitCause::FormatChanges(1));
+                    }
+                    return;
+                }
+                let mut text = result.preview.unwrap_or(data);
+                // Keep the buffer's trailing newline for editors that expect it
+                if result.original.is_some_and(|o| o.ends_with('\n')) && !text.ends_with('\n') {
+                    text.push('\n');
+                }
+                print!("{}", text);
+                return;
+            }
+            require_rule_ids(&eff, &rule, true, false);
             // Emit single top info when default patterns from index are used (no overrides in rigra.toml)
-            if eff.output != "json" {
+            if !output::is_json(&eff.output) {
                 if let Ok(s) = fs::read_to_string(&idx_path) {
-                    if let Ok(ix) = toml::from_str::<Index>(&s) {
+                    if let Ok(mut ix) = toml::from_str::<Index>(&s) {
+                        // Include errors surface from the run itself
+                        let _ = ix.resolve_includes(&idx_path);
                         let mut pat_set: std::collections::BTreeSet<String> =
                             std::collections::BTreeSet::new();
                         for r in ix.rules.iter() {
@@ -169,39 +352,78 @@ fn main() {
                             let joined =
                                 format!("[{}]", pat_set.into_iter().collect::<Vec<_>>().join(", "));
                             eprintln!(
-                                "{} {}",
+                                "{} Using default patterns: {}",
                                 crate::utils::info_prefix(),
-                                format!("Using default patterns: {}", joined)
+                                joined
                             );
                         }
                     }
                 }
             }
             // CLI/config precedence at runtime:
-            // - If diff or check is enabled, force write=false for this run.
+            // - If diff, check, fix-suggest, emit-order or --dry-run is enabled, force write=false for this run.
             // - Otherwise respect write.
             let eff_diff = eff.diff;
             let eff_check = eff.check;
-            let eff_write = if eff_diff || eff_check {
-                false
-            } else {
-                eff.write
-            };
+            let eff_write =
+                if eff_diff || eff_check || fix_suggest.is_some() || emit_order || dry_run {
+                    false
+                } else {
+                    eff.write
+                };
             let repo_root_str = eff.repo_root.to_string_lossy().to_string();
             let (results, errors) = format::run_format(
                 &repo_root_str,
                 &eff.index,
+                Some(&eff.cache_dir),
                 eff_write,
-                eff_diff || eff_check,
+                eff_diff || eff_check || fix_suggest.is_some() || emit_order,
                 eff.strict_linebreak,
                 eff.lb_between_groups,
                 &eff.lb_before_fields,
                 &eff.lb_in_fields,
                 &eff.pattern_overrides,
+                eff.max_file_bytes,
+                assume_sorted,
+                serial,
+                stats.as_mut(),
+                &rule,
             );
-            output::print_format(&results, &eff.output, eff_write, eff_diff, &errors);
-            if eff_check && results.iter().any(|r| r.changed) {
-                std::process::exit(1);
+            if let Some(t) = stats.as_ref() {
+                output::print_stats(t);
+            }
+            if emit_order {
+                output::print_format_order(&results, &eff.output, &errors);
+                return;
+            }
+            if let Some(target) = fix_suggest.as_deref() {
+                let patch = output::compose_format_patch(&results, &eff.repo_root, diff_context);
+                if target == "-" {
+                    print!("{}", patch);
+                } else if let Err(e) = fs::write(target, patch) {
+                    eprintln!(
+                        "{} Failed to write patch '{}': {}",
+                        crate::utils::error_prefix(),
+                        target,
+                        e
+                    );
+                    std::process::exit(2);
+                }
+            }
+            // A patch on stdout must stay clean for `git apply`
+            if fix_suggest.as_deref() != Some("-") {
+                output::print_format(
+                    &results,
+                    &eff.output,
+                    eff_write,
+                    eff_diff,
+                    diff_context,
+                    &errors,
+                );
+            }
+            let changed = results.iter().filter(|r| r.changed).count();
+            if eff_check && changed > 0 {
+                fail(output::ExitCause::FormatChanges(changed));
             }
         }
         Commands::Sync {
@@ -210,8 +432,14 @@ fn main() {
             output,
             index,
             write,
-            dry_run,
             check,
+            plan_only,
+            interactive,
+            yes,
+            force,
+            only_changed,
+            restore,
+            rule,
         } => {
             let eff = config::resolve_effective(
                 repo_root.as_deref(),
@@ -221,62 +449,355 @@ fn main() {
                 Some(write),
                 Some(dry_run),
                 Some(check),
+                cache_dir.as_deref(),
             );
+            if restore {
+                refuse_dry_run(dry_run, "--restore rewrites targets");
+                match sync::restore(&eff.repo_root, Some(&eff.cache_dir), &rule) {
+                    Ok(restored) => {
+                        for (id, target) in restored {
+                            println!("restored {} » {}", id, target.to_string_lossy());
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("{} {}", crate::utils::error_prefix(), e);
+                        std::process::exit(2);
+                    }
+                }
+                return;
+            }
             // Require index to be configured and point to a file
             if !eff.index_configured {
                 eprintln!(
-                    "{} {}",
-                    crate::utils::error_prefix(),
-                    "Index is not configured. Pass --index or add rigra.toml."
+                    "{} Index is not configured. Pass --index or add rigra.toml.",
+                    crate::utils::error_prefix()
                 );
                 std::process::exit(2);
             }
             if config::load_config(&eff.repo_root).is_none() {
                 eprintln!(
-                    "{} {}",
-                    crate::utils::note_prefix(),
-                    "No rigra.toml found; using defaults."
+                    "{} No rigra.toml found; using defaults.",
+                    crate::utils::note_prefix()
                 );
             }
             let idx_path = eff.repo_root.join(&eff.index);
             if !idx_path.exists() || !idx_path.is_file() {
                 eprintln!(
-                    "{} {}",
+                    "{} Index file not found: {} (pass --index or configure rigra.toml)",
                     crate::utils::error_prefix(),
-                    format!(
-                        "Index file not found: {} (pass --index or configure rigra.toml)",
-                        idx_path.to_string_lossy()
-                    )
+                    idx_path.to_string_lossy()
                 );
                 std::process::exit(2);
             }
+            require_rule_ids(&eff, &rule, false, true);
             let eff_diff = eff.diff;
             let eff_check = eff.check;
             // Default write from config: [sync].write acts as ergonomics fallback
             let cfg_sync = config::load_config(&eff.repo_root).unwrap_or_default().sync;
             let cfg_sync_write = cfg_sync.as_ref().and_then(|s| s.write).unwrap_or(false);
-            let eff_write = if eff_diff || eff_check {
+            let eff_write = if eff_diff || eff_check || plan_only {
                 false
             } else {
                 // CLI --write takes precedence; otherwise use [sync].write
-                write || cfg_sync_write
+                write || cfg_sync_write || interactive
             };
+            // --yes answers every prompt, so it degrades to a plain write
+            let prompt = interactive && eff_write && !yes;
+            if prompt && !std::io::stdin().is_terminal() {
+                eprintln!(
+                    "{} --interactive requires a terminal; pass --yes to apply without prompts",
+                    crate::utils::error_prefix()
+                );
+                std::process::exit(2);
+            }
             let repo_root_str = eff.repo_root.to_string_lossy().to_string();
-            let (actions, errors) =
-                sync::run_sync(&repo_root_str, &eff.index, &eff.scope, eff_write);
-            output::print_sync(&actions, &eff.output, &errors);
+            let (mut actions, errors) = if prompt {
+                let stdin = std::io::stdin();
+                let mut input = stdin.lock();
+                let mut ask = |a: &sync::SyncAction, diff: Option<&str>| {
+                    if let Some(d) = diff {
+                        eprint!("{}", d);
+                    }
+                    sync::prompt_yes_no(
+                        &mut input,
+                        &mut std::io::stderr(),
+                        &format!("Apply {} → {}?", a.rule_id, a.target),
+                    )
+                };
+                sync::run_sync_interactive(
+                    &repo_root_str,
+                    &eff.index,
+                    Some(&eff.cache_dir),
+                    &eff.scope,
+                    no_ignore,
+                    force || plan_only || only_changed,
+                    &mut ask,
+                    &rule,
+                )
+            } else {
+                sync::run_sync(
+                    &repo_root_str,
+                    &eff.index,
+                    Some(&eff.cache_dir),
+                    &eff.scope,
+                    eff_write,
+                    no_ignore,
+                    force || plan_only || only_changed,
+                    &rule,
+                )
+            };
+            // Write runs record sync state, merge checksums and backups
+            if eff_write {
+                gitignore_cache(&eff);
+            }
+            if only_changed {
+                sync::retain_changed(&mut actions);
+            }
+            let plan = plan_only.then(|| sync::plan_hash(&eff.repo_root, &actions));
+            output::print_sync(&actions, &eff.output, &errors, plan.as_deref());
             // In check mode, exit non-zero when any action would write
-            if eff_check && actions.iter().any(|a| a.would_write) {
-                std::process::exit(1);
+            let pending = actions.iter().filter(|a| a.would_write).count();
+            if eff_check && pending > 0 {
+                fail(output::ExitCause::SyncPending(pending));
+            }
+        }
+        Commands::Merge {
+            source,
+            dest,
+            keep,
+            override_paths,
+            array,
+            write,
+        } => {
+            let mut strategies = std::collections::HashMap::new();
+            for a in array {
+                match a.split_once('=') {
+                    Some((p, s)) if s == "union" || s == "replace" => {
+                        strategies.insert(p.to_string(), s.to_string());
+                    }
+                    _ => {
+                        eprintln!(
+                            "{} Invalid --array '{}': expected PATH=union|replace",
+                            crate::utils::error_prefix(),
+                            a
+                        );
+                        std::process::exit(2);
+                    }
+                }
+            }
+            let mcfg = config::SyncClientMergeCfg {
+                keep_paths: keep,
+                override_paths,
+                nosync_paths: Vec::new(),
+                array: if strategies.is_empty() {
+                    None
+                } else {
+                    Some(strategies)
+                },
+            };
+            let dest_path = std::path::Path::new(&dest);
+            match sync::merge_files(std::path::Path::new(&source), dest_path, &mcfg) {
+                Ok(out) => {
+                    if write && !dry_run {
+                        if let Err(e) = fs::write(dest_path, &out) {
+                            eprintln!(
+                                "{} Failed to write merged file '{}': {}",
+                                crate::utils::error_prefix(),
+                                dest,
+                                e
+                            );
+                            std::process::exit(2);
+                        }
+                        println!("merged: {}", dest);
+                    } else {
+                        println!("{}", out);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("{} {}", crate::utils::error_prefix(), e);
+                    std::process::exit(2);
+                }
             }
         }
+        Commands::DiffPolicy { old, new, output } => {
+            let load = |p: &str| {
+                policy_diff::load(std::path::Path::new(p)).unwrap_or_else(|e| {
+                    eprintln!("{} {}", crate::utils::error_prefix(), e);
+                    std::process::exit(2);
+                })
+            };
+            let d = policy_diff::diff(&load(&old), &load(&new));
+            output::print_policy_diff(&d, output.as_deref().unwrap_or("human"));
+        }
+        Commands::Policy { cmd } => match cmd {
+            cli::PolicyCmd::Fmt { path, write, check } => {
+                if crate::utils::is_json5(std::path::Path::new(&path)) {
+                    eprintln!(
+                        "{} policy fmt only formats TOML policies: {}",
+                        crate::utils::error_prefix(),
+                        path
+                    );
+                    std::process::exit(2);
+                }
+                let src = fs::read_to_string(&path).unwrap_or_else(|e| {
+                    eprintln!(
+                        "{} Failed to read policy '{}': {}",
+                        crate::utils::error_prefix(),
+                        path,
+                        e
+                    );
+                    std::process::exit(2);
+                });
+                let out = policy_fmt::format_policy(&src).unwrap_or_else(|e| {
+                    eprintln!("{} {} — {}", crate::utils::error_prefix(), e, path);
+                    std::process::exit(2);
+                });
+                let changed = out != src;
+                if check {
+                    if changed {
+                        eprintln!(
+                            "{} {} is not in canonical layout",
+                            crate::utils::info_prefix(),
+                            path
+                        );
+                        fail(output::ExitCause::FormatChanges(1));
+                    }
+                } else if write && !dry_run {
+                    if changed {
+                        if let Err(e) = fs::write(&path, out) {
+                            eprintln!(
+                                "{} Failed to write policy '{}': {}",
+                                crate::utils::error_prefix(),
+                                path,
+                                e
+                            );
+                            std::process::exit(2);
+                        }
+                    }
+                } else {
+                    print!("{}", out);
+                }
+            }
+        },
+        Commands::Explain {
+            rule,
+            file,
+            repo_root,
+            index,
+            output,
+        } => {
+            let eff = config::resolve_effective(
+                repo_root.as_deref(),
+                index.as_deref(),
+                None,
+                output.as_deref(),
+                None,
+                None,
+                None,
+                cache_dir.as_deref(),
+            );
+            if !eff.index_configured {
+                eprintln!(
+                    "{} Index is not configured. Pass --index or add rigra.toml.",
+                    crate::utils::error_prefix()
+                );
+                std::process::exit(2);
+            }
+            let rules = explain::load(
+                &eff.repo_root,
+                &eff.index,
+                &eff.cache_dir,
+                &eff.pattern_overrides,
+            )
+            .unwrap_or_else(|e| {
+                eprintln!("{} {}", crate::utils::error_prefix(), e);
+                std::process::exit(2);
+            });
+            let (rules, subject) = match (&rule, &file) {
+                (_, Some(f)) => (explain::matching_file(rules, &eff.repo_root, f), f),
+                (Some(id), None) => {
+                    let found: Vec<_> = rules.into_iter().filter(|r| &r.rule == id).collect();
+                    if found.is_empty() {
+                        eprintln!(
+                            "{} Unknown rule id '{}' in {}",
+                            crate::utils::error_prefix(),
+                            id,
+                            eff.index
+                        );
+                        std::process::exit(2);
+                    }
+                    (found, id)
+                }
+                (None, None) => unreachable!("clap requires a rule id or --file"),
+            };
+            output::print_explain(&rules, subject, &eff.output);
+        }
+        Commands::Doctor {
+            repo_root,
+            index,
+            output,
+        } => {
+            let eff = config::resolve_effective(
+                repo_root.as_deref(),
+                index.as_deref(),
+                None,
+                output.as_deref(),
+                None,
+                None,
+                None,
+                cache_dir.as_deref(),
+            );
+            if !eff.index_configured {
+                eprintln!(
+                    "{} Index is not configured. Pass --index or add rigra.toml.",
+                    crate::utils::error_prefix()
+                );
+                std::process::exit(2);
+            }
+            let problems =
+                doctor::run(&eff.repo_root, &eff.index, &eff.cache_dir).unwrap_or_else(|e| {
+                    eprintln!("{} {}", crate::utils::error_prefix(), e);
+                    std::process::exit(2);
+                });
+            output::print_doctor(&problems, &eff.output);
+            if !problems.is_empty() {
+                fail(output::ExitCause::PolicyProblems(problems.len()));
+            }
+        }
+        Commands::Bench {
+            repo_root,
+            index,
+            scope,
+            iterations,
+        } => {
+            let eff = config::resolve_effective(
+                repo_root.as_deref(),
+                index.as_deref(),
+                scope.as_deref(),
+                None,
+                None,
+                None,
+                None,
+                cache_dir.as_deref(),
+            );
+            if !eff.index_configured {
+                eprintln!(
+                    "{} Index is not configured. Pass --index or add rigra.toml.",
+                    crate::utils::error_prefix()
+                );
+                std::process::exit(2);
+            }
+            output::print_bench(&bench::run_bench(&eff, iterations));
+        }
         Commands::Conv { cmd } => {
             match cmd {
                 cli::ConvCmd::Install {
                     repo_root,
                     source,
                     name,
+                    into,
                 } => {
+                    refuse_dry_run(dry_run, "conv install downloads into the cache");
                     let eff = config::resolve_effective(
                         repo_root.as_deref(),
                         None,
@@ -285,6 +806,7 @@ fn main() {
                         None,
                         None,
                         None,
+                        cache_dir.as_deref(),
                     );
                     // Prefer CLI overrides; otherwise pull from rigra.toml [conv]
                     let cfg = config::load_config(&eff.repo_root).unwrap_or_default();
@@ -309,58 +831,55 @@ fn main() {
                             } => format!("{}@{}", repo, tag),
                             _ => {
                                 eprintln!(
-                                    "{} {}",
-                                    crate::utils::error_prefix(),
-                                    "--name is required when using file: source without [conv.package]"
+                                    "{} --name is required when using file: source without [conv.package]",
+                                    crate::utils::error_prefix()
                                 );
                                 std::process::exit(2);
                             }
                         }
                     } else {
                         eprintln!(
-                            "{} {}",
-                            crate::utils::error_prefix(),
-                            "missing install context: set [conv.package] in rigra.toml or pass --name"
+                            "{} missing install context: set [conv.package] in rigra.toml or pass --name",
+                            crate::utils::error_prefix()
                         );
                         std::process::exit(2);
                     };
 
-                    // Determine source string
-                    let src_str = if let Some(s) = source {
-                        s
-                    } else if let Some(s) = cfg_conv.and_then(|c| c.source.clone()) {
-                        s
-                    } else {
+                    // Determine sources: CLI override, else [conv] source + sources
+                    let sources = match source {
+                        Some(s) => vec![s],
+                        None => cfg_conv.map(config::conv_sources).unwrap_or_default(),
+                    };
+                    if sources.is_empty() {
                         eprintln!(
-                            "{} {}",
-                            crate::utils::error_prefix(),
-                            "missing source: set [conv.source] in rigra.toml or pass --source"
+                            "{} missing source: set [conv.source] in rigra.toml or pass --source",
+                            crate::utils::error_prefix()
                         );
                         std::process::exit(2);
-                    };
+                    }
                     // If shorthand "github" is used, derive gh:owner/repo@tag from package
-                    let src_str = if src_str == "github" {
-                        if let Some((name, ver)) = crate::config::rsplit_once_at(&name_ver, '@') {
-                            if let Some((owner, repo)) = crate::config::package_owner_repo(name) {
-                                format!("gh:{}/{}@{}", owner, repo, ver)
-                            } else {
-                                src_str
-                            }
-                        } else {
-                            src_str
-                        }
-                    } else {
-                        src_str
-                    };
+                    let sources: Vec<String> = sources
+                        .iter()
+                        .map(|s| config::expand_github_source(s, &name_ver))
+                        .collect();
 
-                    match conv::install(&eff.repo_root, &name_ver, &src_str) {
-                        Ok(path) => println!("installed: {}", path.to_string_lossy()),
+                    // --into wins over [conv].cacheDir
+                    let cache = match into.as_deref() {
+                        Some(dir) => eff.repo_root.join(dir),
+                        None => conv::cache_root(&eff.repo_root, &eff.cache_dir),
+                    };
+                    // The index the install must provide ([conv].subpath)
+                    let subpath = cfg_conv
+                        .and_then(|c| c.subpath.clone())
+                        .unwrap_or_else(|| "index.toml".to_string());
+                    match conv::install_from(&eff.cache_dir, &cache, &name_ver, &subpath, &sources)
+                    {
+                        Ok(path) => {
+                            gitignore_cache(&eff);
+                            println!("installed: {}", path.to_string_lossy())
+                        }
                         Err(e) => {
-                            eprintln!(
-                                "{} {}",
-                                crate::utils::error_prefix(),
-                                format!("install failed: {}", e)
-                            );
+                            eprintln!("{} install failed: {}", crate::utils::error_prefix(), e);
                             std::process::exit(2);
                         }
                     }
@@ -374,12 +893,14 @@ fn main() {
                         None,
                         None,
                         None,
+                        cache_dir.as_deref(),
                     );
-                    for it in conv::list(&eff.repo_root) {
+                    for it in conv::list(&conv::cache_root(&eff.repo_root, &eff.cache_dir)) {
                         println!("{}", it);
                     }
                 }
                 cli::ConvCmd::Prune { repo_root } => {
+                    refuse_dry_run(dry_run, "conv prune deletes cached conventions");
                     let eff = config::resolve_effective(
                         repo_root.as_deref(),
                         None,
@@ -388,18 +909,54 @@ fn main() {
                         None,
                         None,
                         None,
+                        cache_dir.as_deref(),
                     );
-                    if let Err(e) = conv::prune(&eff.repo_root) {
-                        eprintln!(
-                            "{} {}",
-                            crate::utils::error_prefix(),
-                            format!("prune failed: {}", e)
-                        );
+                    if let Err(e) = conv::prune(&conv::cache_root(&eff.repo_root, &eff.cache_dir)) {
+                        eprintln!("{} prune failed: {}", crate::utils::error_prefix(), e);
                         std::process::exit(2);
                     } else {
                         println!("pruned");
                     }
                 }
+                cli::ConvCmd::Indexes {
+                    repo_root,
+                    conv: conv_str,
+                } => {
+                    let eff = config::resolve_effective(
+                        repo_root.as_deref(),
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        cache_dir.as_deref(),
+                    );
+                    let Some(cr) = conv::parse_conv_ref(&conv_str) else {
+                        eprintln!("{} invalid conv string", crate::utils::error_prefix());
+                        std::process::exit(2);
+                    };
+                    let dir = conv::dir_subpath(&cr).to_string();
+                    let dir_ref = conv::ConvRef {
+                        subpath: dir.clone(),
+                        ..cr.clone()
+                    };
+                    let path = conv::resolve_path(
+                        &conv::cache_root(&eff.repo_root, &eff.cache_dir),
+                        &dir_ref,
+                    );
+                    match conv::list_indexes(&path) {
+                        Ok(found) => {
+                            for rel in found {
+                                println!("conv:{}@{}:{}{}", cr.name, cr.ver, dir, rel);
+                            }
+                        }
+                        Err(e) => {
+                            eprintln!("{} {}", crate::utils::error_prefix(), e);
+                            std::process::exit(2);
+                        }
+                    }
+                }
                 cli::ConvCmd::Path {
                     repo_root,
                     conv: conv_str,
@@ -412,12 +969,16 @@ fn main() {
                         None,
                         None,
                         None,
+                        cache_dir.as_deref(),
                     );
                     if let Some(cr) = conv::parse_conv_ref(&conv_str) {
-                        let p = conv::resolve_path(&eff.repo_root, &cr);
+                        let p = conv::resolve_path(
+                            &conv::cache_root(&eff.repo_root, &eff.cache_dir),
+                            &cr,
+                        );
                         println!("{}", p.to_string_lossy());
                     } else {
-                        eprintln!("{} {}", crate::utils::error_prefix(), "invalid conv string");
+                        eprintln!("{} invalid conv string", crate::utils::error_prefix());
                         std::process::exit(2);
                     }
                 }
@@ -425,3 +986,134 @@ fn main() {
         }
     }
 }
+
+/// Exit 2 when `--dry-run` is set for an action that exists only to write,
+/// e.g. "--restore rewrites targets".
+fn refuse_dry_run(dry_run: bool, action: &str) {
+    if dry_run {
+        eprintln!(
+            "{} {} and cannot run with --dry-run",
+            crate::utils::error_prefix(),
+            action
+        );
+        std::process::exit(2);
+    }
+}
+
+/// Keep the cache dir out of git once rigra has written under it, unless
+/// `[cache].gitignore = false`. Call only after such a write. Failures only
+/// warn.
+fn gitignore_cache(eff: &config::Effective) {
+    if !eff.gitignore_cache {
+        return;
+    }
+    match config::ensure_gitignored(&eff.repo_root, &eff.cache_dir) {
+        Ok(true) => eprintln!(
+            "{} Added {} to .gitignore",
+            crate::utils::info_prefix(),
+            eff.cache_dir
+                .strip_prefix(&eff.repo_root)
+                .unwrap_or(&eff.cache_dir)
+                .to_string_lossy()
+        ),
+        Ok(false) => {}
+        Err(e) => eprintln!("{} {}", crate::utils::warn_prefix(), e),
+    }
+}
+
+/// Exit 2 unless every `--rule` id names an index rule (with `lint_rules`)
+/// or a sync rule (with `sync_rules`) of the configured index.
+fn require_rule_ids(eff: &config::Effective, ids: &[String], lint_rules: bool, sync_rules: bool) {
+    if ids.is_empty() {
+        return;
+    }
+    let idx_path = eff.repo_root.join(&eff.index);
+    let available = fs::read_to_string(&idx_path)
+        .map_err(|e| {
+            format!(
+                "Failed to read index: {} — {}",
+                idx_path.to_string_lossy(),
+                e
+            )
+        })
+        .and_then(|s| {
+            toml::from_str::<Index>(&s).map_err(|e| {
+                format!(
+                    "Failed to parse index TOML: {} — {}",
+                    idx_path.to_string_lossy(),
+                    e
+                )
+            })
+        })
+        .and_then(|mut ix| {
+            ix.resolve_includes(&idx_path)?;
+            let mut out: Vec<String> = Vec::new();
+            if lint_rules {
+                out.extend(ix.rules.iter().map(|r| r.id.clone()));
+            }
+            if sync_rules && (ix.sync_ref.is_some() || !ix.included_sync.is_empty()) {
+                match sync::sync_rule_ids(&idx_path, &ix) {
+                    Ok(found) => out.extend(found),
+                    // Lint ignores unreadable sync policies; sync reports them
+                    Err(_) if lint_rules => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            Ok(out)
+        });
+    if let Err(e) = available.and_then(|a| models::index::check_rule_ids(ids, &a)) {
+        eprintln!("{} {}", crate::utils::error_prefix(), e);
+        std::process::exit(2);
+    }
+}
+
+/// Read the newline-separated paths for `lint --files-from` from `src`
+/// (`-` for stdin), skipping blank lines. Exits 2 when unreadable.
+fn read_file_list(src: &str) -> Vec<String> {
+    let text = if src == "-" {
+        let mut s = String::new();
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut s).map(|_| s)
+    } else {
+        fs::read_to_string(src)
+    };
+    let text = text.unwrap_or_else(|e| {
+        eprintln!(
+            "{} Failed to read file list '{}': {}",
+            crate::utils::error_prefix(),
+            src,
+            e
+        );
+        std::process::exit(2);
+    });
+    text.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Note each listed file no index rule matches, since lint skips it.
+fn note_unmatched_files(eff: &config::Effective, files: &[String]) {
+    let Ok(rules) = explain::load(
+        &eff.repo_root,
+        &eff.index,
+        &eff.cache_dir,
+        &eff.pattern_overrides,
+    ) else {
+        // Index errors surface from the lint run itself
+        return;
+    };
+    for f in files {
+        let rel = explain::relative_to(&eff.repo_root, std::path::Path::new(f));
+        if !rules
+            .iter()
+            .any(|r| explain::patterns_match(&r.patterns, &rel))
+        {
+            eprintln!(
+                "{} No rule matches '{}'; not linted",
+                crate::utils::note_prefix(),
+                f
+            );
+        }
+    }
+}
@@ -1,6 +1,13 @@
 //! Index schema: lists rules for lint/format targets and sync operations.
+//!
+//! An index may `include` other index files (paths relative to itself).
+//! Included rules and sync policies are merged after the including index's
+//! own, with their relative paths rebased onto the including index.
 
 use serde::Deserialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 #[derive(Deserialize)]
 /// Top-level index configuration.
@@ -10,6 +17,12 @@ pub struct Index {
     /// External sync policy file path relative to this index
     #[serde(default, rename = "sync")]
     pub sync_ref: Option<String>,
+    /// Other index files merged into this one, relative to this index
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Sync policies inherited through `include` (filled by `resolve_includes`)
+    #[serde(skip)]
+    pub included_sync: Vec<IncludedSync>,
 }
 
 #[derive(Deserialize)]
@@ -18,6 +31,197 @@ pub struct RuleIndex {
     pub id: String,
     pub patterns: Vec<String>,
     pub policy: String,
+    /// Target format: json|json5|yaml|toml (default: by extension); format
+    /// rewrites json and yaml only
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+/// A sync policy reached through `include`. Both paths are relative to the
+/// root index's directory; `base` is the directory its sources resolve from.
+pub struct IncludedSync {
+    pub policy: PathBuf,
+    pub base: PathBuf,
 }
 
 // Sync rules are now defined in external policy files
+
+impl Index {
+    /// Keep only the rules whose id is in `ids`; empty `ids` keeps them all.
+    pub fn retain_rules(&mut self, ids: &[String]) {
+        if !ids.is_empty() {
+            self.rules.retain(|r| ids.contains(&r.id));
+        }
+    }
+
+    /// Merge every index reachable through `include` into `self`, which was
+    /// loaded from `idx_path`. Errors on unreadable includes, cycles, and
+    /// rule ids defined more than once. An index included twice through
+    /// different paths is merged once.
+    pub fn resolve_includes(&mut self, idx_path: &Path) -> Result<(), String> {
+        let mut stack = vec![canonical(idx_path)];
+        let mut seen: HashSet<PathBuf> = stack.iter().cloned().collect();
+        let mut ids: HashSet<String> = HashSet::new();
+        for r in &self.rules {
+            if !ids.insert(r.id.clone()) {
+                return Err(format!("Duplicate rule id '{}' in index", r.id));
+            }
+        }
+        let dir = idx_path.parent().unwrap_or_else(|| Path::new("."));
+        let includes = std::mem::take(&mut self.include);
+        for inc in includes {
+            self.merge_include(dir, Path::new(""), &inc, &mut stack, &mut seen, &mut ids)?;
+        }
+        Ok(())
+    }
+
+    fn merge_include(
+        &mut self,
+        root_dir: &Path,
+        rel_dir: &Path,
+        inc: &str,
+        stack: &mut Vec<PathBuf>,
+        seen: &mut HashSet<PathBuf>,
+        ids: &mut HashSet<String>,
+    ) -> Result<(), String> {
+        let rel_path = rel_dir.join(inc);
+        let path = root_dir.join(&rel_path);
+        let key = canonical(&path);
+        if stack.contains(&key) {
+            return Err(format!(
+                "Index include cycle through {}",
+                path.to_string_lossy()
+            ));
+        }
+        if !seen.insert(key.clone()) {
+            return Ok(());
+        }
+        let s = fs::read_to_string(&path).map_err(|e| {
+            format!(
+                "Failed to read included index: {} — {}",
+                path.to_string_lossy(),
+                e
+            )
+        })?;
+        let child: Index = toml::from_str(&s).map_err(|e| {
+            format!(
+                "Failed to parse included index: {} — {}",
+                path.to_string_lossy(),
+                e
+            )
+        })?;
+        let child_dir = rel_path.parent().unwrap_or_else(|| Path::new(""));
+        for mut r in child.rules {
+            if !ids.insert(r.id.clone()) {
+                return Err(format!(
+                    "Duplicate rule id '{}' in included index {}",
+                    r.id,
+                    path.to_string_lossy()
+                ));
+            }
+            if crate::conv::parse_conv_ref(&r.policy).is_none() {
+                r.policy = child_dir.join(&r.policy).to_string_lossy().to_string();
+            }
+            self.rules.push(r);
+        }
+        if let Some(sr) = child.sync_ref {
+            self.included_sync.push(IncludedSync {
+                policy: child_dir.join(sr),
+                base: child_dir.to_path_buf(),
+            });
+        }
+        stack.push(key);
+        for next in child.include {
+            self.merge_include(root_dir, child_dir, &next, stack, seen, ids)?;
+        }
+        stack.pop();
+        Ok(())
+    }
+}
+
+fn canonical(p: &Path) -> PathBuf {
+    fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf())
+}
+
+/// Check that every id in `wanted` is one of `available`, naming the
+/// available ids when one is not.
+pub fn check_rule_ids(wanted: &[String], available: &[String]) -> Result<(), String> {
+    let unknown: Vec<String> = wanted
+        .iter()
+        .filter(|id| !available.contains(id))
+        .map(|id| format!("'{}'", id))
+        .collect();
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    Err(format!(
+        "Unknown rule {}. Available rules: {}",
+        unknown.join(", "),
+        if available.is_empty() {
+            "(none)".to_string()
+        } else {
+            available.join(", ")
+        }
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(path: &Path) -> Result<Index, String> {
+        let mut ix: Index = toml::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+        ix.resolve_includes(path).map(|_| ix)
+    }
+
+    #[test]
+    fn test_include_rebases_paths_and_detects_errors() {
+        let td = tempfile::tempdir().unwrap();
+        let d = td.path();
+        fs::create_dir_all(d.join("base")).unwrap();
+        fs::write(
+            d.join("index.toml"),
+            "include = [\"base/index.toml\"]\n[[rules]]\nid = \"a\"\npatterns = [\"a.json\"]\npolicy = \"a.toml\"\n",
+        )
+        .unwrap();
+        fs::write(
+            d.join("base/index.toml"),
+            "sync = \"sync.toml\"\n[[rules]]\nid = \"b\"\npatterns = [\"b.json\"]\npolicy = \"b.toml\"\n",
+        )
+        .unwrap();
+        let ix = load(&d.join("index.toml")).unwrap();
+        let got: Vec<_> = ix
+            .rules
+            .iter()
+            .map(|r| (r.id.as_str(), r.policy.as_str()))
+            .collect();
+        assert_eq!(got, vec![("a", "a.toml"), ("b", "base/b.toml")]);
+        assert_eq!(ix.included_sync.len(), 1);
+        assert_eq!(ix.included_sync[0].policy, Path::new("base/sync.toml"));
+
+        // Duplicate id across files
+        fs::write(
+            d.join("base/index.toml"),
+            "[[rules]]\nid = \"a\"\npatterns = [\"b.json\"]\npolicy = \"b.toml\"\n",
+        )
+        .unwrap();
+        let err = load(&d.join("index.toml")).err().unwrap();
+        assert!(err.contains("Duplicate rule id 'a'"), "{}", err);
+
+        // Cycle back to the root
+        fs::write(d.join("base/index.toml"), "include = [\"../index.toml\"]\n").unwrap();
+        let err = load(&d.join("index.toml")).err().unwrap();
+        assert!(err.contains("include cycle"), "{}", err);
+    }
+
+    #[test]
+    fn test_check_rule_ids_names_unknown_and_available() {
+        let avail = vec!["a".to_string(), "b".to_string()];
+        assert!(check_rule_ids(&["b".to_string()], &avail).is_ok());
+        let err = check_rule_ids(&["x".to_string(), "a".to_string(), "y".to_string()], &avail)
+            .unwrap_err();
+        assert_eq!(err, "Unknown rule 'x', 'y'. Available rules: a, b");
+        let err = check_rule_ids(&["x".to_string()], &[]).unwrap_err();
+        assert_eq!(err, "Unknown rule 'x'. Available rules: (none)");
+    }
+}
@@ -14,6 +14,12 @@ pub struct Issue {
     pub severity: String,
     pub path: String,
     pub message: String,
+    /// 1-based source line of `path`, when the target text was scanned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    /// 1-based source column of `path`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub col: Option<usize>,
 }
 
 #[derive(Serialize)]
@@ -23,6 +29,9 @@ pub struct Summary {
     pub warnings: usize,
     pub infos: usize,
     pub files: usize,
+    /// Number of issues resolved by `lint --fix`; absent without it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fixed: Option<usize>,
 }
 
 #[derive(Serialize)]
@@ -30,4 +39,23 @@ pub struct Summary {
 pub struct LintResult {
     pub issues: Vec<Issue>,
     pub summary: Summary,
+    /// Issues resolved by `lint --fix` (already excluded from `issues`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fixed: Vec<Issue>,
+    /// Baseline entries that no longer occur (`lint --diff-baseline`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub resolved: Vec<crate::baseline::BaselineEntry>,
+}
+
+#[derive(Debug, Clone)]
+/// Wall-clock time spent on one rule (glob expansion + checks) for `--stats`.
+pub struct RuleTiming {
+    pub rule: String,
+    pub elapsed: std::time::Duration,
+}
+
+#[derive(Serialize, Debug, Clone)]
+/// Non-fatal runtime error collected during a run (I/O, parse, write failures).
+pub struct RunError {
+    pub message: String,
 }
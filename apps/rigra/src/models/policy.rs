@@ -1,17 +1,29 @@
 //! Policy schema used by lint and format passes.
 //!
 //! Key components:
-//! - `order`: Declares top-level key groups and optional sub-orders, plus
-//!   lint `message` and `level` (info|warn|error).
+//! - `order`: Declares top-level key groups and optional sub-orders for
+//!   nested objects, plus lint `message` and `level` (info|warn|error).
+//!   `collation` (byte|unicode) sorts the remaining unlisted keys at every
+//!   level; at the top they go at a `"*"` entry in `top` or after the last
+//!   group. `rest = "preserve"` keeps them in their original relative order
+//!   instead. `sortArrayBy` sorts arrays of objects by a field.
 //! - `linebreak`: Controls line breaks between top-level groups and inside
 //!   specific object fields via `before_fields` and `in_fields` maps.
+//! - `defaultLevel`: Severity for checks, order, and formatting issues that
+//!   omit their own `level` (defaults to error).
 //! - `checks`: Validation rules (required/type/const/pattern/enum/length...).
+//! - `formatting`: Opt-in lint precondition that flags files `rigra format`
+//!   would change, with its own `message` and `level`.
+//!
+//! Policies are TOML by default; files ending in `.json5` are parsed as JSON5
+//! into the same structures.
 //!
 //! All identifiers and comments are documented in English.
 
 use serde::Deserialize;
 use serde_json::Value as Json;
 use std::collections::HashMap;
+use std::path::Path;
 
 #[derive(Deserialize)]
 /// Root policy loaded from TOML files referenced by the index.
@@ -22,6 +34,33 @@ pub struct Policy {
     pub order: Option<OrderSpec>,
     #[serde(default)]
     pub linebreak: Option<LineBreakSpec>,
+    #[serde(default)]
+    pub formatting: Option<FormattingSpec>,
+    #[serde(default, rename = "defaultLevel")]
+    pub default_level: Option<String>,
+}
+
+impl Policy {
+    /// Parse policy text, using JSON5 for `*.json5` paths and TOML otherwise.
+    pub fn parse(path: &Path, s: &str) -> Result<Policy, String> {
+        if crate::utils::is_json5(path) {
+            crate::json5::from_str(s)
+        } else {
+            toml::from_str(s).map_err(|e| e.to_string())
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+/// Lint-time formatting precondition.
+pub struct FormattingSpec {
+    /// Flag files whose content differs from the formatter's output
+    #[serde(default)]
+    pub check: bool,
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub level: Option<String>, // info|warn|error (default error)
 }
 
 #[derive(Deserialize, Clone)]
@@ -29,12 +68,91 @@ pub struct Policy {
 pub struct OrderSpec {
     #[serde(default)]
     pub top: Vec<Vec<String>>,
+    /// Path of a nested object (`repository`, `jest.coverageThreshold`) to
+    /// the order of its own keys; unlisted keys follow as in `top`
     #[serde(default)]
     pub sub: HashMap<String, Vec<String>>,
     #[serde(default)]
     pub message: Option<String>,
     #[serde(default)]
     pub level: Option<String>, // info|warn|error (treated as error for exit code when 'error')
+    #[serde(default)]
+    pub collation: Collation,
+    #[serde(default)]
+    pub rest: RestOrder,
+    /// Path of an array of objects (`$.contributors`) to the field whose
+    /// string value orders its elements; elements without it go last
+    #[serde(default, rename = "sortArrayBy", alias = "sort_array_by")]
+    pub sort_array_by: HashMap<String, String>,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+/// Placement of keys not listed in `order`; shared by format and lint.
+pub enum RestOrder {
+    /// Sorted by `collation`
+    #[default]
+    Sort,
+    /// In their original relative order
+    Preserve,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+/// Sort order for keys not listed in `order`; shared by format and lint.
+pub enum Collation {
+    /// Byte order of the UTF-8 key (`Zebra` < `apple` < `école`)
+    #[default]
+    Byte,
+    /// Case- and accent-insensitive first (`apple` < `école` < `Zebra`),
+    /// then accents, case and bytes break ties
+    Unicode,
+}
+
+impl Collation {
+    pub fn cmp(self, a: &str, b: &str) -> std::cmp::Ordering {
+        match self {
+            Collation::Byte => a.cmp(b),
+            Collation::Unicode => {
+                let lower = |s| str::chars(s).flat_map(char::to_lowercase);
+                lower(a)
+                    .map(base_letter)
+                    .cmp(lower(b).map(base_letter))
+                    .then_with(|| lower(a).cmp(lower(b)))
+                    .then_with(|| a.cmp(b))
+            }
+        }
+    }
+
+    pub fn sort<S: AsRef<str>>(self, keys: &mut [S]) {
+        keys.sort_by(|a, b| self.cmp(a.as_ref(), b.as_ref()));
+    }
+}
+
+/// Strip the diacritic from a lowercase Latin letter (`é` → `e`).
+fn base_letter(c: char) -> char {
+    match c {
+        'à'..='å' | 'ā' | 'ă' | 'ą' => 'a',
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => 'c',
+        'ď' | 'đ' => 'd',
+        'è'..='ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => 'e',
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => 'g',
+        'ĥ' | 'ħ' => 'h',
+        'ì'..='ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => 'i',
+        'ĵ' => 'j',
+        'ķ' => 'k',
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => 'l',
+        'ñ' | 'ń' | 'ņ' | 'ň' => 'n',
+        'ò'..='ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => 'o',
+        'ŕ' | 'ŗ' | 'ř' => 'r',
+        'ś' | 'ŝ' | 'ş' | 'š' => 's',
+        'ţ' | 'ť' | 'ŧ' => 't',
+        'ù'..='ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => 'u',
+        'ŵ' => 'w',
+        'ý' | 'ÿ' | 'ŷ' => 'y',
+        'ź' | 'ż' | 'ž' => 'z',
+        _ => c,
+    }
 }
 
 #[derive(Deserialize, Clone)]
@@ -62,6 +180,22 @@ pub enum LineBreakRule {
 pub enum Check {
     #[serde(rename = "required")]
     Required {
+        fields: Vec<String>,
+        /// When set, each field is a key name that must appear at any depth
+        /// below this path instead of at an exact location
+        #[serde(default, rename = "anyDescendant")]
+        any_descendant: Option<String>,
+        /// Report every missing field in one issue at `$` instead of one
+        /// issue per field
+        #[serde(default)]
+        aggregate: bool,
+        message: Option<String>,
+        #[serde(default)]
+        level: Option<String>,
+    },
+    /// Passes when at least one of the listed paths is present
+    #[serde(rename = "requiredOneOf")]
+    RequiredOneOf {
         fields: Vec<String>,
         message: Option<String>,
         #[serde(default)]
@@ -70,7 +204,9 @@ pub enum Check {
     #[serde(rename = "type")]
     Type {
         #[serde(default)]
-        /// Map of JSON paths to expected kinds (string|number|integer|boolean|array|object|null)
+        /// Map of JSON paths to expected kinds (string|number|integer|boolean|array|object|null).
+        /// A trailing `[]` (e.g. `$.keywords[]`) asserts the kind of every array element.
+        /// Mismatches that quoting or unquoting would fix carry a "did you mean" hint.
         fields: HashMap<String, String>,
         message: Option<String>,
         #[serde(default)]
@@ -80,14 +216,54 @@ pub enum Check {
     Const {
         field: String,
         value: Json,
+        /// Replace `${VAR}` in string parts of `value` from the environment
+        /// at lint time; unset variables are left as written
+        #[serde(default)]
+        substitute: bool,
+        /// When `value` is a number, parse a numeric string field (`"1.0"`)
+        /// and compare numerically
+        #[serde(default, rename = "coerceStrings")]
+        coerce_strings: bool,
         message: Option<String>,
         #[serde(default)]
         level: Option<String>,
     },
+    /// Regex over a string field; `field` may end in `.*` (each object
+    /// value) or `[]` (each array element)
     #[serde(rename = "pattern")]
     Pattern {
         field: String,
         regex: String,
+        /// Require the regex to match the whole string, not a substring
+        #[serde(default, rename = "fullMatch")]
+        full_match: bool,
+        /// Named groups of `regex` that must capture non-empty text; each
+        /// empty one is reported (`{{group}}` in `message`)
+        #[serde(default)]
+        captures: Vec<String>,
+        message: Option<String>,
+        #[serde(default)]
+        level: Option<String>,
+    },
+    /// Inverse of `pattern`: the string field must not match `regex`
+    /// (e.g. no `-dev` versions); takes the same `.*`/`[]` field forms
+    #[serde(rename = "notPattern")]
+    NotPattern {
+        field: String,
+        regex: String,
+        message: Option<String>,
+        #[serde(default)]
+        level: Option<String>,
+    },
+    /// Passes when the value matches any `allow` regex (skipped when
+    /// `allow` is empty) and no `deny` regex; same field forms as `pattern`
+    #[serde(rename = "patternSet")]
+    PatternSet {
+        field: String,
+        #[serde(default)]
+        allow: Vec<String>,
+        #[serde(default)]
+        deny: Vec<String>,
         message: Option<String>,
         #[serde(default)]
         level: Option<String>,
@@ -100,10 +276,16 @@ pub enum Check {
         #[serde(default)]
         level: Option<String>,
     },
+    /// `minLength`/`maxLength` messages may use `{{expected}}`, `{{actual}}`
+    /// (the length), `{{path}}` and `{{excerpt}}` (the quoted value,
+    /// shortened around an ellipsis)
     #[serde(rename = "minLength")]
     MinLength {
         field: String,
         min: usize,
+        /// Count numbers by their canonical string form (`12345` has length 5)
+        #[serde(default, rename = "coerceNumber")]
+        coerce_number: bool,
         message: Option<String>,
         #[serde(default)]
         level: Option<String>,
@@ -112,8 +294,350 @@ pub enum Check {
     MaxLength {
         field: String,
         max: usize,
+        /// Count numbers by their canonical string form (`12345` has length 5)
+        #[serde(default, rename = "coerceNumber")]
+        coerce_number: bool,
         message: Option<String>,
         #[serde(default)]
         level: Option<String>,
     },
+    /// Validates a semver range (`^1.2.3`, `>=2 <3`, `1.x || 2.x`), not just an exact version
+    #[serde(rename = "semverRange")]
+    SemverRange {
+        field: String,
+        message: Option<String>,
+        #[serde(default)]
+        level: Option<String>,
+    },
+    /// Numeric field within optional `min`/`max` bounds (inclusive unless
+    /// `exclusiveMin`/`exclusiveMax` is set)
+    #[serde(rename = "numberRange")]
+    NumberRange {
+        field: String,
+        #[serde(default)]
+        min: Option<f64>,
+        #[serde(default)]
+        max: Option<f64>,
+        #[serde(default, rename = "exclusiveMin")]
+        exclusive_min: bool,
+        #[serde(default, rename = "exclusiveMax")]
+        exclusive_max: bool,
+        message: Option<String>,
+        #[serde(default)]
+        level: Option<String>,
+    },
+    /// ISO-8601 date within optional inclusive `after`/`before` bounds;
+    /// either bound accepts "now"
+    #[serde(rename = "date")]
+    Date {
+        field: String,
+        #[serde(default)]
+        after: Option<String>,
+        #[serde(default)]
+        before: Option<String>,
+        message: Option<String>,
+        #[serde(default)]
+        level: Option<String>,
+    },
+    /// Object fields that must not share any key (e.g. dependencies vs
+    /// devDependencies)
+    #[serde(rename = "disjointKeys")]
+    DisjointKeys {
+        fields: Vec<String>,
+        message: Option<String>,
+        #[serde(default)]
+        level: Option<String>,
+    },
+    /// String field naming a directory or file that must exist under `base`
+    /// (relative to the repo root), after an optional `transform`:
+    /// `basename` (`@acme/foo` -> `foo`) or `scope` (`@acme/foo` -> `acme`).
+    /// Absolute names and names with `..` are reported, not probed
+    #[serde(rename = "matchesPath")]
+    MatchesPath {
+        field: String,
+        #[serde(default)]
+        base: Option<String>,
+        #[serde(default)]
+        transform: Option<String>,
+        message: Option<String>,
+        #[serde(default)]
+        level: Option<String>,
+    },
+    /// Object field whose every child value must be one of `values`
+    /// (e.g. a closed set of allowed `$.scripts` commands)
+    #[serde(rename = "enumValues")]
+    EnumValues {
+        field: String,
+        values: Vec<Json>,
+        message: Option<String>,
+        #[serde(default)]
+        level: Option<String>,
+    },
+    /// Array field whose elements must all differ; non-arrays are ignored
+    #[serde(rename = "uniqueItems")]
+    UniqueItems {
+        field: String,
+        message: Option<String>,
+        #[serde(default)]
+        level: Option<String>,
+    },
+    /// Numeric field at or above `value` (strictly above when `exclusive`);
+    /// non-numbers are reported as type mismatches
+    #[serde(rename = "minimum")]
+    Minimum {
+        field: String,
+        value: f64,
+        #[serde(default)]
+        exclusive: Option<bool>,
+        /// Parse numeric strings (`"3"`) before comparing
+        #[serde(default, rename = "coerceStrings")]
+        coerce_strings: bool,
+        message: Option<String>,
+        #[serde(default)]
+        level: Option<String>,
+    },
+    /// Numeric field at or below `value` (strictly below when `exclusive`);
+    /// non-numbers are reported as type mismatches
+    #[serde(rename = "maximum")]
+    Maximum {
+        field: String,
+        value: f64,
+        #[serde(default)]
+        exclusive: Option<bool>,
+        /// Parse numeric strings (`"3"`) before comparing
+        #[serde(default, rename = "coerceStrings")]
+        coerce_strings: bool,
+        message: Option<String>,
+        #[serde(default)]
+        level: Option<String>,
+    },
+    /// Relation between two fields: `op` is `eq|ne|lt|le|gt|ge`, comparing
+    /// numbers numerically and strings lexicographically
+    #[serde(rename = "compare")]
+    Compare {
+        left: String,
+        op: String,
+        right: String,
+        message: Option<String>,
+        #[serde(default)]
+        level: Option<String>,
+    },
+    /// Every key of the object at `field` follows `case`
+    /// (`camel|kebab|snake|pascal`); non-objects are ignored
+    #[serde(rename = "keyCase")]
+    KeyCase {
+        field: String,
+        case: String,
+        message: Option<String>,
+        #[serde(default)]
+        level: Option<String>,
+    },
+    /// Object at `field` has exactly the listed `keys`: each missing key and
+    /// each key not listed is reported; non-objects are ignored. Messages may
+    /// use `{{key}}`, `{{path}}` and `{{problem}}` (`missing`|`unexpected`)
+    #[serde(rename = "exactKeys")]
+    ExactKeys {
+        field: String,
+        keys: Vec<String>,
+        message: Option<String>,
+        #[serde(default)]
+        level: Option<String>,
+    },
+    /// When `field` is present, every path in `requires` must be too
+    /// (e.g. `types` requires `main`)
+    #[serde(rename = "dependentRequired")]
+    DependentRequired {
+        field: String,
+        requires: Vec<String>,
+        message: Option<String>,
+        #[serde(default)]
+        level: Option<String>,
+    },
+    /// Array of strings kept in `order` (`asc|desc`, default asc);
+    /// non-arrays are ignored
+    #[serde(rename = "sortedArray")]
+    SortedArray {
+        field: String,
+        #[serde(default)]
+        order: Option<String>,
+        message: Option<String>,
+        #[serde(default)]
+        level: Option<String>,
+    },
+    /// At least `min` elements of the array at `field` have `whereField`
+    /// (equal to `whereValue` when given); a missing array has none, other
+    /// non-arrays are ignored
+    #[serde(rename = "countWhere")]
+    CountWhere {
+        field: String,
+        #[serde(rename = "whereField")]
+        where_field: String,
+        #[serde(default, rename = "whereValue")]
+        where_value: Option<Json>,
+        min: usize,
+        message: Option<String>,
+        #[serde(default)]
+        level: Option<String>,
+    },
+    /// Any other kind, dispatched to a handler registered via
+    /// `checks::register_check`. Must stay the last variant.
+    #[serde(untagged)]
+    Custom {
+        kind: String,
+        #[serde(flatten)]
+        #[allow(dead_code)]
+        params: serde_json::Map<String, Json>,
+    },
+}
+
+impl Check {
+    /// The `kind` string this check was declared with.
+    pub fn kind(&self) -> &str {
+        match self {
+            Check::Required { .. } => "required",
+            Check::RequiredOneOf { .. } => "requiredOneOf",
+            Check::Type { .. } => "type",
+            Check::Const { .. } => "const",
+            Check::Pattern { .. } => "pattern",
+            Check::NotPattern { .. } => "notPattern",
+            Check::PatternSet { .. } => "patternSet",
+            Check::Enum { .. } => "enum",
+            Check::MinLength { .. } => "minLength",
+            Check::MaxLength { .. } => "maxLength",
+            Check::SemverRange { .. } => "semverRange",
+            Check::NumberRange { .. } => "numberRange",
+            Check::Date { .. } => "date",
+            Check::DisjointKeys { .. } => "disjointKeys",
+            Check::MatchesPath { .. } => "matchesPath",
+            Check::EnumValues { .. } => "enumValues",
+            Check::UniqueItems { .. } => "uniqueItems",
+            Check::Minimum { .. } => "minimum",
+            Check::Maximum { .. } => "maximum",
+            Check::Compare { .. } => "compare",
+            Check::KeyCase { .. } => "keyCase",
+            Check::ExactKeys { .. } => "exactKeys",
+            Check::DependentRequired { .. } => "dependentRequired",
+            Check::SortedArray { .. } => "sortedArray",
+            Check::CountWhere { .. } => "countWhere",
+            Check::Custom { kind, .. } => kind,
+        }
+    }
+
+    /// Regexes this check compiles at lint time, for upfront validation.
+    pub fn regexes(&self) -> Vec<&str> {
+        match self {
+            Check::Pattern { regex, .. } | Check::NotPattern { regex, .. } => vec![regex],
+            Check::PatternSet { allow, deny, .. } => {
+                allow.iter().chain(deny).map(String::as_str).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Describe a `numberRange` whose bounds no number can satisfy
+    /// (`min > max`, or equal bounds with either side exclusive).
+    pub fn impossible_range(&self) -> Option<String> {
+        let Check::NumberRange {
+            field,
+            min: Some(lo),
+            max: Some(hi),
+            exclusive_min,
+            exclusive_max,
+            ..
+        } = self
+        else {
+            return None;
+        };
+        let empty = lo > hi || (lo == hi && (*exclusive_min || *exclusive_max));
+        empty.then(|| {
+            format!(
+                "Impossible numberRange for {}: {} {} and {} {} admit no value",
+                field,
+                if *exclusive_min { ">" } else { ">=" },
+                lo,
+                if *exclusive_max { "<" } else { "<=" },
+                hi
+            )
+        })
+    }
+
+    /// Whether `rigra lint --fix` can resolve this check's findings
+    /// (see `checks::apply_fixes`).
+    pub fn fixable(&self) -> bool {
+        matches!(self, Check::SortedArray { .. })
+    }
+
+    /// Rewrite a check whose paths all address array elements (`$[*].a`)
+    /// into one that runs against a single element (`$.a`).
+    ///
+    /// Returns `None` when any path is not element-scoped, or for custom
+    /// kinds whose params are opaque.
+    pub fn for_elements(&self) -> Option<Check> {
+        let mut out = self.clone();
+        let mut ok = true;
+        let mut map = |p: &mut String| match element_subpath(p) {
+            Some(sub) => *p = format!("$.{}", sub),
+            None => ok = false,
+        };
+        match &mut out {
+            Check::Required {
+                fields,
+                any_descendant,
+                ..
+            } => match any_descendant {
+                // Fields are plain key names searched below the subtree
+                Some(root) => map(root),
+                None => fields.iter_mut().for_each(&mut map),
+            },
+            Check::RequiredOneOf { fields, .. } | Check::DisjointKeys { fields, .. } => {
+                fields.iter_mut().for_each(&mut map)
+            }
+            Check::Type { fields, .. } => {
+                let mut rewritten = HashMap::new();
+                for (mut k, v) in fields.drain() {
+                    map(&mut k);
+                    rewritten.insert(k, v);
+                }
+                *fields = rewritten;
+            }
+            Check::Const { field, .. }
+            | Check::Pattern { field, .. }
+            | Check::NotPattern { field, .. }
+            | Check::PatternSet { field, .. }
+            | Check::Enum { field, .. }
+            | Check::MinLength { field, .. }
+            | Check::MaxLength { field, .. }
+            | Check::SemverRange { field, .. }
+            | Check::NumberRange { field, .. }
+            | Check::Date { field, .. }
+            | Check::MatchesPath { field, .. }
+            | Check::EnumValues { field, .. }
+            | Check::UniqueItems { field, .. }
+            | Check::Minimum { field, .. }
+            | Check::Maximum { field, .. }
+            | Check::KeyCase { field, .. }
+            | Check::ExactKeys { field, .. }
+            | Check::SortedArray { field, .. }
+            | Check::CountWhere { field, .. } => map(field),
+            Check::DependentRequired {
+                field, requires, ..
+            } => {
+                map(field);
+                requires.iter_mut().for_each(&mut map);
+            }
+            Check::Compare { left, right, .. } => {
+                map(left);
+                map(right);
+            }
+            Check::Custom { .. } => return None,
+        }
+        ok.then_some(out)
+    }
+}
+
+/// For an element path `$[*].a.b` (or `[*].a.b`), return `a.b`.
+fn element_subpath(p: &str) -> Option<String> {
+    let t = p.trim();
+    let rest = t.strip_prefix('$').unwrap_or(t).strip_prefix("[*]")?;
+    Some(rest.trim_start_matches('.').to_string())
 }
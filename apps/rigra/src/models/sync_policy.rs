@@ -2,7 +2,7 @@
 
 use serde::Deserialize;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Default)]
 pub struct SyncPolicy {
     #[serde(default)]
     pub lint: Option<SyncLintDefaults>,
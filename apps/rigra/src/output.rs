@@ -1,37 +1,64 @@
 //! Output rendering for lint, format, and sync commands.
 //!
-//! Supports `human` (default) and `json` outputs. The JSON form includes
-//! per-item fields and a top-level summary.
+//! Supports `human` (default), `json` and `json-compact` (the same shape on
+//! one line) outputs. The JSON form includes per-item fields and a top-level
+//! summary. Lint also supports `tap` (Test Anything Protocol version 13),
+//! `sarif` (SARIF 2.1.0) and `github` (GitHub Actions workflow commands,
+//! shown as inline annotations).
 
-use crate::models::{LintResult, RunError};
+use crate::bench::BenchStats;
+use crate::config::UiCfg;
+use crate::doctor::Problem;
+use crate::explain::RuleExplain;
+use crate::models::{LintResult, RuleTiming, RunError};
+use crate::policy_diff::{self, PolicyDiff};
 use crate::{format::FormatResult, sync::SyncAction};
 use owo_colors::OwoColorize;
 use serde_json::json;
 use serde_json::Value as JsonVal;
+use std::path::Path;
 
-fn try_print_json(val: &serde_json::Value) {
-    match serde_json::to_string_pretty(val) {
+/// Whether `output` selects a JSON mode (`json` or `json-compact`).
+pub fn is_json(output: &str) -> bool {
+    output == "json" || output == "json-compact"
+}
+
+/// Serialize `val`, on a single line when `output` is `json-compact`.
+fn render_json(val: &serde_json::Value, output: &str) -> serde_json::Result<String> {
+    if output == "json-compact" {
+        serde_json::to_string(val)
+    } else {
+        serde_json::to_string_pretty(val)
+    }
+}
+
+fn try_print_json(val: &serde_json::Value, output: &str) {
+    match render_json(val, output) {
         Ok(s) => println!("{}", s),
         Err(e) => {
             // Fallback structured error when serialization fails
             let fb =
                 json!({"errors":[{"message": format!("Failed to serialize output JSON: {}", e)}]});
-            match serde_json::to_string_pretty(&fb) {
+            match render_json(&fb, output) {
                 Ok(s2) => println!("{}", s2),
-                Err(_) => println!("{}", r#"{"errors":[{"message":"serialization failed"}]}"#),
+                Err(_) => println!("{{\"errors\":[{{\"message\":\"serialization failed\"}}]}}"),
             }
         }
     }
 }
 
 fn use_colors(output: &str) -> bool {
-    output != "json" && std::env::var_os("NO_COLOR").is_none()
+    !is_json(output) && std::env::var_os("NO_COLOR").is_none()
 }
 
-/// Print lint results in the requested format.
-pub fn print_lint(res: &LintResult, output: &str, errors: &[RunError]) {
+/// Print lint results in the requested format. `ui` overrides severity
+/// labels/icons in human output.
+///
+/// Human output groups issues under directory headers unless `flat` is set,
+/// in which case each issue line carries its full relative path.
+pub fn print_lint(res: &LintResult, output: &str, errors: &[RunError], ui: &UiCfg, flat: bool) {
     match output {
-        "json" => {
+        "json" | "json-compact" => {
             let mut root = compose_lint_json(res);
             let errs: Vec<_> = errors
                 .iter()
@@ -42,70 +69,53 @@ pub fn print_lint(res: &LintResult, output: &str, errors: &[RunError]) {
                     obj.insert("errors".to_string(), json!(errs));
                 }
             }
-            try_print_json(&root);
+            try_print_json(&root, output);
+        }
+        "tap" => {
+            for line in compose_lint_tap(res, errors) {
+                println!("{}", line);
+            }
+        }
+        "sarif" => try_print_json(&compose_sarif_json(res, errors), output),
+        "github" => {
+            for line in compose_lint_github(res, errors) {
+                println!("{}", line);
+            }
         }
         _ => {
             let color = use_colors(output);
-            // Group by directory and print directory headers
-            use std::collections::BTreeMap;
-            use std::path::Path;
-            let mut groups: BTreeMap<String, Vec<&crate::models::Issue>> = BTreeMap::new();
-            for is in &res.issues {
-                let dir = match Path::new(&is.file).parent() {
-                    Some(p) => {
-                        let s = p.to_string_lossy().to_string();
-                        if s.is_empty() || s == "." {
-                            "⌂ (root)".to_string()
-                        } else {
-                            s
-                        }
-                    }
-                    None => "⌂ (root)".to_string(),
-                };
-                groups.entry(dir).or_default().push(is);
+            for line in compose_lint_fixed_lines(res, color) {
+                println!("{}", line);
             }
-            for (dir, items) in groups {
-                if color {
-                    println!("▣ {}", dir.bold());
-                } else {
-                    println!("{}", dir);
-                }
-                for is in items {
-                    let sev = match is.severity.as_str() {
-                        "error" => crate::utils::tag_error(color),
-                        "warning" | "warn" => crate::utils::tag_warn(color),
-                        _ => crate::utils::tag_info(color),
-                    };
-                    let icon = match is.severity.as_str() {
-                        "error" => crate::utils::icon_error(color),
-                        "warning" | "warn" => crate::utils::icon_warn(color),
-                        _ => crate::utils::icon_info(color),
-                    };
-                    // Print only the basename under the directory header
-                    let base = Path::new(&is.file)
-                        .file_name()
-                        .map(|f| f.to_string_lossy().to_string())
-                        .unwrap_or_else(|| is.file.clone());
-                    let base = if color { base.bold().to_string() } else { base };
-                    println!("  {} {} {} ❲{}❳ — {}", icon, sev, base, is.rule, is.message);
-                }
+            for line in compose_lint_resolved_lines(res, color) {
+                println!("{}", line);
+            }
+            let lines = if flat {
+                compose_lint_flat_lines(res, color, ui)
+            } else {
+                compose_lint_grouped_lines(res, color, ui)
+            };
+            for line in lines {
+                println!("{}", line);
             }
             // Emit pass message when there are no errors or warnings
             if res.summary.errors == 0 && res.summary.warnings == 0 {
                 if color {
                     println!(
-                        "{} {}",
-                        "✔ ⟦perfect⟧".green().bold(),
-                        "Validation passed. No convention violations detected."
+                        "{} Validation passed. No convention violations detected.",
+                        "✔ ⟦perfect⟧".green().bold()
                     );
                 } else {
                     println!("✔ ⟦perfect⟧ Validation passed. No convention violations detected.");
                 }
             }
-            let summary = format!(
+            let mut summary = format!(
                 "— Summary — errors={} warnings={} infos={} files={}",
                 res.summary.errors, res.summary.warnings, res.summary.infos, res.summary.files
             );
+            if let Some(n) = res.summary.fixed {
+                summary.push_str(&format!(" fixed={}", n));
+            }
             if color {
                 println!("{}", summary.bold());
             } else {
@@ -116,19 +126,19 @@ pub fn print_lint(res: &LintResult, output: &str, errors: &[RunError]) {
 }
 
 /// Print formatting results. When `write` is false, previews and diffs
-/// can be emitted; otherwise only file statuses are shown.
-// removed duplicate import to avoid name redefinition warnings
-
+/// can be emitted; otherwise only file statuses are shown. `diff_context`
+/// sets the number of unchanged lines shown around each diff hunk.
 pub fn print_format(
     results: &[FormatResult],
     output: &str,
     write: bool,
     diff: bool,
+    diff_context: usize,
     errors: &[RunError],
 ) {
     match output {
-        "json" => {
-            let out = compose_format_json(results, write, diff);
+        "json" | "json-compact" => {
+            let out = compose_format_json(results, write, diff, diff_context);
             // Attach aggregated errors array when present
             let errs: Vec<_> = errors
                 .iter()
@@ -140,66 +150,417 @@ pub fn print_format(
                     obj.insert("errors".to_string(), json!(errs));
                 }
             }
-            try_print_json(&root);
+            try_print_json(&root, output);
+        }
+        _ => {
+            for line in
+                compose_format_human_lines(results, use_colors(output), write, diff, diff_context)
+            {
+                println!("{}", line);
+            }
+        }
+    }
+}
+
+/// Print the top-level key order per file for `rigra format --emit-order`.
+pub fn print_format_order(results: &[FormatResult], output: &str, errors: &[RunError]) {
+    match output {
+        "json" | "json-compact" => {
+            let mut root = compose_format_order_json(results);
+            if !errors.is_empty() {
+                let errs: Vec<_> = errors
+                    .iter()
+                    .map(|e| json!({"message": e.message}))
+                    .collect();
+                root["errors"] = json!(errs);
+            }
+            try_print_json(&root, output);
         }
         _ => {
             let color = use_colors(output);
-            let changed_count = results.iter().filter(|r| r.changed).count();
-            if changed_count == 0 {
+            for (file, order) in format_orders(results) {
+                let keys = JsonVal::from(order).to_string();
                 if color {
-                    println!(
-                        "{} {}",
-                        "✔ ⟦stable⟧".blue().bold(),
-                        "Everything is tidy. No changes to format."
-                    );
+                    println!("{} {}\n{}", "---".cyan().bold(), file.bold(), keys);
                 } else {
-                    println!("✔ ⟦stable⟧ Everything is tidy. No changes.");
+                    println!("--- {}\n{}", file, keys);
                 }
-                return;
             }
-            for r in results {
-                if write {
-                    if r.changed {
-                        if color {
-                            println!("{} {}", "✎ formatted »".green().bold(), r.file.bold());
-                        } else {
-                            println!("✎ formatted » {}", r.file);
-                        }
-                    }
-                } else if r.changed {
-                    if diff {
-                        if let Some(d) =
-                            build_naive_diff(r.original.as_deref(), r.preview.as_deref())
-                        {
-                            if color {
-                                println!("{} {}\n{}", "---".cyan().bold(), r.file.bold(), d);
-                            } else {
-                                println!("--- {}\n{}", r.file, d);
-                            }
-                        } else if let Some(prev) = &r.preview {
-                            if color {
-                                println!("{} {}\n{}", "---".cyan().bold(), r.file.bold(), prev);
-                            } else {
-                                println!("--- {}\n{}", r.file, prev);
-                            }
-                        }
-                    } else if let Some(prev) = &r.preview {
-                        if color {
-                            println!("{} {}\n{}", "---".cyan().bold(), r.file.bold(), prev);
-                        } else {
-                            println!("--- {}\n{}", r.file, prev);
-                        }
-                    }
-                }
+        }
+    }
+}
+
+/// Compose `{"results": [{"file", "order"}]}` for `--emit-order` (pure).
+pub fn compose_format_order_json(results: &[FormatResult]) -> JsonVal {
+    let items: Vec<_> = format_orders(results)
+        .into_iter()
+        .map(|(file, order)| json!({"file": file, "order": order}))
+        .collect();
+    json!({ "results": items })
+}
+
+fn format_orders(results: &[FormatResult]) -> Vec<(&str, Vec<String>)> {
+    results
+        .iter()
+        .filter_map(|r| Some((r.file.as_str(), crate::format::emitted_order(r)?)))
+        .collect()
+}
+
+/// Compose human format output (pure). Only changed files are listed;
+/// unchanged files are omitted so large repos stay readable.
+pub fn compose_format_human_lines(
+    results: &[FormatResult],
+    color: bool,
+    write: bool,
+    diff: bool,
+    diff_context: usize,
+) -> Vec<String> {
+    if !results.iter().any(|r| r.changed) {
+        return vec![if color {
+            format!(
+                "{} Everything is tidy. No changes to format.",
+                "✔ ⟦stable⟧".blue().bold()
+            )
+        } else {
+            "✔ ⟦stable⟧ Everything is tidy. No changes.".to_string()
+        }];
+    }
+    let header = |file: &str, body: &str| {
+        if color {
+            format!("{} {}\n{}", "---".cyan().bold(), file.bold(), body)
+        } else {
+            format!("--- {}\n{}", file, body)
+        }
+    };
+    let mut lines = Vec::new();
+    for r in results.iter().filter(|r| r.changed) {
+        if write {
+            lines.push(if color {
+                format!("{} {}", "✎ formatted »".green().bold(), r.file.bold())
+            } else {
+                format!("✎ formatted » {}", r.file)
+            });
+            continue;
+        }
+        let d = if diff {
+            build_unified_diff(r.original.as_deref(), r.preview.as_deref(), diff_context)
+        } else {
+            None
+        };
+        if let Some(body) = d.as_deref().or(r.preview.as_deref()) {
+            lines.push(header(&r.file, body));
+        }
+    }
+    lines
+}
+
+/// Print a policy comparison from `rigra diff-policy`.
+pub fn print_policy_diff(d: &PolicyDiff, output: &str) {
+    match output {
+        "json" | "json-compact" => try_print_json(&compose_policy_diff_json(d), output),
+        _ => {
+            for line in compose_policy_diff_lines(d, use_colors(output)) {
+                println!("{}", line);
+            }
+        }
+    }
+}
+
+/// Compose human policy-diff lines (pure): `+` added, `-` removed and `~`
+/// changed checks, then differing sections.
+pub fn compose_policy_diff_lines(d: &PolicyDiff, color: bool) -> Vec<String> {
+    if d.is_empty() {
+        return vec!["Policies are equivalent.".to_string()];
+    }
+    let paint = |sign: &str, text: String| -> String {
+        if !color {
+            return format!("{} {}", sign, text);
+        }
+        match sign {
+            "+" => format!("{} {}", sign.green().bold(), text),
+            "-" => format!("{} {}", sign.red().bold(), text),
+            _ => format!("{} {}", sign.yellow().bold(), text),
+        }
+    };
+    let mut lines = Vec::new();
+    for c in &d.added {
+        lines.push(paint("+", format!("check {}", policy_diff::check_key(c))));
+    }
+    for c in &d.removed {
+        lines.push(paint("-", format!("check {}", policy_diff::check_key(c))));
+    }
+    for (o, n) in &d.changed {
+        lines.push(paint(
+            "~",
+            format!("check {}: {} → {}", policy_diff::check_key(o), o, n),
+        ));
+    }
+    for (k, o, n) in &d.sections {
+        lines.push(paint("~", format!("{}: {} → {}", k, o, n)));
+    }
+    lines
+}
+
+/// Compose the policy-diff JSON object (pure).
+pub fn compose_policy_diff_json(d: &PolicyDiff) -> JsonVal {
+    json!({
+        "added": d.added,
+        "removed": d.removed,
+        "changed": d.changed.iter().map(|(o, n)| json!({"old": o, "new": n})).collect::<Vec<_>>(),
+        "sections": d.sections.iter().map(|(k, o, n)| json!({"key": k, "old": o, "new": n})).collect::<Vec<_>>(),
+    })
+}
+
+/// Print `rigra explain` results for `subject` (a rule id or file).
+pub fn print_explain(rules: &[RuleExplain], subject: &str, output: &str) {
+    match output {
+        "json" | "json-compact" => try_print_json(&compose_explain_json(rules), output),
+        _ => {
+            for line in compose_explain_lines(rules, subject, use_colors(output)) {
+                println!("{}", line);
+            }
+        }
+    }
+}
+
+/// Compose human explain lines (pure): one block per rule with its
+/// patterns, policy and checks, then one line per check and for the order
+/// giving its fields, effective level and message.
+pub fn compose_explain_lines(rules: &[RuleExplain], subject: &str, color: bool) -> Vec<String> {
+    if rules.is_empty() {
+        return vec![format!("No rules match {}.", subject)];
+    }
+    let mut lines = Vec::new();
+    for r in rules {
+        let head = format!("rule {}", r.rule);
+        lines.push(if color { head.bold().to_string() } else { head });
+        lines.push(format!("  patterns: {}", r.patterns.join(", ")));
+        lines.push(format!("  policy: {}", r.policy.to_string_lossy()));
+        if let Some(e) = &r.error {
+            lines.push(format!("  error: {}", e));
+            continue;
+        }
+        let mut checks = r.checks.clone();
+        if r.order {
+            checks.push("order".into());
+        }
+        if r.formatting {
+            checks.push("formatting".into());
+        }
+        if checks.is_empty() {
+            lines.push("  checks: (none)".into());
+        } else {
+            lines.push(format!("  checks: {}", checks.join(", ")));
+        }
+        for c in &r.details {
+            lines.push(explain_detail_line(
+                &c.kind,
+                &c.fields.join(", "),
+                &c.level,
+                c.message.as_deref(),
+            ));
+        }
+        if let Some(o) = &r.order_detail {
+            let top: Vec<String> = o.top.iter().map(|g| g.join(", ")).collect();
+            lines.push(explain_detail_line(
+                "order",
+                &top.join(" > "),
+                &o.level,
+                o.message.as_deref(),
+            ));
+        }
+    }
+    lines
+}
+
+fn explain_detail_line(kind: &str, target: &str, level: &str, message: Option<&str>) -> String {
+    let mut line = format!("    {} [{}] ({})", kind, target, level);
+    if let Some(m) = message {
+        line.push_str(": ");
+        line.push_str(m);
+    }
+    line
+}
+
+/// Compose the explain JSON array (pure).
+pub fn compose_explain_json(rules: &[RuleExplain]) -> JsonVal {
+    JsonVal::Array(
+        rules
+            .iter()
+            .map(|r| {
+                json!({
+                    "rule": r.rule,
+                    "patterns": r.patterns,
+                    "policy": r.policy.to_string_lossy(),
+                    "checks": r.checks,
+                    "details": r.details.iter().map(|c| json!({
+                        "kind": c.kind,
+                        "fields": c.fields,
+                        "message": c.message,
+                        "level": c.level,
+                    })).collect::<Vec<_>>(),
+                    "order": r.order,
+                    "orderDetail": r.order_detail.as_ref().map(|o| json!({
+                        "top": o.top,
+                        "message": o.message,
+                        "level": o.level,
+                    })),
+                    "formatting": r.formatting,
+                    "error": r.error,
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Print `rigra doctor` problems, or a confirmation when there are none.
+pub fn print_doctor(problems: &[Problem], output: &str) {
+    match output {
+        "json" | "json-compact" => try_print_json(&compose_doctor_json(problems), output),
+        _ => {
+            for line in compose_doctor_lines(problems, use_colors(output)) {
+                println!("{}", line);
             }
         }
     }
 }
 
+/// Compose human doctor lines (pure): one line per problem, prefixed with
+/// its rule and policy path.
+pub fn compose_doctor_lines(problems: &[Problem], color: bool) -> Vec<String> {
+    if problems.is_empty() {
+        return vec!["No policy problems found.".into()];
+    }
+    problems
+        .iter()
+        .map(|p| {
+            let head = format!("rule {} ({})", p.rule, p.policy.to_string_lossy());
+            let head = if color { head.bold().to_string() } else { head };
+            format!("{}: {}", head, p.message)
+        })
+        .collect()
+}
+
+/// Compose the doctor JSON object (pure).
+pub fn compose_doctor_json(problems: &[Problem]) -> JsonVal {
+    let items: Vec<_> = problems
+        .iter()
+        .map(|p| {
+            json!({
+                "rule": p.rule,
+                "policy": p.policy.to_string_lossy(),
+                "message": p.message,
+            })
+        })
+        .collect();
+    json!({"problems": items, "ok": problems.is_empty()})
+}
+
+/// Print the `rigra bench` table to stdout.
+pub fn print_bench(stats: &[BenchStats]) {
+    print!("{}", compose_bench_table(stats));
+}
+
+/// Compose the bench table (pure): one row per pass with min/median/max
+/// wall time in ms and files per second at the median.
+pub fn compose_bench_table(stats: &[BenchStats]) -> String {
+    let ms = |d: std::time::Duration| d.as_secs_f64() * 1000.0;
+    let mut out = format!(
+        "{:<8}{:>6}{:>7}{:>12}{:>12}{:>12}{:>12}\n",
+        "pass", "runs", "files", "min (ms)", "median (ms)", "max (ms)", "files/s"
+    );
+    for s in stats {
+        out.push_str(&format!(
+            "{:<8}{:>6}{:>7}{:>12.3}{:>12.3}{:>12.3}{:>12.1}\n",
+            s.name,
+            s.samples.len(),
+            s.files,
+            ms(s.min()),
+            ms(s.median()),
+            ms(s.max()),
+            s.throughput()
+        ));
+    }
+    out
+}
+
+/// Print the `--stats` per-rule timing table to stderr, slowest first.
+pub fn print_stats(timings: &[RuleTiming]) {
+    eprint!("{}", compose_stats_table(timings));
+}
+
+/// Compose the per-rule timing table (pure) for testing.
+pub fn compose_stats_table(timings: &[RuleTiming]) -> String {
+    let mut rows: Vec<&RuleTiming> = timings.iter().collect();
+    rows.sort_by(|a, b| b.elapsed.cmp(&a.elapsed).then_with(|| a.rule.cmp(&b.rule)));
+    let width = rows
+        .iter()
+        .map(|t| t.rule.len())
+        .chain(std::iter::once("total".len()))
+        .max()
+        .unwrap_or(0);
+    let ms = |d: std::time::Duration| d.as_secs_f64() * 1000.0;
+    let mut out = format!("{:<width$}  {:>10}\n", "rule", "time (ms)");
+    for t in &rows {
+        out.push_str(&format!("{:<width$}  {:>10.3}\n", t.rule, ms(t.elapsed)));
+    }
+    let total: std::time::Duration = rows.iter().map(|t| t.elapsed).sum();
+    out.push_str(&format!("{:<width$}  {:>10.3}\n", "total", ms(total)));
+    out
+}
+
+/// Why a command exits non-zero, for the `--explain-exit` trailer.
+pub enum ExitCause {
+    LintErrors(usize),
+    FormatChanges(usize),
+    SyncPending(usize),
+    PolicyProblems(usize),
+    /// `lint --max-warnings`: more warnings than the threshold allows
+    TooManyWarnings {
+        warnings: usize,
+        max: usize,
+    },
+}
+
+impl ExitCause {
+    /// Process exit code: 3 for an exceeded warning threshold, else 1.
+    pub fn code(&self) -> i32 {
+        match self {
+            ExitCause::TooManyWarnings { .. } => 3,
+            _ => 1,
+        }
+    }
+}
+
+/// Compose the `--explain-exit` trailer, e.g. "Exiting 1: 3 lint errors".
+pub fn compose_exit_trailer(cause: &ExitCause) -> String {
+    let plural =
+        |n: usize, one: &str, many: &str| format!("{} {}", n, if n == 1 { one } else { many });
+    let reason = match cause {
+        ExitCause::LintErrors(n) => plural(*n, "lint error", "lint errors"),
+        ExitCause::FormatChanges(n) => plural(*n, "file needs formatting", "files need formatting"),
+        ExitCause::SyncPending(n) => plural(*n, "file needs syncing", "files need syncing"),
+        ExitCause::PolicyProblems(n) => plural(*n, "policy problem", "policy problems"),
+        ExitCause::TooManyWarnings { warnings, max } => format!(
+            "{} (--max-warnings {})",
+            plural(*warnings, "lint warning", "lint warnings"),
+            max
+        ),
+    };
+    format!("Exiting {}: {}.", cause.code(), reason)
+}
+
 /// Print sync actions summarizing writes and skips.
-pub fn print_sync(actions: &[SyncAction], output: &str, errors: &[RunError]) {
+///
+/// `plan_hash` is set in `--plan-only` mode and reported as `planHash`.
+pub fn print_sync(
+    actions: &[SyncAction],
+    output: &str,
+    errors: &[RunError],
+    plan_hash: Option<&str>,
+) {
     match output {
-        "json" => {
+        "json" | "json-compact" => {
             let items: Vec<_> = actions
                 .iter()
                 .map(|a| {
@@ -223,24 +584,33 @@ pub fn print_sync(actions: &[SyncAction], output: &str, errors: &[RunError]) {
                 .map(|e| json!({"message": e.message}))
                 .collect();
             let mut out = json!({"results": items, "summary": summary});
+            if let (Some(h), Some(obj)) = (plan_hash, out.as_object_mut()) {
+                obj.insert("planHash".to_string(), json!(h));
+            }
             if !errs.is_empty() {
                 if let Some(obj) = out.as_object_mut() {
                     obj.insert("errors".to_string(), json!(errs));
                 }
             }
-            try_print_json(&out);
+            try_print_json(&out, output);
         }
         _ => {
             let color = use_colors(output);
+            if let Some(h) = plan_hash {
+                if color {
+                    println!("{} {}", "◆ ⟦plan⟧".blue().bold(), h);
+                } else {
+                    println!("◆ ⟦plan⟧ {}", h);
+                }
+            }
             // If nothing changed or pending, emit a concise info message
             let wrote_count = actions.iter().filter(|a| a.wrote).count();
             let pending_count = actions.iter().filter(|a| a.would_write).count();
             if wrote_count == 0 && pending_count == 0 {
                 if color {
                     println!(
-                        "{} {}",
-                        "◆ ⟦stable⟧".blue().bold(),
-                        "Everything up to date. No changes to sync."
+                        "{} Everything up to date. No changes to sync.",
+                        "◆ ⟦stable⟧".blue().bold()
                     );
                 } else {
                     println!("◆ ⟦stable⟧ Everything up to date. No changes to sync.");
@@ -321,16 +691,219 @@ pub fn print_sync(actions: &[SyncAction], output: &str, errors: &[RunError]) {
     }
 }
 
-fn build_naive_diff(old: Option<&str>, new: Option<&str>) -> Option<String> {
-    let old = old?;
-    let new = new?;
+/// Build a line-based unified diff (`@@` hunks with `+`/`-`/` ` prefixes).
+///
+/// Each hunk shows up to `context` unchanged lines around a change; hunks
+/// whose context would overlap are merged. Returns `None` when either side
+/// is missing.
+pub(crate) fn build_unified_diff(
+    old: Option<&str>,
+    new: Option<&str>,
+    context: usize,
+) -> Option<String> {
+    let old: Vec<&str> = old?.lines().collect();
+    let new: Vec<&str> = new?.lines().collect();
+    Some(
+        unified_hunks(&old, &new, context)
+            .trim_end_matches('\n')
+            .to_string(),
+    )
+}
+
+/// Compose a git-applyable patch (`diff --git` per file) turning each
+/// changed file's original content into its formatted preview. Paths are
+/// made relative to `repo_root`; a missing final newline is marked the way
+/// `git diff` does.
+pub fn compose_format_patch(results: &[FormatResult], repo_root: &Path, context: usize) -> String {
+    let mut out = String::new();
+    for r in results.iter().filter(|r| r.changed) {
+        let (Some(old), Some(new)) = (r.original.as_deref(), r.preview.as_deref()) else {
+            continue;
+        };
+        let rel = Path::new(&r.file)
+            .strip_prefix(repo_root)
+            .unwrap_or(Path::new(&r.file))
+            .to_string_lossy()
+            .replace('\\', "/");
+        out.push_str(&format!(
+            "diff --git a/{0} b/{0}\n--- a/{0}\n+++ b/{0}\n",
+            rel
+        ));
+        let old: Vec<&str> = old.split_inclusive('\n').collect();
+        let new: Vec<&str> = new.split_inclusive('\n').collect();
+        out.push_str(&unified_hunks(&old, &new, context));
+    }
+    out
+}
+
+/// Render `@@` hunks for a line diff. Lines keep their `\n` when present; a
+/// line without one is the last line of a file lacking a final newline and
+/// gets a `\ No newline at end of file` marker, unless no line in the diff
+/// carries a terminator (plain `lines()` input).
+fn unified_hunks(old: &[&str], new: &[&str], context: usize) -> String {
+    let ops = diff_lines(old, new);
+    let terminated = old.iter().chain(new).any(|l| l.ends_with('\n'));
+    // Group change positions into op ranges padded by `context` equal lines
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for (i, (tag, _)) in ops.iter().enumerate() {
+        if *tag == ' ' {
+            continue;
+        }
+        let start = i.saturating_sub(context);
+        let end = (i + context + 1).min(ops.len());
+        match ranges.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+
+    // Line numbers (0-based) of the old/new cursor before each op
+    let mut pos: Vec<(usize, usize)> = Vec::with_capacity(ops.len() + 1);
+    let (mut oi, mut ni) = (0usize, 0usize);
+    for (tag, _) in ops.iter() {
+        pos.push((oi, ni));
+        match tag {
+            '-' => oi += 1,
+            '+' => ni += 1,
+            _ => {
+                oi += 1;
+                ni += 1;
+            }
+        }
+    }
+
     let mut out = String::new();
-    out.push_str("+++ new\n");
-    out.push_str(new);
-    out.push('\n');
-    out.push_str("--- old\n");
-    out.push_str(old);
-    Some(out)
+    for (start, end) in ranges {
+        let hunk = &ops[start..end];
+        let old_len = hunk.iter().filter(|(t, _)| *t != '+').count();
+        let new_len = hunk.iter().filter(|(t, _)| *t != '-').count();
+        let (o0, n0) = pos[start];
+        // Empty sides point at the line before the hunk, as in `diff -u`
+        let old_start = if old_len == 0 { o0 } else { o0 + 1 };
+        let new_start = if new_len == 0 { n0 } else { n0 + 1 };
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_start, old_len, new_start, new_len
+        ));
+        for (tag, line) in hunk {
+            out.push(*tag);
+            out.push_str(line);
+            if !line.ends_with('\n') {
+                out.push('\n');
+                if terminated {
+                    out.push_str("\\ No newline at end of file\n");
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Compute a minimal line edit script (Myers' linear-space diff).
+///
+/// Returns `(tag, line)` pairs where tag is `' '` (unchanged), `'-'`
+/// (removed from old) or `'+'` (added in new). Memory stays linear in the
+/// input, so large files diff without a quadratic table.
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(char, &'a str)> {
+    let mut ops = Vec::with_capacity(old.len() + new.len());
+    diff_into(old, new, &mut ops);
+    // Within each run of changes, removals come before additions as in
+    // `diff -u`
+    for run in ops.split_mut(|(tag, _)| *tag == ' ') {
+        run.sort_by_key(|(tag, _)| *tag != '-');
+    }
+    ops
+}
+
+fn diff_into<'a>(old: &[&'a str], new: &[&'a str], ops: &mut Vec<(char, &'a str)>) {
+    // Shared prefix and suffix are unchanged; only the middle is searched
+    let pre = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let (old_rest, new_rest) = (&old[pre..], &new[pre..]);
+    let suf = old_rest
+        .iter()
+        .rev()
+        .zip(new_rest.iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &old_rest[..old_rest.len() - suf];
+    let b = &new_rest[..new_rest.len() - suf];
+    ops.extend(old[..pre].iter().map(|l| (' ', *l)));
+    if a.is_empty() || b.is_empty() {
+        ops.extend(a.iter().map(|l| ('-', *l)));
+        ops.extend(b.iter().map(|l| ('+', *l)));
+    } else {
+        let (x, y, u, v) = middle_snake(a, b);
+        if (x, y) == (0, 0) && (u, v) == (a.len(), b.len()) {
+            // Unreachable once both ends differ; keep the output valid anyway
+            ops.extend(a.iter().map(|l| ('-', *l)));
+            ops.extend(b.iter().map(|l| ('+', *l)));
+        } else {
+            diff_into(&a[..x], &b[..y], ops);
+            ops.extend(a[x..u].iter().map(|l| (' ', *l)));
+            diff_into(&a[u..], &b[v..], ops);
+        }
+    }
+    ops.extend(old_rest[old_rest.len() - suf..].iter().map(|l| (' ', *l)));
+}
+
+/// Middle snake of an optimal edit path between non-empty `a` and `b`, as
+/// `(x, y, u, v)`: `a[x..u]` equals `b[y..v]` and both halves around it are
+/// diffed independently.
+fn middle_snake(a: &[&str], b: &[&str]) -> (usize, usize, usize, usize) {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let delta = n - m;
+    let odd = delta % 2 != 0;
+    let max = (n + m + 1) / 2;
+    let off = max + 1;
+    // Furthest x per diagonal, forward from the start and backward from the
+    // end (backward x counts from the end of `a`)
+    let mut vf = vec![0isize; (2 * off + 1) as usize];
+    let mut vb = vec![0isize; (2 * off + 1) as usize];
+    let at = |k: isize| (k + off) as usize;
+    for d in 0..=max {
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && vf[at(k - 1)] < vf[at(k + 1)]) {
+                vf[at(k + 1)]
+            } else {
+                vf[at(k - 1)] + 1
+            };
+            let (x0, y0) = (x, x - k);
+            let mut y = y0;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            vf[at(k)] = x;
+            let c = delta - k;
+            if odd && c.abs() < d && x + vb[at(c)] >= n {
+                return (x0 as usize, y0 as usize, x as usize, y as usize);
+            }
+        }
+        for c in (-d..=d).step_by(2) {
+            let mut x = if c == -d || (c != d && vb[at(c - 1)] < vb[at(c + 1)]) {
+                vb[at(c + 1)]
+            } else {
+                vb[at(c - 1)] + 1
+            };
+            let (x0, y0) = (x, x - c);
+            let mut y = y0;
+            while x < n && y < m && a[(n - 1 - x) as usize] == b[(m - 1 - y) as usize] {
+                x += 1;
+                y += 1;
+            }
+            vb[at(c)] = x;
+            let k = delta - c;
+            if !odd && k.abs() <= d && vf[at(k)] + x >= n {
+                return (
+                    (n - x) as usize,
+                    (m - y) as usize,
+                    (n - x0) as usize,
+                    (m - y0) as usize,
+                );
+            }
+        }
+    }
+    unreachable!("an edit path of at most n + m steps always exists")
 }
 
 /// Compose lint JSON object (pure) for testing/snapshot purposes.
@@ -345,9 +918,53 @@ pub fn compose_lint_json(res: &LintResult) -> JsonVal {
     }
 }
 
-/// Compose grouped human-readable lint lines (excluding summary) for testing.
-#[cfg(test)]
-pub fn compose_lint_grouped_lines(res: &LintResult, color: bool) -> Vec<String> {
+/// Normalize a severity to its `[ui]` key: `error`, `warning`, or `info`.
+fn severity_key(sev: &str) -> &'static str {
+    match sev {
+        "error" => "error",
+        "warning" | "warn" => "warning",
+        _ => "info",
+    }
+}
+
+/// Severity tag (`⟦error⟧`) honoring `[ui].labels`, colored per severity.
+fn severity_label(sev: &str, color: bool, ui: &UiCfg) -> String {
+    let key = severity_key(sev);
+    match ui.labels.get(key) {
+        Some(l) if color => match key {
+            "error" => l.red().bold().to_string(),
+            "warning" => l.yellow().bold().to_string(),
+            _ => l.blue().bold().to_string(),
+        },
+        Some(l) => l.clone(),
+        None => match key {
+            "error" => crate::utils::tag_error(color),
+            "warning" => crate::utils::tag_warn(color),
+            _ => crate::utils::tag_info(color),
+        },
+    }
+}
+
+/// Severity icon (`✖`) honoring `[ui].icons`, colored per severity.
+fn severity_icon(sev: &str, color: bool, ui: &UiCfg) -> String {
+    let key = severity_key(sev);
+    match ui.icons.get(key) {
+        Some(i) if color => match key {
+            "error" => i.red().to_string(),
+            "warning" => i.yellow().to_string(),
+            _ => i.blue().to_string(),
+        },
+        Some(i) => i.clone(),
+        None => match key {
+            "error" => crate::utils::icon_error(color),
+            "warning" => crate::utils::icon_warn(color),
+            _ => crate::utils::icon_info(color),
+        },
+    }
+}
+
+/// Compose grouped human-readable lint lines (excluding summary).
+pub fn compose_lint_grouped_lines(res: &LintResult, color: bool, ui: &UiCfg) -> Vec<String> {
     use std::collections::BTreeMap;
     use std::path::Path;
     let mut groups: BTreeMap<String, Vec<&crate::models::Issue>> = BTreeMap::new();
@@ -373,50 +990,241 @@ pub fn compose_lint_grouped_lines(res: &LintResult, color: bool) -> Vec<String>
             lines.push(dir);
         }
         for is in items {
-            let sev = match is.severity.as_str() {
-                "error" => {
-                    if color {
-                        "⟦error⟧".red().bold().to_string()
-                    } else {
-                        "⟦error⟧".to_string()
-                    }
-                }
-                "warning" | "warn" => {
-                    if color {
-                        "⟦warn⟧".yellow().bold().to_string()
-                    } else {
-                        "⟦warn⟧".to_string()
-                    }
-                }
-                _ => {
-                    if color {
-                        "⟦info⟧".blue().bold().to_string()
-                    } else {
-                        "⟦info⟧".to_string()
-                    }
-                }
-            };
-            let icon = match is.severity.as_str() {
-                "error" => "✖".red().to_string(),
-                "warning" | "warn" => "▲".yellow().to_string(),
-                _ => "◆".blue().to_string(),
-            };
+            // Print only the basename under the directory header
             let base = Path::new(&is.file)
                 .file_name()
                 .map(|f| f.to_string_lossy().to_string())
                 .unwrap_or_else(|| is.file.clone());
-            let base = if color { base.bold().to_string() } else { base };
-            lines.push(format!(
-                "  {} {} {} ❲{}❳ — {}",
-                icon, sev, base, is.rule, is.message
-            ));
+            lines.push(format!("  {}", compose_issue_line(is, &base, color, ui)));
+        }
+    }
+    lines
+}
+
+/// Compose flat human-readable lint lines (excluding summary): one line per
+/// issue with its full relative path and no directory headers.
+pub fn compose_lint_flat_lines(res: &LintResult, color: bool, ui: &UiCfg) -> Vec<String> {
+    res.issues
+        .iter()
+        .map(|is| compose_issue_line(is, &is.file, color, ui))
+        .collect()
+}
+
+/// One human issue line, naming the file as `name` plus its position.
+fn compose_issue_line(is: &crate::models::Issue, name: &str, color: bool, ui: &UiCfg) -> String {
+    let sev = severity_label(&is.severity, color, ui);
+    let icon = severity_icon(&is.severity, color, ui);
+    let name = match (is.line, is.col) {
+        (Some(l), Some(c)) => format!("{}:{}:{}", name, l, c),
+        _ => name.to_string(),
+    };
+    let name = if color { name.bold().to_string() } else { name };
+    format!("{} {} {} ❲{}❳ — {}", icon, sev, name, is.rule, is.message)
+}
+
+/// Compose the `lint --fix` section (pure): one `fixed` line per resolved
+/// issue, printed ahead of the remaining issues. Empty when nothing was fixed.
+pub fn compose_lint_fixed_lines(res: &LintResult, color: bool) -> Vec<String> {
+    if res.fixed.is_empty() {
+        return Vec::new();
+    }
+    let header = format!("✔ Fixed {} issue(s)", res.fixed.len());
+    let mut lines = vec![if color {
+        header.green().bold().to_string()
+    } else {
+        header
+    }];
+    for is in &res.fixed {
+        let tag = if color {
+            "⟦fixed⟧".green().to_string()
+        } else {
+            "⟦fixed⟧".to_string()
+        };
+        lines.push(format!(
+            "  ✔ {} {} ❲{}❳ — {}",
+            tag, is.file, is.rule, is.message
+        ));
+    }
+    lines
+}
+
+/// Compose the `lint --diff-baseline` section (pure): one `resolved` line
+/// per baseline entry that no longer occurs. Empty when none did.
+pub fn compose_lint_resolved_lines(res: &LintResult, color: bool) -> Vec<String> {
+    if res.resolved.is_empty() {
+        return Vec::new();
+    }
+    let header = format!("✔ Resolved {} baselined issue(s)", res.resolved.len());
+    let mut lines = vec![if color {
+        header.green().bold().to_string()
+    } else {
+        header
+    }];
+    for e in &res.resolved {
+        let tag = if color {
+            "⟦resolved⟧".green().to_string()
+        } else {
+            "⟦resolved⟧".to_string()
+        };
+        lines.push(format!(
+            "  ✔ {} {} ❲{}❳ {} — {}",
+            tag, e.file, e.rule, e.path, e.message
+        ));
+    }
+    lines
+}
+
+/// Compose a SARIF 2.1.0 log (pure) with a single run: one `result` per
+/// issue, with the file as the artifact URI and the JSON path as a logical
+/// location (no line/column). Run errors become tool notifications.
+pub fn compose_sarif_json(res: &LintResult, errors: &[RunError]) -> JsonVal {
+    let mut rule_ids: Vec<&str> = Vec::new();
+    for is in &res.issues {
+        if !rule_ids.contains(&is.rule.as_str()) {
+            rule_ids.push(&is.rule);
+        }
+    }
+    let results: Vec<JsonVal> = res
+        .issues
+        .iter()
+        .map(|is| {
+            let level = match severity_key(&is.severity) {
+                "error" => "error",
+                "warning" => "warning",
+                _ => "note",
+            };
+            let uri = is.file.replace('\\', "/");
+            let mut physical = json!({"artifactLocation": {"uri": uri.trim_start_matches("./")}});
+            if let (Some(line), Some(col)) = (is.line, is.col) {
+                physical["region"] = json!({"startLine": line, "startColumn": col});
+            }
+            json!({
+                "ruleId": is.rule,
+                "ruleIndex": rule_ids.iter().position(|r| *r == is.rule),
+                "level": level,
+                "message": {"text": is.message},
+                "locations": [{
+                    "physicalLocation": physical,
+                    "logicalLocations": [{"fullyQualifiedName": is.path, "kind": "member"}]
+                }],
+                "properties": {"jsonPath": is.path}
+            })
+        })
+        .collect();
+    let notifications: Vec<JsonVal> = errors
+        .iter()
+        .map(|e| json!({"level": "error", "message": {"text": e.message}}))
+        .collect();
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "rigra",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rule_ids.iter().map(|id| json!({"id": id})).collect::<Vec<_>>()
+                }
+            },
+            "invocations": [{
+                "executionSuccessful": errors.is_empty(),
+                "toolExecutionNotifications": notifications
+            }],
+            "results": results
+        }]
+    })
+}
+
+/// Compose GitHub Actions workflow commands (pure), one `::error`,
+/// `::warning` or `::notice` annotation per issue. Run errors become
+/// file-less `::error` commands.
+pub fn compose_lint_github(res: &LintResult, errors: &[RunError]) -> Vec<String> {
+    let mut lines: Vec<String> = res
+        .issues
+        .iter()
+        .map(|is| {
+            let level = match severity_key(&is.severity) {
+                "error" => "error",
+                "warning" => "warning",
+                _ => "notice",
+            };
+            let file = is.file.replace('\\', "/");
+            let position = match (is.line, is.col) {
+                (Some(l), Some(c)) => format!(",line={},col={}", l, c),
+                _ => String::new(),
+            };
+            format!(
+                "::{} file={}{}::{}",
+                level,
+                escape_workflow_property(file.trim_start_matches("./")),
+                position,
+                escape_workflow_data(&format!("[{}] {}", is.rule, is.message))
+            )
+        })
+        .collect();
+    for e in errors {
+        lines.push(format!("::error::{}", escape_workflow_data(&e.message)));
+    }
+    lines
+}
+
+/// Escape a workflow command message (`%`, CR and LF).
+fn escape_workflow_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a workflow command property value; `:` and `,` delimit properties.
+fn escape_workflow_property(s: &str) -> String {
+    escape_workflow_data(s)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+/// Compose TAP version 13 lint output (pure). Each issue is one assertion,
+/// failing for errors, with a YAML block carrying its details; a clean run
+/// emits a single passing assertion. Run errors follow as `#` comments.
+pub fn compose_lint_tap(res: &LintResult, errors: &[RunError]) -> Vec<String> {
+    let mut lines = vec!["TAP version 13".to_string()];
+    if res.issues.is_empty() {
+        lines.push("1..1".to_string());
+        lines.push("ok 1 - no convention violations".to_string());
+    } else {
+        lines.push(format!("1..{}", res.issues.len()));
+        for (i, is) in res.issues.iter().enumerate() {
+            let status = if is.severity == "error" {
+                "not ok"
+            } else {
+                "ok"
+            };
+            lines.push(format!("{} {} - {} {}", status, i + 1, is.rule, is.file));
+            lines.push("  ---".to_string());
+            // JSON strings are valid YAML double-quoted scalars
+            for (k, v) in [
+                ("severity", &is.severity),
+                ("file", &is.file),
+                ("path", &is.path),
+                ("message", &is.message),
+            ] {
+                lines.push(format!("  {}: {}", k, json!(v)));
+            }
+            lines.push("  ...".to_string());
         }
     }
+    for e in errors {
+        lines.push(format!("# error: {}", e.message));
+    }
     lines
 }
 
 /// Compose format JSON object (pure) for testing/snapshot purposes.
-pub fn compose_format_json(results: &[FormatResult], write: bool, diff: bool) -> JsonVal {
+pub fn compose_format_json(
+    results: &[FormatResult],
+    write: bool,
+    diff: bool,
+    diff_context: usize,
+) -> JsonVal {
     let items: Vec<_> = results
         .iter()
         .map(|r| {
@@ -425,7 +1233,7 @@ pub fn compose_format_json(results: &[FormatResult], write: bool, diff: bool) ->
                 "changed": r.changed,
                 "wrote": write && r.changed,
                 "preview": if !write { r.preview.as_ref() } else { None },
-                "diff": if diff && !write { build_naive_diff(r.original.as_deref(), r.preview.as_deref()) } else { None }
+                "diff": if diff && !write { build_unified_diff(r.original.as_deref(), r.preview.as_deref(), diff_context) } else { None }
             })
         })
         .collect();
@@ -434,7 +1242,15 @@ pub fn compose_format_json(results: &[FormatResult], write: bool, diff: bool) ->
         "total": results.len(),
         "wrote": if write { results.iter().filter(|r| r.changed).count() } else { 0 },
     });
-    json!({"results": items, "summary": summary})
+    // Partitioned file lists for scripts that only need names
+    let (changed, unchanged): (Vec<&FormatResult>, Vec<&FormatResult>) =
+        results.iter().partition(|r| r.changed);
+    json!({
+        "results": items,
+        "changed": changed.iter().map(|r| r.file.as_str()).collect::<Vec<_>>(),
+        "unchanged": unchanged.iter().map(|r| r.file.as_str()).collect::<Vec<_>>(),
+        "summary": summary,
+    })
 }
 
 #[cfg(test)]
@@ -458,18 +1274,255 @@ mod tests {
             },
         ];
         // Case: write=false, diff=true ⇒ previews and diffs present for changed item
-        let out = compose_format_json(&results, false, true);
+        let out = compose_format_json(&results, false, true, 3);
         assert_eq!(out["summary"]["changed"], 1);
         assert_eq!(out["summary"]["wrote"], 0);
         assert!(out["results"][0]["preview"].is_string());
         assert!(out["results"][0]["diff"].is_string());
         // Case: write=true ⇒ no preview/diff, wrote equals changed
-        let out2 = compose_format_json(&results, true, false);
+        let out2 = compose_format_json(&results, true, false, 3);
         assert_eq!(out2["summary"]["wrote"], 1);
         assert!(out2["results"][0]["preview"].is_null());
         assert!(out2["results"][0]["diff"].is_null());
     }
 
+    #[test]
+    fn test_compose_format_json_partitions_changed_and_unchanged() {
+        let result = |file: &str, changed: bool| FormatResult {
+            file: file.into(),
+            changed,
+            preview: None,
+            original: None,
+        };
+        let results = vec![
+            result("a.json", true),
+            result("b.json", false),
+            result("c.json", true),
+            result("d.json", false),
+        ];
+        let out = compose_format_json(&results, false, false, 3);
+        assert_eq!(out["changed"], json!(["a.json", "c.json"]));
+        assert_eq!(out["unchanged"], json!(["b.json", "d.json"]));
+
+        let out = compose_format_json(&[], true, false, 3);
+        assert_eq!(out["changed"], json!([]));
+        assert_eq!(out["unchanged"], json!([]));
+    }
+
+    #[test]
+    fn test_unified_diff_hunk_has_exactly_n_context_lines() {
+        let old: String = (1..=12).map(|i| format!("line{}\n", i)).collect();
+        let new = old.replace("line6\n", "LINE6\n");
+        let d = build_unified_diff(Some(&old), Some(&new), 2).unwrap();
+        assert_eq!(
+            d,
+            "@@ -4,5 +4,5 @@\n line4\n line5\n-line6\n+LINE6\n line7\n line8"
+        );
+        // Zero context emits only the changed lines
+        let d0 = build_unified_diff(Some(&old), Some(&new), 0).unwrap();
+        assert_eq!(d0, "@@ -6,1 +6,1 @@\n-line6\n+LINE6");
+    }
+
+    #[test]
+    fn test_unified_diff_splits_distant_changes_and_json_embeds_it() {
+        let old: String = (1..=40).map(|i| format!("line{}\n", i)).collect();
+        let new = old
+            .replace("line3\n", "LINE3\n")
+            .replace("line30\n", "")
+            .replace("line40\n", "line40\nline41\n");
+        let d = build_unified_diff(Some(&old), Some(&new), 1).unwrap();
+        let headers: Vec<&str> = d.lines().filter(|l| l.starts_with("@@")).collect();
+        assert_eq!(
+            headers,
+            vec!["@@ -2,3 +2,3 @@", "@@ -29,3 +29,2 @@", "@@ -40,1 +39,2 @@"]
+        );
+        // Lines far from any change are never emitted
+        assert!(!d.lines().any(|l| l == " line10" || l == " line20"));
+        assert!(d.lines().all(|l| l.starts_with(['@', ' ', '+', '-'])));
+
+        let results = vec![FormatResult {
+            file: "big.json".into(),
+            changed: true,
+            preview: Some(new.clone()),
+            original: Some(old.clone()),
+        }];
+        let out = compose_format_json(&results, false, true, 1);
+        assert_eq!(out["results"][0]["diff"], d.as_str());
+    }
+
+    #[test]
+    fn test_compose_format_human_lines_omit_unchanged() {
+        let results = vec![
+            FormatResult {
+                file: "a.json".into(),
+                changed: true,
+                preview: Some("{\n  \"x\": 1\n}".into()),
+                original: Some("{\n  \"x\":1\n}".into()),
+            },
+            FormatResult {
+                file: "b.json".into(),
+                changed: false,
+                preview: None,
+                original: Some("{\n  \"y\":2\n}".into()),
+            },
+        ];
+        let lines = compose_format_human_lines(&results, false, true, false, 3);
+        assert_eq!(lines, vec!["✎ formatted » a.json".to_string()]);
+        let lines = compose_format_human_lines(&results, false, false, false, 3);
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].starts_with("--- a.json\n"));
+        assert!(lines.iter().all(|l| !l.contains("b.json")));
+        let tidy = compose_format_human_lines(&results[1..], false, true, false, 3);
+        assert_eq!(
+            tidy,
+            vec!["✔ ⟦stable⟧ Everything is tidy. No changes.".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_compose_lint_tap_plan_matches_assertions() {
+        let issue = |severity: &str, path: &str| crate::models::Issue {
+            file: "p.json".into(),
+            rule: "pkg".into(),
+            severity: severity.into(),
+            path: path.into(),
+            message: "Field \"x\" missing".into(),
+            line: None,
+            col: None,
+        };
+        let res = crate::models::LintResult {
+            issues: vec![issue("error", "$.x"), issue("warning", "$.y")],
+            summary: crate::models::Summary {
+                errors: 1,
+                warnings: 1,
+                infos: 0,
+                files: 1,
+                fixed: None,
+            },
+            fixed: Vec::new(),
+            resolved: Vec::new(),
+        };
+        let lines = compose_lint_tap(&res, &[]);
+        assert_eq!(lines[0], "TAP version 13");
+        let plan: usize = lines[1].strip_prefix("1..").unwrap().parse().unwrap();
+        let points: Vec<_> = lines
+            .iter()
+            .filter(|l| l.starts_with("ok ") || l.starts_with("not ok "))
+            .collect();
+        assert_eq!(plan, points.len());
+        assert_eq!(points[0], "not ok 1 - pkg p.json");
+        assert_eq!(points[1], "ok 2 - pkg p.json");
+        assert!(lines.contains(&"  message: \"Field \\\"x\\\" missing\"".to_string()));
+
+        let clean = crate::models::LintResult {
+            issues: Vec::new(),
+            summary: crate::models::Summary {
+                errors: 0,
+                warnings: 0,
+                infos: 0,
+                files: 1,
+                fixed: None,
+            },
+            fixed: Vec::new(),
+            resolved: Vec::new(),
+        };
+        assert_eq!(
+            compose_lint_tap(&clean, &[]),
+            vec!["TAP version 13", "1..1", "ok 1 - no convention violations"]
+        );
+    }
+
+    /// Apply a single-file patch from `compose_format_patch` to `old`.
+    fn apply_patch(old: &str, patch: &str) -> String {
+        let old: Vec<&str> = old.split_inclusive('\n').collect();
+        let mut out: Vec<String> = Vec::new();
+        let mut cursor = 0usize;
+        for line in patch.lines().skip(3) {
+            if let Some(h) = line.strip_prefix("@@ -") {
+                let (start, len) = h.split(' ').next().unwrap().split_once(',').unwrap();
+                let (start, len): (usize, usize) = (start.parse().unwrap(), len.parse().unwrap());
+                let upto = if len == 0 { start } else { start - 1 };
+                out.extend(old[cursor..upto].iter().map(|l| l.to_string()));
+                cursor = upto;
+            } else if line.starts_with('\\') {
+                if let Some(last) = out.last_mut() {
+                    last.pop();
+                }
+            } else {
+                let (tag, text) = line.split_at(1);
+                match tag {
+                    " " => {
+                        out.push(format!("{}\n", text));
+                        cursor += 1;
+                    }
+                    "-" => cursor += 1,
+                    _ => out.push(format!("{}\n", text)),
+                }
+            }
+        }
+        out.extend(old[cursor..].iter().map(|l| l.to_string()));
+        out.concat()
+    }
+
+    #[test]
+    fn test_diff_lines_is_minimal_and_rebuilds_both_sides() {
+        let cases: [(&str, &str, usize); 5] = [
+            ("abcabba", "cbabac", 5),
+            ("abc", "abc", 0),
+            ("", "xy", 2),
+            ("xaybzc", "abc", 3),
+            ("abcdef", "fedcba", 10),
+        ];
+        for (old, new, edits) in cases {
+            let old: Vec<&str> = old.split("").filter(|c| !c.is_empty()).collect();
+            let new: Vec<&str> = new.split("").filter(|c| !c.is_empty()).collect();
+            let ops = diff_lines(&old, &new);
+            let side = |skip: char| -> Vec<&str> {
+                ops.iter()
+                    .filter(|(t, _)| *t != skip)
+                    .map(|(_, l)| *l)
+                    .collect()
+            };
+            assert_eq!(side('+'), old);
+            assert_eq!(side('-'), new);
+            assert_eq!(ops.iter().filter(|(t, _)| *t != ' ').count(), edits);
+        }
+        // Large inputs with a change in the middle stay cheap
+        let old: Vec<String> = (0..50_000).map(|i| format!("line {}", i)).collect();
+        let mut new = old.clone();
+        new[25_000] = "changed".into();
+        let old: Vec<&str> = old.iter().map(String::as_str).collect();
+        let new: Vec<&str> = new.iter().map(String::as_str).collect();
+        assert_eq!(diff_lines(&old, &new).len(), 50_001);
+    }
+
+    #[test]
+    fn test_compose_format_patch_applies_to_formatted_output() {
+        let original = "{\"version\":\"1.0.0\",\n\"name\":\"x\",\n\"a\":1,\n\"b\":2,\n\"c\":3,\n\"d\":4,\n\"e\":5,\n\"f\":6,\n\"g\":7,\n\"z\":true}";
+        let formatted = "{\n  \"name\": \"x\",\n  \"version\": \"1.0.0\",\n  \"a\": 1,\n  \"b\": 2,\n  \"c\": 3,\n  \"d\": 4,\n  \"e\": 5,\n  \"f\": 6,\n  \"g\": 7,\n  \"z\": true\n}\n";
+        let results = vec![
+            FormatResult {
+                file: "/repo/pkg/package.json".into(),
+                changed: true,
+                preview: Some(formatted.into()),
+                original: Some(original.into()),
+            },
+            FormatResult {
+                file: "/repo/other.json".into(),
+                changed: false,
+                preview: None,
+                original: Some("{}\n".into()),
+            },
+        ];
+        let patch = compose_format_patch(&results, Path::new("/repo"), 1);
+        assert!(patch.starts_with(
+            "diff --git a/pkg/package.json b/pkg/package.json\n--- a/pkg/package.json\n+++ b/pkg/package.json\n@@ "
+        ));
+        assert!(patch.contains("\\ No newline at end of file\n"));
+        assert!(!patch.contains("other.json"));
+        assert_eq!(apply_patch(original, &patch), formatted);
+    }
+
     #[test]
     fn test_compose_lint_json_shape() {
         let res = crate::models::LintResult {
@@ -479,19 +1532,137 @@ mod tests {
                 severity: "warn".into(),
                 path: "$.x".into(),
                 message: "msg".into(),
+                line: None,
+                col: None,
             }],
             summary: crate::models::Summary {
                 errors: 0,
                 warnings: 1,
                 infos: 0,
                 files: 1,
+                fixed: None,
             },
+            fixed: Vec::new(),
+            resolved: Vec::new(),
         };
         let out = compose_lint_json(&res);
         assert_eq!(out["summary"]["warnings"], 1);
         assert_eq!(out["issues"][0]["path"], "$.x");
     }
 
+    #[test]
+    fn test_json_compact_matches_pretty() {
+        let val = json!({"issues": [{"path": "$.a", "message": "x"}], "summary": {"errors": 1}});
+        let pretty = render_json(&val, "json").unwrap();
+        let compact = render_json(&val, "json-compact").unwrap();
+        assert!(pretty.contains('\n'));
+        assert!(!compact.contains('\n'));
+        assert_eq!(serde_json::from_str::<JsonVal>(&compact).unwrap(), val);
+        assert_eq!(serde_json::from_str::<JsonVal>(&pretty).unwrap(), val);
+        assert!(is_json("json-compact") && is_json("json") && !is_json("sarif"));
+    }
+
+    #[test]
+    fn test_compose_lint_github_workflow_commands() {
+        let issue = |severity: &str, message: &str| crate::models::Issue {
+            file: "./pkg/package.json".into(),
+            rule: "pkg".into(),
+            severity: severity.into(),
+            path: "$.name".into(),
+            message: message.into(),
+            line: None,
+            col: None,
+        };
+        let res = crate::models::LintResult {
+            issues: vec![
+                issue("error", "Field 'name' is required at $.name"),
+                issue("warn", "100% wrong\nsecond line"),
+                issue("info", "fyi"),
+            ],
+            summary: crate::models::Summary {
+                errors: 1,
+                warnings: 1,
+                infos: 1,
+                files: 1,
+                fixed: None,
+            },
+            fixed: Vec::new(),
+            resolved: Vec::new(),
+        };
+        let errors = vec![RunError {
+            message: "Failed to read a,b:c".into(),
+        }];
+        assert_eq!(
+            compose_lint_github(&res, &errors),
+            vec![
+                "::error file=pkg/package.json::[pkg] Field 'name' is required at $.name",
+                "::warning file=pkg/package.json::[pkg] 100%25 wrong%0Asecond line",
+                "::notice file=pkg/package.json::[pkg] fyi",
+                "::error::Failed to read a,b:c",
+            ]
+        );
+        assert_eq!(escape_workflow_property("a:b,c\r"), "a%3Ab%2Cc%0D");
+    }
+
+    #[test]
+    fn test_compose_sarif_json_shape() {
+        let issue = |rule: &str, severity: &str, path: &str| crate::models::Issue {
+            file: "./pkg/package.json".into(),
+            rule: rule.into(),
+            severity: severity.into(),
+            path: path.into(),
+            message: format!("bad {}", path),
+            line: None,
+            col: None,
+        };
+        let res = crate::models::LintResult {
+            issues: vec![
+                issue("pkg", "error", "$.name"),
+                issue("order", "warn", "$"),
+                issue("pkg", "info", "$.version"),
+            ],
+            summary: crate::models::Summary {
+                errors: 1,
+                warnings: 1,
+                infos: 1,
+                files: 1,
+                fixed: None,
+            },
+            fixed: Vec::new(),
+            resolved: Vec::new(),
+        };
+        let errors = vec![RunError {
+            message: "Failed to read x".into(),
+        }];
+        let out = compose_sarif_json(&res, &errors);
+        assert_eq!(out["version"], "2.1.0");
+        let run = &out["runs"][0];
+        assert_eq!(run["tool"]["driver"]["name"], "rigra");
+        assert_eq!(
+            run["tool"]["driver"]["rules"],
+            json!([{"id": "pkg"}, {"id": "order"}])
+        );
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 3);
+        let levels: Vec<_> = results.iter().map(|r| r["level"].clone()).collect();
+        assert_eq!(levels, vec!["error", "warning", "note"]);
+        assert_eq!(results[2]["ruleId"], "pkg");
+        assert_eq!(results[2]["ruleIndex"], 0);
+        assert_eq!(results[1]["ruleIndex"], 1);
+        assert_eq!(results[0]["message"]["text"], "bad $.name");
+        let loc = &results[0]["locations"][0];
+        assert_eq!(
+            loc["physicalLocation"]["artifactLocation"]["uri"],
+            "pkg/package.json"
+        );
+        assert_eq!(loc["logicalLocations"][0]["fullyQualifiedName"], "$.name");
+        assert_eq!(run["invocations"][0]["executionSuccessful"], false);
+        assert_eq!(
+            run["invocations"][0]["toolExecutionNotifications"][0]["message"]["text"],
+            "Failed to read x"
+        );
+    }
+
     #[test]
     fn test_compose_lint_grouped_lines_headers_and_basenames() {
         let res = crate::models::LintResult {
@@ -502,6 +1673,8 @@ mod tests {
                     severity: "error".into(),
                     path: "$.repository.directory".into(),
                     message: "Field 'repository.directory' is required".into(),
+                    line: None,
+                    col: None,
                 },
                 crate::models::Issue {
                     file: "conventions/hyperedge/ts-lib-mono/package.json".into(),
@@ -509,6 +1682,8 @@ mod tests {
                     severity: "error".into(),
                     path: "$.author".into(),
                     message: "Author must be in the format 'Name <email> (url)'".into(),
+                    line: None,
+                    col: None,
                 },
                 crate::models::Issue {
                     file: "package.json".into(),
@@ -516,6 +1691,8 @@ mod tests {
                     severity: "warn".into(),
                     path: "$.name".into(),
                     message: "Type mismatch at $.name, got string".into(),
+                    line: None,
+                    col: None,
                 },
             ],
             summary: crate::models::Summary {
@@ -523,9 +1700,15 @@ mod tests {
                 warnings: 1,
                 infos: 0,
                 files: 3,
+                fixed: None,
             },
+            fixed: Vec::new(),
+            resolved: Vec::new(),
         };
-        let lines = compose_lint_grouped_lines(&res, false);
+        let lines = compose_lint_grouped_lines(&res, false, &UiCfg::default());
+        assert!(lines
+            .iter()
+            .any(|l| l.starts_with("  ✖ ⟦error⟧ package.json")));
         // Expect three headers (two nested dirs + '.') and three item lines
         assert!(lines.iter().any(|l| l == "conventions/hyperedge/ts-base"));
         assert!(lines
@@ -541,4 +1724,110 @@ mod tests {
             .iter()
             .any(|l| l.contains(" package.json ❲pkgjson-root❳ — Type mismatch at $.name")));
     }
+
+    #[test]
+    fn test_compose_lint_flat_lines_use_full_paths_without_headers() {
+        let issue = |file: &str, line: Option<usize>| crate::models::Issue {
+            file: file.into(),
+            rule: "pkg".into(),
+            severity: "error".into(),
+            path: "$.name".into(),
+            message: "Field 'name' is required".into(),
+            line,
+            col: line,
+        };
+        let res = crate::models::LintResult {
+            issues: vec![
+                issue("packages/a/package.json", Some(2)),
+                issue("package.json", None),
+            ],
+            summary: crate::models::Summary {
+                errors: 2,
+                warnings: 0,
+                infos: 0,
+                files: 2,
+                fixed: None,
+            },
+            fixed: Vec::new(),
+            resolved: Vec::new(),
+        };
+        let lines = compose_lint_flat_lines(&res, false, &UiCfg::default());
+        assert_eq!(
+            lines,
+            vec![
+                "✖ ⟦error⟧ packages/a/package.json:2:2 ❲pkg❳ — Field 'name' is required",
+                "✖ ⟦error⟧ package.json ❲pkg❳ — Field 'name' is required",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compose_lint_lines_use_custom_ui_labels_and_icons() {
+        let res = crate::models::LintResult {
+            issues: vec![
+                crate::models::Issue {
+                    file: "package.json".into(),
+                    rule: "r".into(),
+                    severity: "error".into(),
+                    path: "$.name".into(),
+                    message: "missing".into(),
+                    line: None,
+                    col: None,
+                },
+                crate::models::Issue {
+                    file: "package.json".into(),
+                    rule: "r".into(),
+                    severity: "warn".into(),
+                    path: "$.x".into(),
+                    message: "odd".into(),
+                    line: None,
+                    col: None,
+                },
+            ],
+            summary: crate::models::Summary {
+                errors: 1,
+                warnings: 0,
+                infos: 1,
+                files: 1,
+                fixed: None,
+            },
+            fixed: Vec::new(),
+            resolved: Vec::new(),
+        };
+        let mut ui = UiCfg::default();
+        ui.labels.insert("error".into(), "[ERR]".into());
+        ui.icons.insert("error".into(), "x".into());
+        ui.labels.insert("warning".into(), "[WARN]".into());
+        let lines = compose_lint_grouped_lines(&res, false, &ui);
+        assert!(lines.contains(&"  x [ERR] package.json ❲r❳ — missing".to_string()));
+        // Unset icon falls back to the default glyph
+        assert!(lines.contains(&"  ▲ [WARN] package.json ❲r❳ — odd".to_string()));
+    }
+
+    #[test]
+    fn test_exit_trailer_names_cause_and_count() {
+        assert_eq!(
+            compose_exit_trailer(&ExitCause::LintErrors(3)),
+            "Exiting 1: 3 lint errors."
+        );
+        assert_eq!(
+            compose_exit_trailer(&ExitCause::LintErrors(1)),
+            "Exiting 1: 1 lint error."
+        );
+        assert_eq!(
+            compose_exit_trailer(&ExitCause::FormatChanges(2)),
+            "Exiting 1: 2 files need formatting."
+        );
+        assert_eq!(
+            compose_exit_trailer(&ExitCause::SyncPending(1)),
+            "Exiting 1: 1 file needs syncing."
+        );
+        assert_eq!(
+            compose_exit_trailer(&ExitCause::TooManyWarnings {
+                warnings: 4,
+                max: 2
+            }),
+            "Exiting 3: 4 lint warnings (--max-warnings 2)."
+        );
+    }
 }
@@ -0,0 +1,175 @@
+//! Structural comparison of two policy files (`rigra diff-policy`).
+//!
+//! Checks are matched by `kind` plus their `field`/`fields`; repeated keys
+//! are paired in declaration order. Other top-level sections (`order`,
+//! `linebreak`, `formatting`, `defaultLevel`) are compared as a whole.
+
+use crate::models::policy::Policy;
+use serde_json::Value as Json;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+/// Differences between an old and a new policy.
+#[derive(Debug, Default)]
+pub struct PolicyDiff {
+    /// Checks only in the new policy
+    pub added: Vec<Json>,
+    /// Checks only in the old policy
+    pub removed: Vec<Json>,
+    /// Checks present in both with different settings, as `(old, new)`
+    pub changed: Vec<(Json, Json)>,
+    /// Top-level sections that differ, as `(key, old, new)`; a missing side is null
+    pub sections: Vec<(String, Json, Json)>,
+}
+
+impl PolicyDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+            && self.sections.is_empty()
+    }
+}
+
+/// Load a policy file as a generic JSON tree, after checking it parses as a
+/// `Policy` so invalid files are rejected up front.
+pub fn load(path: &Path) -> Result<Json, String> {
+    let s = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read policy '{}': {}", path.to_string_lossy(), e))?;
+    let invalid = |e: String| format!("Invalid policy '{}': {}", path.to_string_lossy(), e);
+    Policy::parse(path, &s).map_err(invalid)?;
+    if crate::utils::is_json5(path) {
+        crate::json5::parse(&s).map_err(invalid)
+    } else {
+        let v: toml::Value = toml::from_str(&s).map_err(|e| invalid(e.to_string()))?;
+        serde_json::to_value(v).map_err(|e| invalid(e.to_string()))
+    }
+}
+
+/// Compare two policies loaded with `load`.
+pub fn diff(old: &Json, new: &Json) -> PolicyDiff {
+    let mut out = PolicyDiff::default();
+    let checks = |p: &Json| -> Vec<Json> {
+        p.get("checks")
+            .and_then(|c| c.as_array())
+            .cloned()
+            .unwrap_or_default()
+    };
+    let (old_checks, mut new_checks) = (checks(old), checks(new));
+    for o in old_checks {
+        let key = check_key(&o);
+        match new_checks.iter().position(|n| check_key(n) == key) {
+            Some(i) => {
+                let n = new_checks.remove(i);
+                if n != o {
+                    out.changed.push((o, n));
+                }
+            }
+            None => out.removed.push(o),
+        }
+    }
+    out.added = new_checks;
+
+    let keys: BTreeSet<&String> = [old, new]
+        .iter()
+        .filter_map(|p| p.as_object())
+        .flat_map(|m| m.keys())
+        .filter(|k| *k != "checks")
+        .collect();
+    for k in keys {
+        let (o, n) = (old.get(k), new.get(k));
+        if o != n {
+            out.sections.push((
+                k.clone(),
+                o.cloned().unwrap_or(Json::Null),
+                n.cloned().unwrap_or(Json::Null),
+            ));
+        }
+    }
+    out
+}
+
+/// Identity of a check for matching: `kind` plus its target field(s).
+pub fn check_key(c: &Json) -> String {
+    let kind = c.get("kind").and_then(|k| k.as_str()).unwrap_or("?");
+    let target = match (c.get("field"), c.get("fields")) {
+        (Some(Json::String(f)), _) => f.clone(),
+        (_, Some(Json::Array(fs))) => fs
+            .iter()
+            .map(|f| {
+                f.as_str()
+                    .map(String::from)
+                    .unwrap_or_else(|| f.to_string())
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        (_, Some(Json::Object(fs))) => fs.keys().cloned().collect::<Vec<_>>().join(", "),
+        _ => String::new(),
+    };
+    format!("{} [{}]", kind, target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_lists_added_removed_changed_and_order() {
+        let td = tempfile::tempdir().unwrap();
+        let old_p = td.path().join("old.toml");
+        let new_p = td.path().join("new.toml");
+        fs::write(
+            &old_p,
+            r#"
+[order]
+top = [["name"], ["version"]]
+
+[[checks]]
+kind = "required"
+fields = ["name"]
+
+[[checks]]
+kind = "pattern"
+field = "name"
+regex = "^[a-z]+$"
+
+[[checks]]
+kind = "enum"
+field = "license"
+values = ["MIT"]
+"#,
+        )
+        .unwrap();
+        fs::write(
+            &new_p,
+            r#"
+[order]
+top = [["name"], ["version"], ["license"]]
+
+[[checks]]
+kind = "pattern"
+field = "name"
+regex = "^[a-z-]+$"
+
+[[checks]]
+kind = "required"
+fields = ["name"]
+
+[[checks]]
+kind = "required"
+fields = ["license"]
+"#,
+        )
+        .unwrap();
+        let d = diff(&load(&old_p).unwrap(), &load(&new_p).unwrap());
+        let keys = |v: &[Json]| v.iter().map(check_key).collect::<Vec<_>>();
+        assert_eq!(keys(&d.added), vec!["required [license]"]);
+        assert_eq!(keys(&d.removed), vec!["enum [license]"]);
+        assert_eq!(d.changed.len(), 1);
+        assert_eq!(check_key(&d.changed[0].1), "pattern [name]");
+        assert_eq!(d.sections.len(), 1);
+        assert_eq!(d.sections[0].0, "order");
+        assert!(diff(&load(&old_p).unwrap(), &load(&old_p).unwrap()).is_empty());
+    }
+}
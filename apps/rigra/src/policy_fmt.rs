@@ -0,0 +1,166 @@
+//! Canonical policy layout (`rigra policy fmt`).
+//!
+//! Rewrites a TOML policy in a stable layout while keeping its comments:
+//! top-level keys such as `defaultLevel` first, then `[[checks]]`,
+//! `[order]`, `[linebreak]` and `[formatting]`, with unknown sections last.
+//! Each check lists `kind`, then `field`/`fields`, its other keys as written,
+//! and `message`/`level` last. Sections are separated by one blank line and
+//! `=` is surrounded by single spaces.
+
+use crate::models::policy::Policy;
+use toml_edit::{DocumentMut, Item, Table};
+
+/// Top-level sections in canonical order.
+const SECTIONS: &[&str] = &["checks", "order", "linebreak", "formatting"];
+
+/// Format policy TOML text canonically. Errors when the text is not a
+/// valid policy.
+pub fn format_policy(src: &str) -> Result<String, String> {
+    toml::from_str::<Policy>(src).map_err(|e| format!("Failed to parse policy: {}", e))?;
+    let mut doc: DocumentMut = src
+        .parse()
+        .map_err(|e| format!("Failed to parse policy: {}", e))?;
+    let root = doc.as_table_mut();
+    root.sort_values_by(|a, _, b, _| section_rank(a.get()).cmp(&section_rank(b.get())));
+    if let Some(checks) = root
+        .get_mut("checks")
+        .and_then(Item::as_array_of_tables_mut)
+    {
+        for chk in checks.iter_mut() {
+            chk.sort_values_by(|a, _, b, _| check_key_rank(a.get()).cmp(&check_key_rank(b.get())));
+        }
+    }
+    if let Some(order) = root.get_mut("order").and_then(Item::as_table_mut) {
+        order.sort_values_by(|a, _, b, _| (a.get() != "top").cmp(&(b.get() != "top")));
+    }
+    // Headers print by position, so renumber them in key order
+    let mut next = 0;
+    space_values(root);
+    for (_, item) in root.iter_mut() {
+        layout(item, &mut next);
+    }
+    let out = doc.to_string();
+    Ok(format!("{}\n", out.trim()))
+}
+
+fn section_rank(key: &str) -> usize {
+    SECTIONS
+        .iter()
+        .position(|s| *s == key)
+        .unwrap_or(SECTIONS.len())
+}
+
+fn check_key_rank(key: &str) -> u8 {
+    match key {
+        "kind" => 0,
+        "field" | "fields" => 1,
+        "message" => 3,
+        "level" => 4,
+        _ => 2,
+    }
+}
+
+/// Assign header positions depth-first and put one blank line before each
+/// header, keeping any comments above it.
+fn layout(item: &mut Item, next: &mut isize) {
+    match item {
+        Item::Table(t) => layout_table(t, next),
+        Item::ArrayOfTables(aot) => {
+            for t in aot.iter_mut() {
+                layout_table(t, next);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn layout_table(t: &mut Table, next: &mut isize) {
+    t.set_position(Some(*next));
+    *next += 1;
+    if !t.is_implicit() {
+        let prefix = t.decor().prefix().and_then(|p| p.as_str()).unwrap_or("");
+        let prefix = format!("\n{}", prefix.trim_start_matches(['\n', '\r', ' ', '\t']));
+        t.decor_mut().set_prefix(prefix);
+    }
+    space_values(t);
+    for (_, child) in t.iter_mut() {
+        layout(child, next);
+    }
+}
+
+/// Write every `key = value` of `t` with single spaces around `=`, keeping
+/// comments above keys and after values.
+fn space_values(t: &mut Table) {
+    for (mut key, item) in t.iter_mut() {
+        if let Some(v) = item.as_value_mut() {
+            key.leaf_decor_mut().set_suffix(" ");
+            v.decor_mut().set_prefix(" ");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_policy_orders_sections_and_keeps_comments() {
+        let messy = r#"# Package policy
+[order]
+# after top-level keys
+collation = "unicode"
+top = [["name"], ["version"]]
+[order.sub]
+scripts = ["build"]
+
+
+
+[[checks]]
+level = "warning" # kept inline
+# the field being checked
+field = "$.name"
+kind = "pattern"
+regex = "^@acme/"
+[[checks]]
+message="license required"
+fields   = ["license"]
+kind = "required"
+"#;
+        let messy = format!("defaultLevel = \"warning\"\n{}", messy);
+        let out = format_policy(&messy).unwrap();
+        assert_eq!(
+            out,
+            r#"defaultLevel = "warning"
+
+[[checks]]
+kind = "pattern"
+# the field being checked
+field = "$.name"
+regex = "^@acme/"
+level = "warning" # kept inline
+
+[[checks]]
+kind = "required"
+fields = ["license"]
+message = "license required"
+
+# Package policy
+[order]
+top = [["name"], ["version"]]
+# after top-level keys
+collation = "unicode"
+
+[order.sub]
+scripts = ["build"]
+"#
+        );
+        // Canonical output is a fixed point
+        assert_eq!(format_policy(&out).unwrap(), out);
+    }
+
+    #[test]
+    fn test_format_policy_rejects_invalid_policy() {
+        let err = format_policy("[[checks]]\nfield = \"$.a\"\n").unwrap_err();
+        assert!(err.starts_with("Failed to parse policy"), "{}", err);
+    }
+}
@@ -0,0 +1,151 @@
+//! Incremental reader for top-level JSON arrays.
+//!
+//! Backs lint's `[limits].streamArrays` mode: elements are split out of the
+//! byte stream one at a time and parsed individually, so memory stays bounded
+//! by the largest element instead of the whole document.
+
+use serde_json::Value as Json;
+use std::io::{BufReader, Read};
+
+/// Counters reported after streaming an array.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StreamStats {
+    pub elements: usize,
+    /// Largest single element buffered, in bytes
+    pub max_element_bytes: usize,
+}
+
+#[derive(Debug)]
+pub enum StreamError {
+    /// The document is not a top-level array; callers fall back to a full parse
+    NotArray,
+    Io(String),
+    Parse(String),
+}
+
+impl std::fmt::Display for StreamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StreamError::NotArray => write!(f, "not a top-level array"),
+            StreamError::Io(e) => write!(f, "read error: {}", e),
+            StreamError::Parse(e) => write!(f, "invalid JSON: {}", e),
+        }
+    }
+}
+
+/// Call `f` with each element of a top-level JSON array read from `reader`.
+pub fn for_each_array_element<R: Read>(
+    reader: R,
+    mut f: impl FnMut(usize, Json),
+) -> Result<StreamStats, StreamError> {
+    let mut bytes = BufReader::new(reader).bytes();
+    let mut next = move || -> Result<Option<u8>, StreamError> {
+        bytes
+            .next()
+            .transpose()
+            .map_err(|e| StreamError::Io(e.to_string()))
+    };
+    // Skip leading whitespace (and a UTF-8 BOM) up to the opening bracket
+    loop {
+        match next()? {
+            Some(b) if b.is_ascii_whitespace() || b == 0xEF || b == 0xBB || b == 0xBF => {}
+            Some(b'[') => break,
+            _ => return Err(StreamError::NotArray),
+        }
+    }
+    let mut stats = StreamStats::default();
+    let mut buf: Vec<u8> = Vec::new();
+    let (mut depth, mut in_str, mut escaped) = (0usize, false, false);
+    loop {
+        let Some(b) = next()? else {
+            return Err(StreamError::Parse("unterminated array".into()));
+        };
+        if in_str {
+            buf.push(b);
+            match (escaped, b) {
+                (true, _) => escaped = false,
+                (false, b'\\') => escaped = true,
+                (false, b'"') => in_str = false,
+                _ => {}
+            }
+            continue;
+        }
+        match b {
+            b',' | b']' if depth == 0 => {
+                let raw = buf.trim_ascii();
+                if raw.is_empty() {
+                    // `[]` is fine; an empty slot like `[1,,2]` is not
+                    if b == b']' && stats.elements == 0 {
+                        break;
+                    }
+                    return Err(StreamError::Parse("empty array element".into()));
+                }
+                stats.max_element_bytes = stats.max_element_bytes.max(raw.len());
+                let v: Json =
+                    serde_json::from_slice(raw).map_err(|e| StreamError::Parse(e.to_string()))?;
+                f(stats.elements, v);
+                stats.elements += 1;
+                buf.clear();
+                if b == b']' {
+                    break;
+                }
+            }
+            _ => {
+                match b {
+                    b'"' => in_str = true,
+                    b'{' | b'[' => depth += 1,
+                    b'}' | b']' => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+                buf.push(b);
+            }
+        }
+    }
+    // Only whitespace may follow the closing bracket
+    while let Some(b) = next()? {
+        if !b.is_ascii_whitespace() {
+            return Err(StreamError::Parse("trailing content after array".into()));
+        }
+    }
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_streams_elements_in_order() {
+        let src = r#" [ {"a": "x,]"}, [1, [2]], "s\"]", 3 , null ] "#;
+        let mut seen = Vec::new();
+        let stats = for_each_array_element(src.as_bytes(), |i, v| seen.push((i, v))).unwrap();
+        assert_eq!(stats.elements, 5);
+        assert_eq!(seen[0], (0, json!({"a": "x,]"})));
+        assert_eq!(seen[1].1, json!([1, [2]]));
+        assert_eq!(seen[2].1, json!("s\"]"));
+        assert_eq!(seen[4], (4, Json::Null));
+    }
+
+    #[test]
+    fn test_rejects_non_arrays_and_malformed_input() {
+        assert!(matches!(
+            for_each_array_element(&b"{\"a\":1}"[..], |_, _| {}),
+            Err(StreamError::NotArray)
+        ));
+        assert!(matches!(
+            for_each_array_element(&b"[1,,2]"[..], |_, _| {}),
+            Err(StreamError::Parse(_))
+        ));
+        assert!(matches!(
+            for_each_array_element(&b"[1, 2"[..], |_, _| {}),
+            Err(StreamError::Parse(_))
+        ));
+        assert_eq!(
+            for_each_array_element(&b"[]"[..], |_, _| {})
+                .unwrap()
+                .elements,
+            0
+        );
+    }
+}
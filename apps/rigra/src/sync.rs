@@ -1,7 +1,17 @@
 //! Template synchronization based on index `sync` rules.
 //!
 //! Applies file/dir copy operations conditionally per `when` scope tokens.
-//! Uses simple recursive copying for directories.
+//! Uses simple recursive copying for directories. Interactive runs ask for
+//! confirmation (with a short diff) before each write.
+//!
+//! Runs are incremental: after a rule syncs successfully, a stamp of its
+//! source and destination mtimes is stored in `<cache dir>/sync/state`;
+//! later write runs skip rules whose stamp is unchanged unless forced, and
+//! `restore` drops the stamps of the rules it restores. Check and dry runs always compare the target, so
+//! local edits to it still show up as drift.
+//!
+//! Before a write changes an existing target, its previous content is kept
+//! in `<cache dir>/sync/backups/<rule id>/`; `restore` copies it back.
 
 use crate::models::index::Index;
 use crate::models::sync_policy::{SyncPolicy, SyncRule};
@@ -9,7 +19,9 @@ use crate::models::RunError;
 use crate::{config, utils};
 // colorization handled via utils::error_prefix; keep local color uses minimal
 use serde_json::Value as Json;
+use std::collections::BTreeMap;
 use std::fs;
+use std::io::{BufRead, Write};
 use std::path::{Path, PathBuf};
 
 pub struct SyncAction {
@@ -22,26 +34,100 @@ pub struct SyncAction {
 }
 
 /// Run sync actions for the given `scope`, producing a list of results.
+///
+/// When `no_ignore` is true, `[sync].ignore` in rigra.toml is disregarded.
+/// Merge checksums and sync state are kept under `cache_dir` (default
+/// `<repo>/.rigra`). With `force`, rules are re-evaluated even when their
+/// sources are unchanged since the last successful sync. A non-empty
+/// `rules` limits the run to those sync rule ids (`--rule`).
+#[allow(clippy::too_many_arguments)]
 pub fn run_sync(
     repo_root: &str,
     index_path: &str,
+    cache_dir: Option<&Path>,
+    scope: &str,
+    write: bool,
+    no_ignore: bool,
+    force: bool,
+    rules: &[String],
+) -> (Vec<SyncAction>, Vec<RunError>) {
+    sync_with(
+        repo_root, index_path, cache_dir, scope, write, no_ignore, force, None, rules,
+    )
+}
+
+/// Drop actions whose target content would not change (byte-identical
+/// copies or merges), keeping only real writes and pending changes.
+pub fn retain_changed(actions: &mut Vec<SyncAction>) {
+    actions.retain(|a| a.wrote || a.would_write);
+}
+
+/// Confirmation callback for interactive sync: receives the planned action
+/// and a unified diff preview (when the source is a single file).
+pub type ConfirmFn<'a> = &'a mut dyn FnMut(&SyncAction, Option<&str>) -> bool;
+
+/// Run sync in write mode, asking `confirm` before each action that would
+/// write. Declined actions are reported with `wrote = false`.
+#[allow(clippy::too_many_arguments)]
+pub fn run_sync_interactive(
+    repo_root: &str,
+    index_path: &str,
+    cache_dir: Option<&Path>,
+    scope: &str,
+    no_ignore: bool,
+    force: bool,
+    confirm: ConfirmFn,
+    rules: &[String],
+) -> (Vec<SyncAction>, Vec<RunError>) {
+    sync_with(
+        repo_root,
+        index_path,
+        cache_dir,
+        scope,
+        true,
+        no_ignore,
+        force,
+        Some(confirm),
+        rules,
+    )
+}
+
+/// Ask a y/N question on `out` and read the answer from `input`.
+/// Anything other than `y`/`yes` (including EOF) counts as "no".
+pub fn prompt_yes_no<R: BufRead, W: Write>(input: &mut R, out: &mut W, question: &str) -> bool {
+    let _ = write!(out, "{} [y/N] ", question);
+    let _ = out.flush();
+    let mut line = String::new();
+    if input.read_line(&mut line).is_err() {
+        return false;
+    }
+    matches!(line.trim().to_ascii_lowercase().as_str(), "y" | "yes")
+}
+
+#[allow(clippy::too_many_arguments)]
+fn sync_with(
+    repo_root: &str,
+    index_path: &str,
+    cache_dir: Option<&Path>,
     scope: &str,
     write: bool,
+    no_ignore: bool,
+    force: bool,
+    mut confirm: Option<ConfirmFn>,
+    rules: &[String],
 ) -> (Vec<SyncAction>, Vec<RunError>) {
     let root = PathBuf::from(repo_root);
     let idx_path = root.join(index_path);
+    let cache_dir = config::cache_dir_or_default(&root, cache_dir);
     let mut errors: Vec<RunError> = Vec::new();
     let idx_str = match fs::read_to_string(&idx_path) {
         Ok(s) => s,
         Err(e) => {
             eprintln!(
-                "{} {}",
+                "{} Failed to read index: {} — {}. Pass --index or configure rigra.toml.",
                 crate::utils::error_prefix(),
-                format!(
-                    "Failed to read index: {} — {}. Pass --index or configure rigra.toml.",
-                    idx_path.to_string_lossy(),
-                    e
-                )
+                idx_path.to_string_lossy(),
+                e
             );
             errors.push(RunError {
                 message: format!(
@@ -53,17 +139,14 @@ pub fn run_sync(
             return (Vec::new(), errors);
         }
     };
-    let index: Index = match toml::from_str(&idx_str) {
+    let mut index: Index = match toml::from_str(&idx_str) {
         Ok(ix) => ix,
         Err(e) => {
             eprintln!(
-                "{} {}",
+                "{} Failed to parse index TOML: {} — {}",
                 crate::utils::error_prefix(),
-                format!(
-                    "Failed to parse index TOML: {} — {}",
-                    idx_path.to_string_lossy(),
-                    e
-                )
+                idx_path.to_string_lossy(),
+                e
             );
             errors.push(RunError {
                 message: format!(
@@ -75,6 +158,11 @@ pub fn run_sync(
             return (Vec::new(), errors);
         }
     };
+    if let Err(msg) = index.resolve_includes(&idx_path) {
+        eprintln!("{} {}", crate::utils::error_prefix(), msg);
+        errors.push(RunError { message: msg });
+        return (Vec::new(), errors);
+    }
 
     // Load client config (rigra.toml) for sync overrides
     let client_cfg = config::load_config(&root).unwrap_or_default();
@@ -94,14 +182,22 @@ pub fn run_sync(
         .and_then(|s| s.hooks.as_ref().and_then(|h| h.post.clone()))
         .unwrap_or_default();
 
-    // Load external sync policy file
-    let pol_path_rel = match index.sync_ref.as_ref() {
-        Some(r) => r,
+    // Load external sync policy file; an index may instead inherit all of
+    // its sync rules through `include`
+    let mut policy = match index.sync_ref.as_ref() {
+        Some(r) => match read_sync_policy(&resolve_path(&idx_path, r)) {
+            Ok(p) => p,
+            Err(msg) => {
+                eprintln!("{} {}", crate::utils::error_prefix(), msg);
+                errors.push(RunError { message: msg });
+                return (Vec::new(), errors);
+            }
+        },
+        None if !index.included_sync.is_empty() => SyncPolicy::default(),
         None => {
             eprintln!(
-                "{} {}",
-                crate::utils::error_prefix(),
-                "Index missing 'sync' policy reference. Add sync = \"sync.toml\" in index.toml."
+                "{} Index missing 'sync' policy reference. Add sync = \"sync.toml\" in index.toml.",
+                crate::utils::error_prefix()
             );
             errors.push(RunError {
                 message: "Index missing 'sync' policy reference".to_string(),
@@ -109,58 +205,24 @@ pub fn run_sync(
             return (Vec::new(), errors);
         }
     };
-    let pol_path = idx_path
-        .parent()
-        .unwrap_or_else(|| Path::new("."))
-        .join(pol_path_rel);
-    let pol_str = match fs::read_to_string(&pol_path) {
-        Ok(s) => s,
-        Err(e) => {
-            eprintln!(
-                "{} {}",
-                crate::utils::error_prefix(),
-                format!(
-                    "Failed to read sync policy: {} — {}",
-                    pol_path.to_string_lossy(),
-                    e
-                )
-            );
-            errors.push(RunError {
-                message: format!(
-                    "Failed to read sync policy: {} — {}",
-                    pol_path.to_string_lossy(),
-                    e
-                ),
-            });
+    match included_sync_rules(&idx_path, &index, &policy.sync) {
+        Ok(rules) => policy.sync.extend(rules),
+        Err(msg) => {
+            eprintln!("{} {}", crate::utils::error_prefix(), msg);
+            errors.push(RunError { message: msg });
             return (Vec::new(), errors);
         }
-    };
-    let policy: SyncPolicy = match toml::from_str(&pol_str) {
-        Ok(p) => p,
-        Err(e) => {
-            eprintln!(
-                "{} {}",
-                crate::utils::error_prefix(),
-                format!(
-                    "Invalid sync policy TOML: {} — {}",
-                    pol_path.to_string_lossy(),
-                    e
-                )
-            );
-            errors.push(RunError {
-                message: format!(
-                    "Invalid sync policy TOML: {} — {}",
-                    pol_path.to_string_lossy(),
-                    e
-                ),
-            });
-            return (Vec::new(), errors);
-        }
-    };
+    }
 
+    let state_path = state_path(&cache_dir);
+    let mut state = load_state(&state_path);
+    let mut state_changed = false;
     let mut actions = Vec::new();
     for rule in policy.sync {
-        if ignore_ids.contains(&rule.id) {
+        if !rules.is_empty() && !rules.contains(&rule.id) {
+            continue;
+        }
+        if !no_ignore && ignore_ids.contains(&rule.id) {
             continue;
         }
         if !is_rule_enabled(&rule.when, scope) {
@@ -173,15 +235,66 @@ pub fn run_sync(
             .and_then(|c| c.target.clone())
             .unwrap_or_else(|| rule.target.clone());
         let dst = root.join(&dst_target);
+        let client = sync_cfg_map.get(&rule.id);
+        // Unchanged since the last successful sync: nothing to write. Previews
+        // compare the target instead so local edits still count as drift
+        let stamp = sync_stamp(&src, &dst);
+        if write && !force && stamp.is_some() && state.get(&rule.id) == stamp.as_ref() {
+            actions.push(SyncAction {
+                rule_id: rule.id,
+                source: src.to_string_lossy().to_string(),
+                target: dst.to_string_lossy().to_string(),
+                wrote: false,
+                format: rule.format.clone(),
+                would_write: false,
+            });
+            continue;
+        }
+        // Interactive: dry-run first, then ask before applying a pending write
+        let approved = match confirm.as_mut() {
+            Some(ask) if write => {
+                let (_, pending) = apply_sync(&cache_dir, &rule, &src, &dst, client, false, None);
+                if pending {
+                    let planned = SyncAction {
+                        rule_id: rule.id.clone(),
+                        source: src.to_string_lossy().to_string(),
+                        target: dst.to_string_lossy().to_string(),
+                        wrote: false,
+                        format: rule.format.clone(),
+                        would_write: true,
+                    };
+                    let diff = preview_diff(&rule, &src, &dst, client);
+                    ask(&planned, diff.as_deref())
+                } else {
+                    true
+                }
+            }
+            _ => true,
+        };
+        if write && approved && dst.exists() {
+            let (_, pending) = apply_sync(&cache_dir, &rule, &src, &dst, client, false, None);
+            if pending {
+                if let Err(e) = backup_target(&cache_dir, &root, &rule.id, &dst) {
+                    eprintln!("{} {}", crate::utils::warn_prefix(), e);
+                }
+            }
+        }
         let (wrote, would_write) = apply_sync(
-            &root,
+            &cache_dir,
             &rule,
             &src,
             &dst,
-            sync_cfg_map.get(&rule.id),
-            write,
+            client,
+            write && approved,
             Some(&mut errors),
         );
+        // Record the stamp after writing so the destination side is current
+        if write && (wrote || !would_write) {
+            if let Some(s) = sync_stamp(&src, &dst) {
+                state.insert(rule.id.clone(), s);
+                state_changed = true;
+            }
+        }
         actions.push(SyncAction {
             rule_id: rule.id,
             source: src.to_string_lossy().to_string(),
@@ -192,6 +305,12 @@ pub fn run_sync(
         });
     }
 
+    if state_changed {
+        if let Err(e) = save_state(&state_path, &state) {
+            eprintln!("{} {}", crate::utils::warn_prefix(), e);
+        }
+    }
+
     // Run post hooks for wrote actions
     for a in &actions {
         if a.wrote {
@@ -209,6 +328,63 @@ pub fn run_sync(
     (actions, errors)
 }
 
+/// Read and parse a sync policy file.
+pub fn read_sync_policy(path: &Path) -> Result<SyncPolicy, String> {
+    let s = fs::read_to_string(path).map_err(|e| {
+        format!(
+            "Failed to read sync policy: {} — {}",
+            path.to_string_lossy(),
+            e
+        )
+    })?;
+    toml::from_str(&s).map_err(|e| {
+        format!(
+            "Invalid sync policy TOML: {} — {}",
+            path.to_string_lossy(),
+            e
+        )
+    })
+}
+
+/// Load sync rules from policies inherited through index `include`,
+/// rebasing their sources onto the root index. Rule ids must not repeat
+/// across `existing` and the included policies.
+pub fn included_sync_rules(
+    idx_path: &Path,
+    index: &Index,
+    existing: &[SyncRule],
+) -> Result<Vec<SyncRule>, String> {
+    let mut ids: std::collections::HashSet<String> =
+        existing.iter().map(|r| r.id.clone()).collect();
+    let mut out = Vec::new();
+    for inc in &index.included_sync {
+        let pol_path = resolve_path(idx_path, &inc.policy.to_string_lossy());
+        for mut rule in read_sync_policy(&pol_path)?.sync {
+            if !ids.insert(rule.id.clone()) {
+                return Err(format!(
+                    "Duplicate sync rule id '{}' in {}",
+                    rule.id,
+                    pol_path.to_string_lossy()
+                ));
+            }
+            rule.source = inc.base.join(&rule.source).to_string_lossy().to_string();
+            out.push(rule);
+        }
+    }
+    Ok(out)
+}
+
+/// Ids of every sync rule of `index` (loaded from `idx_path`, includes
+/// resolved), own policy first.
+pub fn sync_rule_ids(idx_path: &Path, index: &Index) -> Result<Vec<String>, String> {
+    let mut rules = match index.sync_ref.as_ref() {
+        Some(r) => read_sync_policy(&resolve_path(idx_path, r))?.sync,
+        None => Vec::new(),
+    };
+    rules.extend(included_sync_rules(idx_path, index, &rules)?);
+    Ok(rules.into_iter().map(|r| r.id).collect())
+}
+
 /// Resolve a path relative to the index file location.
 fn resolve_path(idx_path: &Path, rel: &str) -> PathBuf {
     let base = idx_path.parent().unwrap_or_else(|| Path::new("."));
@@ -235,7 +411,6 @@ fn same_content(src: &Path, dst: &Path) -> bool {
 }
 
 fn copy_rule(
-    rule: &SyncRule,
     src: &PathBuf,
     dst: &PathBuf,
     write: bool,
@@ -259,14 +434,11 @@ fn copy_rule(
                     }
                     Err(e) => {
                         eprintln!(
-                            "{} {}",
+                            "{} Failed to copy file '{}' -> '{}': {}",
                             crate::utils::error_prefix(),
-                            format!(
-                                "Failed to copy file '{}' -> '{}': {}",
-                                src.to_string_lossy(),
-                                dst.to_string_lossy(),
-                                e
-                            )
+                            src.to_string_lossy(),
+                            dst.to_string_lossy(),
+                            e
                         );
                         // capture as runtime error on copy failure
                         // Note: still mark would_write as true to signal intended change
@@ -301,7 +473,7 @@ fn copy_rule(
             for entry in entries.flatten() {
                 let p = entry.path();
                 let t = dst.join(entry.file_name());
-                let (_w, _would) = copy_rule(rule, &p, &t, write, errs_opt.as_deref_mut());
+                let (_w, _would) = copy_rule(&p, &t, write, errs_opt.as_deref_mut());
                 if _would {
                     would_write = true;
                 }
@@ -314,9 +486,18 @@ fn copy_rule(
     (wrote, would_write)
 }
 
+/// Whether a rule is applied as a structured JSON merge rather than a copy.
+fn is_json_merge(rule: &SyncRule, client: Option<&config::SyncClientCfg>) -> bool {
+    rule.format
+        .as_deref()
+        .is_some_and(|f| f.eq_ignore_ascii_case("json"))
+        && client.and_then(|c| c.merge.as_ref()).is_some()
+}
+
 /// Apply sync for a rule, performing copy or smart merge depending on rule.format and client config.
+/// Merge checksums are recorded under `cache_dir`.
 pub fn apply_sync(
-    _root: &Path,
+    cache_dir: &Path,
     rule: &SyncRule,
     src: &PathBuf,
     dst: &PathBuf,
@@ -325,14 +506,262 @@ pub fn apply_sync(
     errors: Option<&mut Vec<RunError>>,
 ) -> (bool, bool) {
     // Structured merge only when format=json and client merge config is present
-    if let Some(ct) = rule.format.as_ref() {
-        if ct.as_str().eq_ignore_ascii_case("json") {
-            if let Some(mcfg) = client.and_then(|c| c.merge.as_ref()) {
-                return apply_json_merge(rule, src, dst, mcfg, write, errors);
-            }
+    if is_json_merge(rule, client) {
+        if let Some(mcfg) = client.and_then(|c| c.merge.as_ref()) {
+            return apply_json_merge(cache_dir, src, dst, mcfg, write, errors);
+        }
+    }
+    copy_rule(src, dst, write, errors)
+}
+
+/// Unified diff between the current target and what sync would write.
+/// Only available when the source is a single file.
+fn preview_diff(
+    rule: &SyncRule,
+    src: &Path,
+    dst: &Path,
+    client: Option<&config::SyncClientCfg>,
+) -> Option<String> {
+    if !src.is_file() {
+        return None;
+    }
+    let mcfg = client.and_then(|c| c.merge.as_ref()).filter(|_| {
+        rule.format
+            .as_deref()
+            .is_some_and(|f| f.eq_ignore_ascii_case("json"))
+    });
+    let new = match mcfg {
+        Some(m) => merge_files(src, dst, m).ok()?,
+        None => read_to_string(src)?,
+    };
+    crate::output::build_unified_diff(read_to_string(dst).as_deref(), Some(&new), 3)
+}
+
+/// Stable hash over the normalized sync plan.
+///
+/// Covers each action's rule id, repo-relative source and target, format,
+/// pending state, and the source content, in a fixed order. Identical inputs
+/// always produce the same hash, so CI can compare it with a stored value.
+pub fn plan_hash(repo_root: &Path, actions: &[SyncAction]) -> String {
+    let rel = |p: &str| {
+        let p = Path::new(p);
+        p.strip_prefix(repo_root)
+            .unwrap_or(p)
+            .to_string_lossy()
+            .replace('\\', "/")
+    };
+    let mut lines: Vec<String> = actions
+        .iter()
+        .map(|a| {
+            format!(
+                "{}\t{}\t{}\t{}\t{}\t{:016x}",
+                a.rule_id,
+                rel(&a.source),
+                rel(&a.target),
+                a.format.as_deref().unwrap_or(""),
+                a.would_write,
+                source_digest(Path::new(&a.source))
+            )
+        })
+        .collect();
+    lines.sort();
+    format!("{:016x}", utils::stable_hash(lines.join("\n").as_bytes()))
+}
+
+/// Content digest of a sync source; directories hash their files in
+/// sorted relative-path order. Missing sources hash to a fixed value.
+fn source_digest(p: &Path) -> u64 {
+    if p.is_file() {
+        return fs::read(p).map(|b| utils::stable_hash(&b)).unwrap_or(0);
+    }
+    let mut files = Vec::new();
+    collect_files(p, p, &mut files);
+    files.sort();
+    let mut buf = Vec::new();
+    for (rel, abs) in files {
+        buf.extend_from_slice(rel.as_bytes());
+        buf.push(0);
+        buf.extend_from_slice(
+            &utils::stable_hash(&fs::read(abs).unwrap_or_default()).to_le_bytes(),
+        );
+    }
+    utils::stable_hash(&buf)
+}
+
+fn collect_files(base: &Path, dir: &Path, out: &mut Vec<(String, PathBuf)>) {
+    let Ok(rd) = fs::read_dir(dir) else {
+        return;
+    };
+    for e in rd.flatten() {
+        let p = e.path();
+        if p.is_dir() {
+            collect_files(base, &p, out);
+        } else {
+            let rel = p
+                .strip_prefix(base)
+                .unwrap_or(&p)
+                .to_string_lossy()
+                .replace('\\', "/");
+            out.push((rel, p));
+        }
+    }
+}
+
+fn backups_dir(cache_dir: &Path) -> PathBuf {
+    cache_dir.join("sync").join("backups")
+}
+
+/// Replace the backup for `rule_id` with the current content of `dst`,
+/// remembering its repo-relative location in a `target` file.
+fn backup_target(cache_dir: &Path, root: &Path, rule_id: &str, dst: &Path) -> Result<(), String> {
+    let dir = backups_dir(cache_dir).join(rule_id);
+    let _ = fs::remove_dir_all(&dir);
+    let content = dir.join("content");
+    ensure_parent(&content);
+    copy_tree(dst, &content)
+        .map_err(|e| format!("Failed to back up '{}': {}", dst.to_string_lossy(), e))?;
+    let rel = dst.strip_prefix(root).unwrap_or(dst);
+    fs::write(dir.join("target"), rel.to_string_lossy().as_bytes())
+        .map_err(|e| format!("Failed to record backup target: {}", e))
+}
+
+/// Copy a file or directory tree from `src` to `dst`.
+fn copy_tree(src: &Path, dst: &Path) -> std::io::Result<()> {
+    if src.is_dir() {
+        fs::create_dir_all(dst)?;
+        for entry in fs::read_dir(src)? {
+            let entry = entry?;
+            copy_tree(&entry.path(), &dst.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        fs::copy(src, dst).map(|_| ())
+    }
+}
+
+/// Restore sync targets from their most recent backups (all rules, or just
+/// those in `rules`), returning `(rule id, restored target)` pairs. Fails
+/// when there is nothing to restore.
+pub fn restore(
+    repo_root: &Path,
+    cache_dir: Option<&Path>,
+    rules: &[String],
+) -> Result<Vec<(String, PathBuf)>, String> {
+    let cache_dir = config::cache_dir_or_default(repo_root, cache_dir);
+    let backups = backups_dir(&cache_dir);
+    let mut ids: Vec<String> = match rules {
+        [] => fs::read_dir(&backups)
+            .map(|rd| {
+                rd.flatten()
+                    .map(|e| e.file_name().to_string_lossy().to_string())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        _ => rules.to_vec(),
+    };
+    ids.sort();
+    let mut restored = Vec::new();
+    for id in ids {
+        let dir = backups.join(&id);
+        let Some(rel) = read_to_string(&dir.join("target")) else {
+            continue;
+        };
+        let target = repo_root.join(rel.trim());
+        if dir.join("content").is_file() && target.is_dir() {
+            let _ = fs::remove_dir_all(&target);
+        }
+        ensure_parent(&target);
+        copy_tree(&dir.join("content"), &target)
+            .map_err(|e| format!("Failed to restore '{}': {}", target.to_string_lossy(), e))?;
+        restored.push((id, target));
+    }
+    if restored.is_empty() {
+        return Err(match rules {
+            [] => "No sync backups found".to_string(),
+            [id] => format!("No sync backup found for rule '{}'", id),
+            _ => format!("No sync backup found for rules '{}'", rules.join("', '")),
+        });
+    }
+    // The restored targets no longer match their stamps; the next write reruns them
+    let state_path = state_path(&cache_dir);
+    let mut state = load_state(&state_path);
+    let before = state.len();
+    for (id, _) in &restored {
+        state.remove(id);
+    }
+    if state.len() != before {
+        save_state(&state_path, &state)?;
+    }
+    Ok(restored)
+}
+
+fn state_path(cache_dir: &Path) -> PathBuf {
+    cache_dir.join("sync").join("state")
+}
+
+/// Per-rule stamps from the last successful sync; unreadable state is empty.
+fn load_state(path: &Path) -> BTreeMap<String, String> {
+    read_to_string(path)
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(path: &Path, state: &BTreeMap<String, String>) -> Result<(), String> {
+    ensure_parent(path);
+    let s = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Failed to serialize sync state: {}", e))?;
+    fs::write(path, s + "\n").map_err(|e| {
+        format!(
+            "Failed to write sync state '{}': {}",
+            path.to_string_lossy(),
+            e
+        )
+    })
+}
+
+/// Stamp of a rule's inputs: path, size, and mtime of every source file,
+/// plus those of the destination (each file of a directory target), so a
+/// local edit to the target invalidates it. `None` when the source is
+/// missing, so the rule always runs.
+fn sync_stamp(src: &Path, dst: &Path) -> Option<String> {
+    let meta = |p: &Path| -> Option<String> {
+        let md = fs::metadata(p).ok()?;
+        let mtime = md
+            .modified()
+            .ok()?
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()?;
+        Some(format!("{}:{}", md.len(), mtime.as_nanos()))
+    };
+    let mut files = Vec::new();
+    if src.is_file() {
+        files.push((String::new(), src.to_path_buf()));
+    } else if src.is_dir() {
+        collect_files(src, src, &mut files);
+        files.sort();
+    } else {
+        return None;
+    }
+    let mut lines = Vec::with_capacity(files.len() + 1);
+    for (rel, abs) in files {
+        lines.push(format!("{}\t{}", rel, meta(&abs)?));
+    }
+    if dst.is_dir() {
+        let mut dst_files = Vec::new();
+        collect_files(dst, dst, &mut dst_files);
+        dst_files.sort();
+        for (rel, abs) in dst_files {
+            let m = meta(&abs).unwrap_or_else(|| "missing".to_string());
+            lines.push(format!("{}\t{}\t{}", dst.to_string_lossy(), rel, m));
         }
+    } else {
+        let m = meta(dst).unwrap_or_else(|| "missing".to_string());
+        lines.push(format!("{}\t{}", dst.to_string_lossy(), m));
     }
-    copy_rule(rule, src, dst, write, errors)
+    Some(format!(
+        "{:016x}",
+        utils::stable_hash(lines.join("\n").as_bytes())
+    ))
 }
 
 fn read_to_string(p: &Path) -> Option<String> {
@@ -346,9 +775,11 @@ fn fingerprint(s: &str) -> String {
     format!("{:016x}-{}", h.finish(), s.len())
 }
 
-fn checksum_path(root: &Path, target: &Path) -> PathBuf {
+fn checksum_path(cache_dir: &Path, target: &Path) -> PathBuf {
     let rel = utils::rel_to_wd(target).replace('/', "__");
-    root.join(".rigra/sync/checksums")
+    cache_dir
+        .join("sync")
+        .join("checksums")
         .join(format!("{}.chk", rel))
 }
 
@@ -358,77 +789,20 @@ fn ensure_parent(p: &Path) {
     }
 }
 
-fn apply_json_merge(
-    rule: &SyncRule,
-    src: &PathBuf,
-    dst: &PathBuf,
-    mcfg: &config::SyncClientMergeCfg,
-    write: bool,
-    errors: Option<&mut Vec<RunError>>,
-) -> (bool, bool) {
-    let mut wrote = false;
-    let mut errs_opt = errors;
-    // will compute `would_write` only when differing from current
-    let src_str = match read_to_string(src) {
-        Some(s) => s,
-        None => return (wrote, false),
-    };
-    let src_json: Json = match serde_json::from_str(&src_str) {
-        Ok(j) => j,
-        Err(_) => {
-            let (w, ww) = copy_rule(rule, src, dst, write, errs_opt.as_deref_mut());
-            return (w, ww);
-        }
-    };
-    let dst_json: Json = if let Some(s) = read_to_string(dst) {
-        serde_json::from_str(&s).unwrap_or(Json::Null)
-    } else {
-        Json::Null
-    };
+/// Merge `src` over `dst` according to the client merge config.
+///
+/// Precedence: override > keep > default (source); `noSync` wins last.
+/// Array strategies (`union`/`replace`) are applied afterwards.
+pub fn merge_json(src_json: &Json, dst_json: &Json, mcfg: &config::SyncClientMergeCfg) -> Json {
     let mut result = src_json.clone();
 
-    // Helper closures to set or remove path (no wildcard support)
-    let set_path = |root: &mut Json, path: &str, val: Option<Json>| {
-        let p = path.trim().trim_start_matches('$').trim_start_matches('.');
-        let mut segs: Vec<&str> = p.split('.').filter(|s| !s.is_empty()).collect();
-        if segs.is_empty() {
-            if let Some(v) = val {
-                *root = v;
-            } else {
-                *root = Json::Null;
-            }
-            return;
-        }
-        let last = segs.pop().unwrap();
-        let mut cur = root;
-        for s in segs {
-            if let Json::Object(map) = cur {
-                if !map.contains_key(s) {
-                    map.insert(s.to_string(), Json::Object(serde_json::Map::new()));
-                }
-                cur = map.get_mut(s).unwrap();
-            } else {
-                // cannot set nested into non-object; abort
-                return;
-            }
-        }
-        if let Json::Object(map) = cur {
-            if let Some(v) = val {
-                map.insert(last.to_string(), v);
-            } else {
-                map.remove(last);
-            }
-        }
-    };
-
-    // Apply precedence: override > keep > default; noSync wins last
     for p in &mcfg.override_paths {
-        if let Some(v) = utils::get_json_path(&src_json, p) {
+        if let Some(v) = utils::get_json_path(src_json, p) {
             set_path(&mut result, p, Some(v.clone()));
         }
     }
     for p in &mcfg.keep_paths {
-        if let Some(v) = utils::get_json_path(&dst_json, p) {
+        if let Some(v) = utils::get_json_path(dst_json, p) {
             set_path(&mut result, p, Some(v.clone()));
         } else {
             // remove any value from result
@@ -436,7 +810,7 @@ fn apply_json_merge(
         }
     }
     for p in &mcfg.nosync_paths {
-        if let Some(v) = utils::get_json_path(&dst_json, p) {
+        if let Some(v) = utils::get_json_path(dst_json, p) {
             set_path(&mut result, p, Some(v.clone()));
         } else {
             set_path(&mut result, p, None);
@@ -447,8 +821,8 @@ fn apply_json_merge(
     if let Some(arr) = mcfg.array.as_ref() {
         for (path, strat) in arr.iter() {
             if strat == "union" {
-                if let Some(Json::Array(sa)) = utils::get_json_path(&src_json, path) {
-                    let da = utils::get_json_path(&dst_json, path).and_then(|v| v.as_array());
+                if let Some(Json::Array(sa)) = utils::get_json_path(src_json, path) {
+                    let da = utils::get_json_path(dst_json, path).and_then(|v| v.as_array());
                     let mut merged = Vec::new();
                     if let Some(darr) = da {
                         merged.extend(darr.iter().cloned());
@@ -462,12 +836,100 @@ fn apply_json_merge(
                 }
             } else {
                 // replace
-                if let Some(v) = utils::get_json_path(&src_json, path) {
+                if let Some(v) = utils::get_json_path(src_json, path) {
                     set_path(&mut result, path, Some(v.clone()));
                 }
             }
         }
     }
+    result
+}
+
+/// Merge two JSON files on disk and return the pretty-printed result.
+///
+/// Backs the standalone `rigra merge` command; a missing `dst` merges
+/// against `null` just like a first-time sync.
+pub fn merge_files(
+    src: &Path,
+    dst: &Path,
+    mcfg: &config::SyncClientMergeCfg,
+) -> Result<String, String> {
+    let src_str = fs::read_to_string(src)
+        .map_err(|e| format!("Failed to read source '{}': {}", src.to_string_lossy(), e))?;
+    let src_json: Json = serde_json::from_str(&src_str)
+        .map_err(|e| format!("Invalid source JSON '{}': {}", src.to_string_lossy(), e))?;
+    let dst_json: Json = match read_to_string(dst) {
+        Some(s) => serde_json::from_str(&s)
+            .map_err(|e| format!("Invalid dest JSON '{}': {}", dst.to_string_lossy(), e))?,
+        None => Json::Null,
+    };
+    let merged = merge_json(&src_json, &dst_json, mcfg);
+    serde_json::to_string_pretty(&merged).map_err(|e| format!("Failed to serialize: {}", e))
+}
+
+/// Set or remove a value at a dotted path (no wildcard support), creating
+/// intermediate objects as needed.
+fn set_path(root: &mut Json, path: &str, val: Option<Json>) {
+    let p = path.trim().trim_start_matches('$').trim_start_matches('.');
+    let mut segs: Vec<&str> = p.split('.').filter(|s| !s.is_empty()).collect();
+    if segs.is_empty() {
+        if let Some(v) = val {
+            *root = v;
+        } else {
+            *root = Json::Null;
+        }
+        return;
+    }
+    let last = segs.pop().unwrap();
+    let mut cur = root;
+    for s in segs {
+        if let Json::Object(map) = cur {
+            if !map.contains_key(s) {
+                map.insert(s.to_string(), Json::Object(serde_json::Map::new()));
+            }
+            cur = map.get_mut(s).unwrap();
+        } else {
+            // cannot set nested into non-object; abort
+            return;
+        }
+    }
+    if let Json::Object(map) = cur {
+        if let Some(v) = val {
+            map.insert(last.to_string(), v);
+        } else {
+            map.remove(last);
+        }
+    }
+}
+
+fn apply_json_merge(
+    cache_dir: &Path,
+    src: &PathBuf,
+    dst: &PathBuf,
+    mcfg: &config::SyncClientMergeCfg,
+    write: bool,
+    errors: Option<&mut Vec<RunError>>,
+) -> (bool, bool) {
+    let mut wrote = false;
+    let mut errs_opt = errors;
+    // will compute `would_write` only when differing from current
+    let src_str = match read_to_string(src) {
+        Some(s) => s,
+        None => return (wrote, false),
+    };
+    let src_json: Json = match serde_json::from_str(&src_str) {
+        Ok(j) => j,
+        Err(_) => {
+            let (w, ww) = copy_rule(src, dst, write, errs_opt.as_deref_mut());
+            return (w, ww);
+        }
+    };
+    let dst_json: Json = if let Some(s) = read_to_string(dst) {
+        serde_json::from_str(&s).unwrap_or(Json::Null)
+    } else {
+        Json::Null
+    };
+    let result = merge_json(&src_json, &dst_json, mcfg);
 
     // Serialize and compare checksums
     let out_str = match serde_json::to_string_pretty(&result) {
@@ -481,17 +943,14 @@ fn apply_json_merge(
     }
     let would_write = true;
     if write {
-        let cpath = checksum_path(&src.parent().unwrap_or_else(|| Path::new(".")), dst);
+        let cpath = checksum_path(cache_dir, dst);
         ensure_parent(&cpath);
         if let Err(e) = fs::write(&cpath, &out_fp) {
             eprintln!(
-                "{} {}",
+                "{} Failed to write checksum '{}': {}",
                 crate::utils::error_prefix(),
-                format!(
-                    "Failed to write checksum '{}': {}",
-                    cpath.to_string_lossy(),
-                    e
-                )
+                cpath.to_string_lossy(),
+                e
             );
             if let Some(errs) = errs_opt.as_deref_mut() {
                 errs.push(RunError {
@@ -508,15 +967,12 @@ fn apply_json_merge(
             Ok(_) => wrote = true,
             Err(e) => {
                 eprintln!(
-                    "{} {}",
+                    "{} Failed to write merged file '{}': {}",
                     crate::utils::error_prefix(),
-                    format!(
-                        "Failed to write merged file '{}': {}",
-                        dst.to_string_lossy(),
-                        e
-                    )
+                    dst.to_string_lossy(),
+                    e
                 );
-                if let Some(errs) = errs_opt.as_deref_mut() {
+                if let Some(errs) = errs_opt {
                     errs.push(RunError {
                         message: format!(
                             "Failed to write merged file '{}': {}",
@@ -532,16 +988,20 @@ fn apply_json_merge(
     (wrote, would_write)
 }
 
-/// Check whether a rule is enabled for a given scope value.
+/// Check whether a rule is enabled for a given scope value; `scope` may list
+/// several active scopes separated by commas (see `config::expand_scope`).
 fn is_rule_enabled(when: &str, scope: &str) -> bool {
     let w = when.trim();
     if w.is_empty() || w == "*" || w.eq_ignore_ascii_case("any") || w.eq_ignore_ascii_case("all") {
         return true;
     }
     // support comma or pipe separated tokens
-    w.split(|c| c == ',' || c == '|')
-        .map(|s| s.trim())
-        .any(|tok| !tok.is_empty() && tok.eq_ignore_ascii_case(scope))
+    w.split([',', '|']).map(|s| s.trim()).any(|tok| {
+        !tok.is_empty()
+            && scope
+                .split(',')
+                .any(|active| tok.eq_ignore_ascii_case(active.trim()))
+    })
 }
 
 #[cfg(test)]
@@ -549,6 +1009,342 @@ mod tests {
     use super::*;
     use tempfile::tempdir;
 
+    #[test]
+    fn test_merge_files_keep_path_preserves_dest_value() {
+        let tmp = tempdir().unwrap();
+        let src = tmp.path().join("src.json");
+        let dst = tmp.path().join("dst.json");
+        std::fs::write(
+            &src,
+            r#"{"name":"tpl","scripts":{"build":"tsc","test":"vitest"}}"#,
+        )
+        .unwrap();
+        std::fs::write(&dst, r#"{"name":"mine","scripts":{"build":"custom"}}"#).unwrap();
+        let mcfg = config::SyncClientMergeCfg {
+            keep_paths: vec!["name".into(), "scripts.build".into()],
+            ..Default::default()
+        };
+        let out = merge_files(&src, &dst, &mcfg).unwrap();
+        let v: Json = serde_json::from_str(&out).unwrap();
+        assert_eq!(
+            v,
+            serde_json::json!({"name":"mine","scripts":{"build":"custom","test":"vitest"}})
+        );
+    }
+
+    #[test]
+    fn test_unchanged_source_is_skipped_until_forced() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        let conv = root.join("conv");
+        std::fs::create_dir_all(conv.join("templates")).unwrap();
+        std::fs::write(conv.join("templates/a.txt"), b"hello").unwrap();
+        std::fs::write(
+            conv.join("sync.toml"),
+            "[[sync]]\nid = \"r1\"\nsource = \"templates/a.txt\"\ntarget = \"out/a.txt\"\nwhen = \"*\"\n",
+        )
+        .unwrap();
+        std::fs::write(conv.join("index.toml"), "sync = \"sync.toml\"\n").unwrap();
+        let run = |force: bool| {
+            let (actions, _) = run_sync(
+                root.to_str().unwrap(),
+                "conv/index.toml",
+                None,
+                "repo",
+                true,
+                false,
+                force,
+                &[],
+            );
+            actions[0].wrote
+        };
+        let target = root.join("out/a.txt");
+
+        assert!(run(false));
+        assert!(root.join(".rigra/sync/state").exists());
+        // An edit that keeps size and mtime matches the stamp: skipped until forced
+        let mtime = std::fs::metadata(&target).unwrap().modified().unwrap();
+        std::fs::write(&target, b"local").unwrap();
+        let file = std::fs::File::options().write(true).open(&target).unwrap();
+        file.set_modified(mtime).unwrap();
+        drop(file);
+        assert!(!run(false));
+        assert_eq!(std::fs::read(&target).unwrap(), b"local");
+        assert!(run(true));
+        assert_eq!(std::fs::read(&target).unwrap(), b"hello");
+
+        // A local edit to the target changes the stamp, so a plain write fixes it
+        std::fs::write(&target, b"local edit").unwrap();
+        assert!(run(false));
+        assert_eq!(std::fs::read(&target).unwrap(), b"hello");
+
+        std::fs::write(conv.join("templates/a.txt"), b"hello, world").unwrap();
+        assert!(run(false));
+        assert_eq!(std::fs::read(&target).unwrap(), b"hello, world");
+
+        // Check/dry runs ignore the stamp: a local edit is still drift
+        std::fs::write(&target, b"local").unwrap();
+        let (actions, _) = run_sync(
+            root.to_str().unwrap(),
+            "conv/index.toml",
+            None,
+            "repo",
+            false,
+            false,
+            false,
+            &[],
+        );
+        assert!(actions[0].would_write && !actions[0].wrote);
+    }
+
+    #[test]
+    fn test_restore_brings_back_pre_sync_content() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        let conv = root.join("conv");
+        std::fs::create_dir_all(conv.join("templates")).unwrap();
+        std::fs::write(conv.join("templates/a.txt"), b"template").unwrap();
+        std::fs::write(
+            conv.join("sync.toml"),
+            "[[sync]]\nid = \"r1\"\nsource = \"templates/a.txt\"\ntarget = \"a.txt\"\nwhen = \"*\"\n",
+        )
+        .unwrap();
+        std::fs::write(conv.join("index.toml"), "sync = \"sync.toml\"\n").unwrap();
+        // Nothing synced yet: nothing to restore
+        assert!(restore(root, None, &[]).is_err());
+
+        std::fs::write(root.join("a.txt"), b"hand-tuned").unwrap();
+        let (actions, _) = run_sync(
+            root.to_str().unwrap(),
+            "conv/index.toml",
+            None,
+            "repo",
+            true,
+            false,
+            false,
+            &[],
+        );
+        assert!(actions[0].wrote);
+        assert_eq!(std::fs::read(root.join("a.txt")).unwrap(), b"template");
+
+        let err = restore(root, None, &["other".to_string()]).unwrap_err();
+        assert_eq!(err, "No sync backup found for rule 'other'");
+        let restored = restore(root, None, &["r1".to_string()]).unwrap();
+        assert_eq!(restored, vec![("r1".to_string(), root.join("a.txt"))]);
+        assert_eq!(std::fs::read(root.join("a.txt")).unwrap(), b"hand-tuned");
+        // The stamp is dropped, so the next write is not skipped
+        let state = load_state(&state_path(&root.join(".rigra")));
+        assert!(!state.contains_key("r1"));
+        let (actions, _) = run_sync(
+            root.to_str().unwrap(),
+            "conv/index.toml",
+            None,
+            "repo",
+            true,
+            false,
+            false,
+            &[],
+        );
+        assert!(actions[0].wrote);
+        assert_eq!(std::fs::read(root.join("a.txt")).unwrap(), b"template");
+    }
+
+    #[test]
+    fn test_only_changed_drops_identical_targets() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        let conv = root.join("conv");
+        std::fs::create_dir_all(conv.join("templates")).unwrap();
+        std::fs::write(conv.join("templates/a.txt"), b"same").unwrap();
+        std::fs::write(conv.join("templates/b.txt"), b"new").unwrap();
+        std::fs::write(
+            conv.join("sync.toml"),
+            "[[sync]]\nid = \"a\"\nsource = \"templates/a.txt\"\ntarget = \"a.txt\"\nwhen = \"*\"\n\n[[sync]]\nid = \"b\"\nsource = \"templates/b.txt\"\ntarget = \"b.txt\"\nwhen = \"*\"\n",
+        )
+        .unwrap();
+        std::fs::write(conv.join("index.toml"), "sync = \"sync.toml\"\n").unwrap();
+        std::fs::write(root.join("a.txt"), b"same").unwrap();
+        std::fs::write(root.join("b.txt"), b"old").unwrap();
+
+        let (mut actions, _) = run_sync(
+            root.to_str().unwrap(),
+            "conv/index.toml",
+            None,
+            "repo",
+            false,
+            false,
+            true,
+            &[],
+        );
+        assert_eq!(actions.len(), 2);
+        retain_changed(&mut actions);
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].rule_id, "b");
+    }
+
+    #[test]
+    fn test_no_ignore_processes_ignored_sync_ids() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        let conv = root.join("conv");
+        std::fs::create_dir_all(conv.join("templates")).unwrap();
+        std::fs::write(conv.join("templates/a.txt"), b"hello").unwrap();
+        std::fs::write(
+            conv.join("sync.toml"),
+            "[[sync]]\nid = \"r1\"\nsource = \"templates/a.txt\"\ntarget = \"out/a.txt\"\nwhen = \"*\"\n",
+        )
+        .unwrap();
+        std::fs::write(conv.join("index.toml"), "sync = \"sync.toml\"\n").unwrap();
+        std::fs::write(root.join("rigra.toml"), "[sync]\nignore = [\"r1\"]\n").unwrap();
+
+        let (ignored, _) = run_sync(
+            root.to_str().unwrap(),
+            "conv/index.toml",
+            None,
+            "repo",
+            false,
+            false,
+            false,
+            &[],
+        );
+        assert!(ignored.is_empty());
+
+        let (actions, _) = run_sync(
+            root.to_str().unwrap(),
+            "conv/index.toml",
+            None,
+            "repo",
+            false,
+            true,
+            false,
+            &[],
+        );
+        assert_eq!(actions.len(), 1);
+        assert!(actions[0].would_write);
+    }
+
+    #[test]
+    fn test_interactive_no_skips_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let conv = root.join("conv");
+        std::fs::create_dir_all(conv.join("templates")).unwrap();
+        std::fs::write(conv.join("templates/a.txt"), b"hello\n").unwrap();
+        std::fs::write(
+            conv.join("sync.toml"),
+            "[[sync]]\nid = \"r1\"\nsource = \"templates/a.txt\"\ntarget = \"a.txt\"\nwhen = \"repo\"\n",
+        )
+        .unwrap();
+        std::fs::write(conv.join("index.toml"), "sync = \"sync.toml\"\n").unwrap();
+        std::fs::write(root.join("a.txt"), b"old\n").unwrap();
+
+        let mut input = std::io::Cursor::new("n\n");
+        let mut prompts = Vec::new();
+        let mut seen_diff = None;
+        let mut ask = |a: &SyncAction, diff: Option<&str>| {
+            seen_diff = diff.map(|d| d.to_string());
+            prompt_yes_no(&mut input, &mut prompts, &format!("Apply {}?", a.rule_id))
+        };
+        let (actions, errors) = run_sync_interactive(
+            root.to_str().unwrap(),
+            "conv/index.toml",
+            None,
+            "repo",
+            false,
+            false,
+            &mut ask,
+            &[],
+        );
+        assert!(errors.is_empty());
+        assert_eq!(actions.len(), 1);
+        assert!(!actions[0].wrote);
+        assert!(actions[0].would_write);
+        assert_eq!(
+            std::fs::read_to_string(root.join("a.txt")).unwrap(),
+            "old\n"
+        );
+        assert!(String::from_utf8(prompts)
+            .unwrap()
+            .contains("Apply r1? [y/N]"));
+        let diff = seen_diff.unwrap();
+        assert!(diff.contains("-old") && diff.contains("+hello"));
+
+        let mut yes = std::io::Cursor::new("y\n");
+        let mut ask = |_: &SyncAction, _: Option<&str>| {
+            prompt_yes_no(&mut yes, &mut std::io::sink(), "Apply?")
+        };
+        let (actions, _) = run_sync_interactive(
+            root.to_str().unwrap(),
+            "conv/index.toml",
+            None,
+            "repo",
+            false,
+            false,
+            &mut ask,
+            &[],
+        );
+        assert!(actions[0].wrote);
+        assert_eq!(
+            std::fs::read_to_string(root.join("a.txt")).unwrap(),
+            "hello\n"
+        );
+    }
+
+    #[test]
+    fn test_plan_hash_is_stable_and_tracks_source_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let conv = root.join("conv");
+        std::fs::create_dir_all(conv.join("templates/dir")).unwrap();
+        std::fs::write(conv.join("templates/a.txt"), b"hello").unwrap();
+        std::fs::write(conv.join("templates/dir/b.txt"), b"nested").unwrap();
+        std::fs::write(
+            conv.join("sync.toml"),
+            r#"
+[[sync]]
+id = "file"
+source = "templates/a.txt"
+target = "a.txt"
+when = "repo"
+
+[[sync]]
+id = "dir"
+source = "templates/dir"
+target = "dir"
+when = "repo"
+"#,
+        )
+        .unwrap();
+        std::fs::write(conv.join("index.toml"), "sync = \"sync.toml\"\n").unwrap();
+
+        let plan = || {
+            let (actions, _) = run_sync(
+                root.to_str().unwrap(),
+                "conv/index.toml",
+                None,
+                "repo",
+                false,
+                false,
+                false,
+                &[],
+            );
+            assert!(actions.iter().all(|a| !a.wrote));
+            plan_hash(root, &actions)
+        };
+        let first = plan();
+        assert_eq!(first.len(), 16);
+        assert_eq!(first, plan());
+        // Planning never writes
+        assert!(!root.join("a.txt").exists());
+
+        std::fs::write(conv.join("templates/a.txt"), b"hello, world").unwrap();
+        let changed = plan();
+        assert_ne!(first, changed);
+
+        std::fs::write(conv.join("templates/dir/b.txt"), b"nested v2").unwrap();
+        assert_ne!(changed, plan());
+    }
+
     #[test]
     fn test_sync_when_filters_rules() {
         let tmp = tempdir().unwrap();
@@ -582,8 +1378,12 @@ mod tests {
         let (actions, _errs) = run_sync(
             root.to_str().unwrap(),
             &format!("{}/index.toml", conv.file_name().unwrap().to_string_lossy()),
+            None,
             "repo",
             true,
+            false,
+            false,
+            &[],
         );
         // only r1 should write; r2 filtered out by `when`
         assert!(actions.iter().any(|a| a.rule_id == "r1" && a.wrote));
@@ -15,7 +15,51 @@ pub fn rel_to_wd(p: &Path) -> String {
     }
 }
 
+/// Return the file size when it exceeds `limit`, otherwise `None`.
+///
+/// A missing limit or unreadable metadata never triggers a skip.
+pub fn oversized(p: &Path, limit: Option<u64>) -> Option<u64> {
+    let limit = limit?;
+    let len = std::fs::metadata(p).ok()?.len();
+    if len > limit {
+        Some(len)
+    } else {
+        None
+    }
+}
+
 /// Get nested value by a simple JSONPath-like string: `$.a.b.c` or `a.b.c`.
+/// FNV-1a 64-bit hash; stable across runs, platforms, and toolchains
+/// (unlike `DefaultHasher`), so it can be stored and compared later.
+pub fn stable_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325u64, |h, b| {
+        (h ^ u64::from(*b)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Whether a config/policy path should be parsed as JSON5.
+pub fn is_json5(p: &Path) -> bool {
+    p.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("json5"))
+}
+
+/// Format of a lint/format target: the rule's `format`, else by extension
+/// (`.json5`, `.yaml`/`.yml`, `.toml`), else JSON.
+pub fn target_format<'a>(path: &Path, declared: Option<&'a str>) -> &'a str {
+    if let Some(f) = declared {
+        return f;
+    }
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("json5") => "json5",
+        Some("yaml" | "yml") => "yaml",
+        Some("toml") => "toml",
+        _ => "json",
+    }
+}
+
+/// Resolve a simple `$.a.b` / `a.b` path, with `[n]` array indices allowed
+/// on any segment (`$.files[0]`, `$.matrix[1][0].name`).
 pub fn get_json_path<'a>(json: &'a Json, path: &str) -> Option<&'a Json> {
     let trimmed = path.trim();
     let p = if let Some(stripped) = trimmed.strip_prefix("$") {
@@ -31,22 +75,61 @@ pub fn get_json_path<'a>(json: &'a Json, path: &str) -> Option<&'a Json> {
         if seg.is_empty() {
             continue;
         }
-        match cur {
-            Json::Object(map) => {
-                if let Some(v) = map.get(seg) {
-                    cur = v;
-                } else {
-                    return None;
-                }
-            }
-            _ => {
-                return None;
-            }
+        // Literal keys win, so keys that happen to contain '[' still resolve
+        if let Some(v) = cur.as_object().and_then(|m| m.get(seg)) {
+            cur = v;
+            continue;
+        }
+        let (key, indices) = split_indices(seg)?;
+        if !key.is_empty() {
+            cur = cur.as_object()?.get(key)?;
+        }
+        for i in indices {
+            cur = cur.as_array()?.get(i)?;
+        }
+    }
+    Some(cur)
+}
+
+/// Mutable counterpart of `get_json_path`, with the same path rules.
+pub fn get_json_path_mut<'a>(json: &'a mut Json, path: &str) -> Option<&'a mut Json> {
+    let trimmed = path.trim();
+    let p = trimmed
+        .strip_prefix('$')
+        .map_or(trimmed, |s| s.trim_start_matches('.'));
+    let mut cur = json;
+    for seg in p.split('.').filter(|s| !s.is_empty()) {
+        if cur.as_object().is_some_and(|m| m.contains_key(seg)) {
+            cur = cur.as_object_mut()?.get_mut(seg)?;
+            continue;
+        }
+        let (key, indices) = split_indices(seg)?;
+        if !key.is_empty() {
+            cur = cur.as_object_mut()?.get_mut(key)?;
+        }
+        for i in indices {
+            cur = cur.as_array_mut()?.get_mut(i)?;
         }
     }
     Some(cur)
 }
 
+/// Split `name[1][2]` into `("name", [1, 2])`; `None` if brackets are malformed.
+fn split_indices(seg: &str) -> Option<(&str, Vec<usize>)> {
+    let (key, mut rest) = match seg.find('[') {
+        Some(pos) => (&seg[..pos], &seg[pos..]),
+        None => return Some((seg, Vec::new())),
+    };
+    let mut indices = Vec::new();
+    while !rest.is_empty() {
+        let inner = rest.strip_prefix('[')?;
+        let end = inner.find(']')?;
+        indices.push(inner[..end].parse().ok()?);
+        rest = &inner[end + 1..];
+    }
+    Some((key, indices))
+}
+
 /// Whether colors should be used for global messages (checks NO_COLOR).
 pub fn use_colors_global() -> bool {
     std::env::var_os("NO_COLOR").is_none()
@@ -81,7 +164,6 @@ pub fn note_prefix() -> String {
 }
 
 /// Standardized warn prefix for human-readable output.
-#[allow(dead_code)]
 pub fn warn_prefix() -> String {
     if use_colors_global() {
         "▲ ⟦warn⟧".yellow().bold().to_string()
@@ -161,4 +243,23 @@ mod tests {
         assert!(get_json_path(&data, "nested.missing").is_none());
         assert!(get_json_path(&data, "$.nested.a.b.c").is_none());
     }
+
+    #[test]
+    fn test_get_json_path_array_indices() {
+        let data = serde_json::json!({
+            "files": ["dist", "README.md"],
+            "matrix": [[1, 2], [3, {"name": "x"}]],
+            "odd[key]": true
+        });
+        assert_eq!(get_json_path(&data, "$.files[1]").unwrap(), "README.md");
+        assert_eq!(get_json_path(&data, "$.matrix[1][1].name").unwrap(), "x");
+        assert_eq!(get_json_path(&data, "odd[key]").unwrap(), true);
+        assert!(get_json_path(&data, "$.files[2]").is_none());
+        assert!(get_json_path(&data, "$.files[x]").is_none());
+        assert!(get_json_path(&data, "$.matrix[0").is_none());
+        assert_eq!(
+            get_json_path(&serde_json::json!([{"a": 1}]), "$[0].a").unwrap(),
+            1
+        );
+    }
 }
@@ -50,9 +50,10 @@ meta = []
     .unwrap();
 
     // Run format preview
-    let results = format::run_format(
+    let (results, _errors) = format::run_format(
         root.to_str().unwrap(),
         &format!("{}/index.toml", conv.file_name().unwrap().to_string_lossy()),
+        None,
         false,
         false,
         false,
@@ -60,6 +61,11 @@ meta = []
         &std::collections::HashMap::new(),
         &std::collections::HashMap::new(),
         &std::collections::HashMap::new(),
+        None,
+        false,
+        false,
+        None,
+        &[],
     );
     assert_eq!(results.len(), 1);
     let preview = results[0].preview.as_ref().unwrap();
@@ -112,9 +118,10 @@ top = [["name"],["version"],["license"]]
     .unwrap();
 
     // Case A: write=true (no diff/check) ⇒ file should be rewritten, no preview
-    let results_write = rigra::format::run_format(
+    let (results_write, _errors) = rigra::format::run_format(
         root.to_str().unwrap(),
         &format!("{}/index.toml", conv.file_name().unwrap().to_string_lossy()),
+        None,  // cache_dir
         true,  // write
         false, // capture_old
         false, // strict_linebreak
@@ -122,6 +129,11 @@ top = [["name"],["version"],["license"]]
         &std::collections::HashMap::new(),
         &std::collections::HashMap::new(),
         &std::collections::HashMap::new(),
+        None,
+        false,
+        false,
+        None,
+        &[],
     );
     assert_eq!(results_write.len(), 1);
     assert!(results_write[0].changed);
@@ -144,9 +156,10 @@ top = [["name"],["version"],["license"]]
     .unwrap();
 
     // Case B: diff/check override write=false ⇒ preview present, file unchanged
-    let results_diff = rigra::format::run_format(
+    let (results_diff, _errors) = rigra::format::run_format(
         root.to_str().unwrap(),
         &format!("{}/index.toml", conv.file_name().unwrap().to_string_lossy()),
+        None,  // cache_dir
         false, // effective write becomes false when diff/check true
         true,  // capture_old to enable diff
         false,
@@ -154,6 +167,11 @@ top = [["name"],["version"],["license"]]
         &std::collections::HashMap::new(),
         &std::collections::HashMap::new(),
         &std::collections::HashMap::new(),
+        None,
+        false,
+        false,
+        None,
+        &[],
     );
     assert_eq!(results_diff.len(), 1);
     assert!(results_diff[0].changed);
@@ -200,11 +218,15 @@ sync = "sync.toml"
     )
     .unwrap();
 
-    let actions = sync::run_sync(
+    let (actions, _errors) = sync::run_sync(
         root.to_str().unwrap(),
         &format!("{}/index.toml", conv.file_name().unwrap().to_string_lossy()),
+        None,
         "repo",
         true,
+        false,
+        false,
+        &[],
     );
     assert!(actions.iter().any(|a| a.rule_id == "r1" && a.wrote));
     assert!(actions.iter().all(|a| a.rule_id != "r2"));
@@ -267,9 +289,10 @@ scripts = "keep"
     .unwrap();
 
     // Run format with strict linebreaks enabled
-    let results = format::run_format(
+    let (results, _errors) = format::run_format(
         root.to_str().unwrap(),
         &format!("{}/index.toml", conv.file_name().unwrap().to_string_lossy()),
+        None,                              // cache_dir
         false,                             // write
         true,                              // capture_old for potential diffs
         true,                              // strict_linebreak
@@ -277,6 +300,11 @@ scripts = "keep"
         &std::collections::HashMap::new(), // lb_before_fields_override
         &std::collections::HashMap::new(), // lb_in_fields_override
         &std::collections::HashMap::new(), // pattern_overrides
+        None,                              // max_file_bytes
+        false,                             // assume_sorted
+        false,                             // serial
+        None,                              // stats
+        &[],                               // rules
     );
     assert_eq!(results.len(), 1);
     let preview = results[0].preview.as_ref().expect("expected preview");
@@ -336,11 +364,16 @@ level = "warn"
     )
     .unwrap();
 
-    let res = lint::run_lint(
+    let (res, _errors) = lint::run_lint(
         root.to_str().unwrap(),
         &format!("{}/index.toml", conv.file_name().unwrap().to_string_lossy()),
+        None,
         "repo",
         &std::collections::HashMap::new(),
+        None,
+        false,
+        None,
+        &[],
     );
     assert!(res
         .issues
@@ -396,9 +429,10 @@ license = "none"
     // Overrides: enable between_groups and force license=keep
     let mut before_over = std::collections::HashMap::new();
     before_over.insert("license".to_string(), "keep".to_string());
-    let results = format::run_format(
+    let (results, _errors) = format::run_format(
         root.to_str().unwrap(),
         &format!("{}/index.toml", conv.file_name().unwrap().to_string_lossy()),
+        None,
         false,
         false,
         true,         // strict linebreaks on
@@ -406,6 +440,11 @@ license = "none"
         &before_over, // override before_fields
         &std::collections::HashMap::new(),
         &std::collections::HashMap::new(),
+        None,
+        false,
+        false,
+        None,
+        &[],
     );
     assert_eq!(results.len(), 1);
     let preview = results[0].preview.as_ref().unwrap();
@@ -426,3 +465,2312 @@ license = "none"
     }
     assert!(found, "license line not found");
 }
+
+#[test]
+fn lint_skips_files_over_max_file_bytes_with_warning() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+
+    fs::write(
+        conv.join("index.toml"),
+        r#"
+[[rules]]
+id = "pkgjson"
+patterns = ["package.json"]
+policy = "policy.toml"
+"#,
+    )
+    .unwrap();
+
+    fs::write(
+        conv.join("policy.toml"),
+        r#"
+[[checks]]
+kind = "required"
+fields = ["license"]
+"#,
+    )
+    .unwrap();
+
+    let big = format!("{{\n  \"name\": \"{}\"\n}}", "x".repeat(256));
+    fs::write(root.join("package.json"), &big).unwrap();
+
+    let (res, _errors) = lint::run_lint(
+        root.to_str().unwrap(),
+        "conv/index.toml",
+        None,
+        "repo",
+        &std::collections::HashMap::new(),
+        Some(64),
+        false,
+        None,
+        &[],
+    );
+    // The required check never runs; only the skip warning is reported
+    assert_eq!(res.issues.len(), 1);
+    assert_eq!(res.issues[0].severity, "warning");
+    assert!(res.issues[0].message.contains(&format!(
+        "{} bytes exceeds limits.maxFileBytes (64)",
+        big.len()
+    )));
+    assert_eq!(res.summary.files, 0);
+}
+
+#[test]
+fn lint_flags_minified_json_when_formatting_check_enabled() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+
+    fs::write(
+        conv.join("index.toml"),
+        r#"
+[[rules]]
+id = "pkgjson"
+patterns = ["*.json"]
+policy = "policy.toml"
+"#,
+    )
+    .unwrap();
+
+    fs::write(
+        conv.join("policy.toml"),
+        r#"
+[order]
+top = [["name"],["version"]]
+
+[formatting]
+check = true
+level = "warning"
+"#,
+    )
+    .unwrap();
+
+    // Same content: one minified, one already pretty-printed
+    fs::write(root.join("min.json"), r#"{"name":"x","version":"1.0.0"}"#).unwrap();
+    fs::write(
+        root.join("pretty.json"),
+        "{\n  \"name\": \"x\",\n  \"version\": \"1.0.0\"\n}\n",
+    )
+    .unwrap();
+
+    let (res, _errors) = lint::run_lint(
+        root.to_str().unwrap(),
+        "conv/index.toml",
+        None,
+        "repo",
+        &std::collections::HashMap::new(),
+        None,
+        false,
+        None,
+        &[],
+    );
+    assert_eq!(res.issues.len(), 1);
+    assert!(res.issues[0].file.ends_with("min.json"));
+    assert_eq!(res.issues[0].severity, "warning");
+    assert_eq!(
+        res.issues[0].message,
+        "File is not formatted; run rigra format"
+    );
+}
+
+#[test]
+fn lint_stats_lists_each_rule_with_duration() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+
+    fs::write(
+        conv.join("index.toml"),
+        r#"
+[[rules]]
+id = "pkgjson"
+patterns = ["package.json"]
+policy = "policy.toml"
+
+[[rules]]
+id = "tsconfig"
+patterns = ["tsconfig.json"]
+policy = "policy.toml"
+"#,
+    )
+    .unwrap();
+    fs::write(
+        conv.join("policy.toml"),
+        "[[checks]]\nkind = \"required\"\nfields = [\"name\"]\n",
+    )
+    .unwrap();
+    fs::write(root.join("package.json"), r#"{"name":"x"}"#).unwrap();
+    fs::write(root.join("tsconfig.json"), r#"{}"#).unwrap();
+
+    let mut timings = Vec::new();
+    let (res, _errors) = lint::run_lint(
+        root.to_str().unwrap(),
+        "conv/index.toml",
+        None,
+        "repo",
+        &std::collections::HashMap::new(),
+        None,
+        false,
+        Some(&mut timings),
+        &[],
+    );
+    // Timing does not change what lint reports
+    assert_eq!(res.issues.len(), 1);
+
+    let mut rules: Vec<&str> = timings.iter().map(|t| t.rule.as_str()).collect();
+    rules.sort();
+    assert_eq!(rules, vec!["pkgjson", "tsconfig"]);
+
+    let table = rigra::output::compose_stats_table(&timings);
+    for rule in ["pkgjson", "tsconfig", "total"] {
+        let line = table
+            .lines()
+            .find(|l| l.starts_with(rule))
+            .expect("rule row");
+        let ms: f64 = line.split_whitespace().last().unwrap().parse().unwrap();
+        assert!(ms >= 0.0);
+    }
+}
+
+#[test]
+fn lint_reads_json5_policy_referenced_from_index() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+
+    fs::write(
+        conv.join("index.toml"),
+        r#"
+[[rules]]
+id = "pkgjson"
+patterns = ["package.json"]
+policy = "policy.json5"
+"#,
+    )
+    .unwrap();
+    fs::write(
+        conv.join("policy.json5"),
+        r#"{
+  // every package needs a license
+  checks: [{ kind: 'required', fields: ['license'], level: 'warning' }],
+}"#,
+    )
+    .unwrap();
+    fs::write(root.join("package.json"), r#"{"name":"x"}"#).unwrap();
+
+    let (res, _errors) = lint::run_lint(
+        root.to_str().unwrap(),
+        "conv/index.toml",
+        None,
+        "repo",
+        &std::collections::HashMap::new(),
+        None,
+        false,
+        None,
+        &[],
+    );
+    assert_eq!(res.issues.len(), 1);
+    assert_eq!(res.issues[0].path, "$.license");
+    assert_eq!(res.issues[0].severity, "warning");
+}
+
+#[test]
+fn lint_runs_rules_and_sync_from_included_index() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(conv.join("base/templates")).unwrap();
+
+    fs::write(
+        conv.join("index.toml"),
+        r#"
+include = ["base/index.toml"]
+
+[[rules]]
+id = "pkgjson"
+patterns = ["package.json"]
+policy = "pkg.toml"
+"#,
+    )
+    .unwrap();
+    fs::write(
+        conv.join("pkg.toml"),
+        "[[checks]]\nkind = \"required\"\nfields = [\"license\"]\n",
+    )
+    .unwrap();
+    // Included index: its policy, sync file and sources resolve from base/
+    fs::write(
+        conv.join("base/index.toml"),
+        r#"
+sync = "sync.toml"
+
+[[rules]]
+id = "tsconfig"
+patterns = ["tsconfig.json"]
+policy = "ts.toml"
+"#,
+    )
+    .unwrap();
+    fs::write(
+        conv.join("base/ts.toml"),
+        "[[checks]]\nkind = \"required\"\nfields = [\"compilerOptions\"]\n",
+    )
+    .unwrap();
+    fs::write(
+        conv.join("base/sync.toml"),
+        r#"
+[[sync]]
+id = "editorconfig"
+source = "templates/.editorconfig"
+target = ".editorconfig"
+when = "repo"
+"#,
+    )
+    .unwrap();
+    fs::write(conv.join("base/templates/.editorconfig"), "root = true\n").unwrap();
+    fs::write(root.join("package.json"), r#"{"name":"x"}"#).unwrap();
+    fs::write(root.join("tsconfig.json"), r#"{}"#).unwrap();
+
+    let (res, errors) = lint::run_lint(
+        root.to_str().unwrap(),
+        "conv/index.toml",
+        None,
+        "repo",
+        &std::collections::HashMap::new(),
+        None,
+        false,
+        None,
+        &[],
+    );
+    assert!(errors.is_empty());
+    let mut rules: Vec<_> = res.issues.iter().map(|i| i.rule.as_str()).collect();
+    rules.sort();
+    assert_eq!(rules, vec!["pkgjson", "sync:editorconfig", "tsconfig"]);
+
+    let (actions, errors) = sync::run_sync(
+        root.to_str().unwrap(),
+        "conv/index.toml",
+        None,
+        "repo",
+        true,
+        false,
+        false,
+        &[],
+    );
+    assert!(errors.is_empty());
+    assert_eq!(actions.len(), 1);
+    assert_eq!(
+        fs::read_to_string(root.join(".editorconfig")).unwrap(),
+        "root = true\n"
+    );
+}
+
+#[test]
+fn client_lint_level_remaps_sync_issue_severity() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(conv.join("templates")).unwrap();
+    fs::write(conv.join("index.toml"), "sync = \"sync.toml\"\n").unwrap();
+    fs::write(
+        conv.join("sync.toml"),
+        r#"
+[lint]
+level = "error"
+
+[[sync]]
+id = "editorconfig"
+source = "templates/.editorconfig"
+target = ".editorconfig"
+when = "repo"
+
+[[sync]]
+id = "gitattributes"
+source = "templates/.gitattributes"
+target = ".gitattributes"
+when = "repo"
+level = "error"
+"#,
+    )
+    .unwrap();
+    fs::write(conv.join("templates/.editorconfig"), "root = true\n").unwrap();
+    fs::write(conv.join("templates/.gitattributes"), "* text=auto\n").unwrap();
+    let lint = || {
+        let (res, _errors) = lint::run_lint(
+            root.to_str().unwrap(),
+            "conv/index.toml",
+            None,
+            "repo",
+            &std::collections::HashMap::new(),
+            None,
+            false,
+            None,
+            &[],
+        );
+        let mut sevs: Vec<_> = res
+            .issues
+            .iter()
+            .map(|i| (i.rule.clone(), i.severity.clone()))
+            .collect();
+        sevs.sort();
+        sevs
+    };
+    assert_eq!(
+        lint(),
+        vec![
+            ("sync:editorconfig".to_string(), "error".to_string()),
+            ("sync:gitattributes".to_string(), "error".to_string()),
+        ]
+    );
+
+    // Global downgrade, with a per-rule override taking precedence
+    fs::write(
+        root.join("rigra.toml"),
+        r#"
+[sync]
+lintLevel = "warning"
+
+[sync.config.gitattributes]
+lintLevel = "info"
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        lint(),
+        vec![
+            ("sync:editorconfig".to_string(), "warning".to_string()),
+            ("sync:gitattributes".to_string(), "info".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn conv_referenced_policy_is_used_by_lint_and_format() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+    // Policy shipped by another (already installed) convention
+    let other = root.join(".rigra/conv/other@v1/policies");
+    fs::create_dir_all(&other).unwrap();
+    fs::write(
+        other.join("pkg.toml"),
+        r#"
+[order]
+top = [["name"],["version"]]
+
+[[checks]]
+kind = "required"
+fields = ["license"]
+"#,
+    )
+    .unwrap();
+
+    fs::write(
+        conv.join("index.toml"),
+        r#"
+[[rules]]
+id = "pkgjson"
+patterns = ["package.json"]
+policy = "conv:other@v1:policies/pkg.toml"
+"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("package.json"),
+        r#"{"version":"1.0.0","name":"x"}"#,
+    )
+    .unwrap();
+
+    let (res, _errors) = lint::run_lint(
+        root.to_str().unwrap(),
+        "conv/index.toml",
+        None,
+        "repo",
+        &std::collections::HashMap::new(),
+        None,
+        false,
+        None,
+        &[],
+    );
+    assert!(res
+        .issues
+        .iter()
+        .any(|i| i.path == "$.license" && i.rule == "pkgjson"));
+
+    let (results, _errors) = format::run_format(
+        root.to_str().unwrap(),
+        "conv/index.toml",
+        None,
+        false,
+        true,
+        false,
+        None,
+        &std::collections::HashMap::new(),
+        &std::collections::HashMap::new(),
+        &std::collections::HashMap::new(),
+        None,
+        false,
+        false,
+        None,
+        &[],
+    );
+    assert_eq!(results.len(), 1);
+    assert!(results[0].changed);
+    let preview = results[0].preview.as_ref().unwrap();
+    assert!(preview.find("\"name\"").unwrap() < preview.find("\"version\"").unwrap());
+}
+
+#[test]
+fn lint_streams_large_top_level_arrays_with_bounded_memory() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+
+    fs::write(
+        conv.join("index.toml"),
+        r#"
+[[rules]]
+id = "records"
+patterns = ["records.json"]
+policy = "policy.toml"
+"#,
+    )
+    .unwrap();
+
+    fs::write(
+        conv.join("policy.toml"),
+        r#"
+[[checks]]
+kind = "required"
+fields = ["$[*].name"]
+"#,
+    )
+    .unwrap();
+
+    // Every 1000th record lacks a name
+    let n = 20_000;
+    let records: Vec<String> = (0..n)
+        .map(|i| {
+            if i % 1000 == 3 {
+                format!("{{\"id\":{}}}", i)
+            } else {
+                format!("{{\"id\":{},\"name\":\"item-{}\"}}", i, i)
+            }
+        })
+        .collect();
+    let data = format!("[\n{}\n]\n", records.join(",\n"));
+    fs::write(root.join("records.json"), &data).unwrap();
+
+    for stream_arrays in [true, false] {
+        let (res, _errors) = lint::run_lint(
+            root.to_str().unwrap(),
+            "conv/index.toml",
+            None,
+            "repo",
+            &std::collections::HashMap::new(),
+            None,
+            stream_arrays,
+            None,
+            &[],
+        );
+        assert_eq!(res.issues.len(), n / 1000);
+        assert_eq!(res.summary.files, 1);
+        assert!(res.issues.iter().any(|i| i.path == "$[3].name"));
+        assert!(res.issues.iter().any(|i| i.path == "$[19003].name"));
+    }
+
+    // Only one element is buffered at a time
+    let file = fs::File::open(root.join("records.json")).unwrap();
+    let mut seen = 0;
+    let stats = rigra::stream::for_each_array_element(file, |_, _| seen += 1).unwrap();
+    assert_eq!(stats.elements, n);
+    assert_eq!(seen, n);
+    assert!(stats.max_element_bytes < 64);
+    assert!(data.len() > 500_000);
+}
+
+#[test]
+fn format_assume_sorted_skips_reserializing_ordered_files() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+    fs::write(
+        conv.join("index.toml"),
+        r#"
+[[rules]]
+id = "pkgjson"
+patterns = ["*.json"]
+policy = "policy.toml"
+"#,
+    )
+    .unwrap();
+    fs::write(
+        conv.join("policy.toml"),
+        r#"
+checks = []
+
+[order]
+top = [["name"],["version"]]
+"#,
+    )
+    .unwrap();
+
+    // Keys already in order, but minified: only a reserialize would change it
+    fs::write(
+        root.join("ordered.json"),
+        r#"{"name":"x","version":"1.0.0","a":1,"z":2}"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("shuffled.json"),
+        r#"{"version":"1.0.0","name":"x"}"#,
+    )
+    .unwrap();
+
+    let run = |assume_sorted: bool| {
+        let (results, _errors) = format::run_format(
+            root.to_str().unwrap(),
+            "conv/index.toml",
+            None,
+            false,
+            false,
+            false,
+            None,
+            &std::collections::HashMap::new(),
+            &std::collections::HashMap::new(),
+            &std::collections::HashMap::new(),
+            None,
+            assume_sorted,
+            false,
+            None,
+            &[],
+        );
+        results
+            .into_iter()
+            .map(|r| {
+                let name = std::path::Path::new(&r.file)
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .to_string();
+                (name, r.changed, r.preview.is_some())
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        run(true),
+        vec![
+            ("ordered.json".to_string(), false, false),
+            ("shuffled.json".to_string(), true, true),
+        ]
+    );
+    // Without the fast path the minified file is reserialized and differs
+    assert_eq!(run(false)[0], ("ordered.json".to_string(), true, true));
+}
+
+#[test]
+fn cache_dir_relocates_conv_cache_and_sync_state() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let cache = root.join("shared-cache");
+    let conv = root.join("conv");
+    fs::create_dir_all(conv.join("templates")).unwrap();
+
+    // Installed convention lives under the custom cache dir only
+    let other = cache.join("conv/other@v1/policies");
+    fs::create_dir_all(&other).unwrap();
+    fs::write(
+        other.join("pkg.toml"),
+        r#"
+[[checks]]
+kind = "required"
+fields = ["license"]
+"#,
+    )
+    .unwrap();
+    fs::write(conv.join("templates/base.json"), r#"{"a": 1}"#).unwrap();
+    fs::write(
+        conv.join("sync.toml"),
+        r#"
+[[sync]]
+id = "base"
+source = "templates/base.json"
+target = "base.json"
+format = "json"
+when = "repo"
+"#,
+    )
+    .unwrap();
+    fs::write(
+        conv.join("index.toml"),
+        r#"
+sync = "sync.toml"
+
+[[rules]]
+id = "pkgjson"
+patterns = ["package.json"]
+policy = "conv:other@v1:policies/pkg.toml"
+"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("rigra.toml"),
+        r#"
+[sync.config.base.merge]
+keep = ["$.b"]
+"#,
+    )
+    .unwrap();
+    fs::write(root.join("package.json"), r#"{"name":"x"}"#).unwrap();
+
+    let (res, _errors) = lint::run_lint(
+        root.to_str().unwrap(),
+        "conv/index.toml",
+        Some(&cache),
+        "repo",
+        &std::collections::HashMap::new(),
+        None,
+        false,
+        None,
+        &[],
+    );
+    assert!(res.issues.iter().any(|i| i.path == "$.license"));
+
+    let (actions, _errors) = sync::run_sync(
+        root.to_str().unwrap(),
+        "conv/index.toml",
+        Some(&cache),
+        "repo",
+        true,
+        false,
+        false,
+        &[],
+    );
+    assert!(actions.iter().any(|a| a.rule_id == "base" && a.wrote));
+    let checksums = cache.join("sync/checksums");
+    assert_eq!(fs::read_dir(&checksums).unwrap().count(), 1);
+    assert!(!root.join(".rigra").exists());
+}
+
+#[test]
+fn format_serial_matches_parallel_results() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+    fs::create_dir_all(root.join("pkgs")).unwrap();
+    fs::write(
+        conv.join("index.toml"),
+        r#"
+[[rules]]
+id = "pkgjson"
+patterns = ["pkgs/*.json"]
+policy = "policy.toml"
+"#,
+    )
+    .unwrap();
+    fs::write(
+        conv.join("policy.toml"),
+        r#"
+[order]
+top = [["name"],["version"]]
+"#,
+    )
+    .unwrap();
+    for i in 0..32 {
+        fs::write(
+            root.join(format!("pkgs/p{:02}.json", i)),
+            format!(r#"{{"z":{},"version":"1.0.{}","name":"p{}"}}"#, i, i, i),
+        )
+        .unwrap();
+    }
+
+    let run = |serial: bool| {
+        let (results, _errors) = format::run_format(
+            root.to_str().unwrap(),
+            "conv/index.toml",
+            None,
+            false,
+            false,
+            true,
+            None,
+            &std::collections::HashMap::new(),
+            &std::collections::HashMap::new(),
+            &std::collections::HashMap::new(),
+            None,
+            false,
+            serial,
+            None,
+            &[],
+        );
+        results
+            .into_iter()
+            .map(|r| (r.file, r.changed, r.preview))
+            .collect::<Vec<_>>()
+    };
+
+    let serial = run(true);
+    assert_eq!(serial.len(), 32);
+    assert!(serial.iter().all(|(_, changed, _)| *changed));
+    assert_eq!(serial, run(false));
+}
+
+#[test]
+fn unicode_collation_applies_to_format_and_lint() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+    fs::write(
+        conv.join("index.toml"),
+        "[[rules]]\nid = \"cfg\"\npatterns = [\"cfg.json\"]\npolicy = \"policy.toml\"\n",
+    )
+    .unwrap();
+    let write_policy = |collation: &str| {
+        fs::write(
+            conv.join("policy.toml"),
+            format!(
+                "[order]\ntop = [[\"name\"]]\ncollation = \"{}\"\n",
+                collation
+            ),
+        )
+        .unwrap();
+    };
+    // Unicode-aware order of the unlisted keys
+    fs::write(
+        root.join("cfg.json"),
+        "{\n  \"name\": \"x\",\n  \"apple\": 1,\n  \"éclair\": 2,\n  \"Zebra\": 3\n}\n",
+    )
+    .unwrap();
+    let lint = || {
+        lint::run_lint(
+            root.to_str().unwrap(),
+            "conv/index.toml",
+            None,
+            "repo",
+            &std::collections::HashMap::new(),
+            None,
+            false,
+            None,
+            &[],
+        )
+        .0
+    };
+    let format = || {
+        format::run_format(
+            root.to_str().unwrap(),
+            "conv/index.toml",
+            None,
+            false,
+            false,
+            false,
+            None,
+            &std::collections::HashMap::new(),
+            &std::collections::HashMap::new(),
+            &std::collections::HashMap::new(),
+            None,
+            false,
+            false,
+            None,
+            &[],
+        )
+        .0
+    };
+
+    write_policy("unicode");
+    assert!(lint().issues.is_empty());
+    assert!(!format()[0].changed);
+
+    // Byte order puts "Zebra" first and "éclair" last
+    write_policy("byte");
+    assert_eq!(lint().issues.len(), 1);
+    let results = format();
+    assert!(results[0].changed);
+    let preview = results[0].preview.as_ref().unwrap();
+    let pos = |k: &str| preview.find(&format!("\"{}\"", k)).unwrap();
+    assert!(pos("Zebra") < pos("apple") && pos("apple") < pos("éclair"));
+}
+
+#[test]
+fn lint_checks_yaml_and_toml_targets() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+    fs::write(
+        conv.join("index.toml"),
+        r#"
+[[rules]]
+id = "ci"
+patterns = ["ci.yml"]
+policy = "policy.toml"
+
+[[rules]]
+id = "cfg"
+patterns = ["settings.conf"]
+policy = "policy.toml"
+format = "toml"
+"#,
+    )
+    .unwrap();
+    fs::write(
+        conv.join("policy.toml"),
+        "[[checks]]\nkind = \"required\"\nfields = [\"name\", \"jobs.build\"]\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("ci.yml"),
+        "name: ci\njobs:\n  test:\n    runs-on: ubuntu-latest\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("settings.conf"),
+        "name = \"cfg\"\n[jobs.build]\nsteps = 1\n",
+    )
+    .unwrap();
+    let (res, errors) = lint::run_lint(
+        root.to_str().unwrap(),
+        "conv/index.toml",
+        None,
+        "repo",
+        &std::collections::HashMap::new(),
+        None,
+        false,
+        None,
+        &[],
+    );
+    assert!(errors.is_empty());
+    assert_eq!(res.summary.files, 2);
+    // Only the YAML file lacks jobs.build
+    assert_eq!(res.issues.len(), 1);
+    assert_eq!(res.issues[0].rule, "ci");
+    assert_eq!(res.issues[0].path, "$.jobs.build");
+}
+
+#[test]
+fn lint_merges_parallel_rules_in_index_order() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+    let ids: Vec<String> = (0..12).map(|i| format!("r{:02}", 11 - i)).collect();
+    let mut index = String::new();
+    for id in &ids {
+        index.push_str(&format!(
+            "[[rules]]\nid = \"{id}\"\npatterns = [\"{id}/*.json\"]\npolicy = \"policy.toml\"\n\n"
+        ));
+        fs::create_dir_all(root.join(id)).unwrap();
+        fs::write(root.join(id).join("b.json"), "{}").unwrap();
+        fs::write(root.join(id).join("a.json"), "{\"name\": 1}").unwrap();
+    }
+    fs::write(conv.join("index.toml"), index).unwrap();
+    fs::write(
+        conv.join("policy.toml"),
+        "[[checks]]\nkind = \"required\"\nfields = [\"name\", \"version\"]\n",
+    )
+    .unwrap();
+    let run = || {
+        lint::run_lint(
+            root.to_str().unwrap(),
+            "conv/index.toml",
+            None,
+            "repo",
+            &std::collections::HashMap::new(),
+            None,
+            false,
+            None,
+            &[],
+        )
+        .0
+    };
+    let res = run();
+    assert_eq!(res.summary.files, 24);
+    assert_eq!(res.issues.len(), 36);
+    // Rules stay in index order; within a rule issues sort by file, message
+    let rules: Vec<&str> = res.issues.iter().map(|i| i.rule.as_str()).collect();
+    let expected: Vec<&str> = ids
+        .iter()
+        .flat_map(|id| std::iter::repeat_n(id.as_str(), 3))
+        .collect();
+    assert_eq!(rules, expected);
+    for chunk in res.issues.chunks(3) {
+        assert!(chunk[0].file.ends_with("a.json") && chunk[2].file.ends_with("b.json"));
+        assert!(chunk[1].message <= chunk[2].message);
+    }
+    let again: Vec<(String, String)> = run()
+        .issues
+        .into_iter()
+        .map(|i| (i.file, i.message))
+        .collect();
+    let first: Vec<(String, String)> = res
+        .issues
+        .into_iter()
+        .map(|i| (i.file, i.message))
+        .collect();
+    assert_eq!(again, first);
+}
+
+#[test]
+fn lint_issues_carry_source_positions() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+    fs::write(
+        conv.join("index.toml"),
+        "[[rules]]\nid = \"pkg\"\npatterns = [\"package.json\"]\npolicy = \"policy.toml\"\n",
+    )
+    .unwrap();
+    fs::write(
+        conv.join("policy.toml"),
+        r#"
+[[checks]]
+kind = "pattern"
+field = "version"
+regex = "^\\d+\\.\\d+\\.\\d+$"
+
+[[checks]]
+kind = "required"
+fields = ["license"]
+"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("package.json"),
+        "{\n  \"name\": \"x\",\n  \"version\": \"one\"\n}\n",
+    )
+    .unwrap();
+    let (res, errors) = lint::run_lint(
+        root.to_str().unwrap(),
+        "conv/index.toml",
+        None,
+        "repo",
+        &std::collections::HashMap::new(),
+        None,
+        false,
+        None,
+        &[],
+    );
+    assert!(errors.is_empty());
+    let at = |path: &str| {
+        let is = res.issues.iter().find(|i| i.path == path).unwrap();
+        (is.line, is.col)
+    };
+    assert_eq!(at("$.version"), (Some(3), Some(3)));
+    // A missing field points at the enclosing object
+    assert_eq!(at("$.license"), (Some(1), Some(1)));
+    let out = rigra::output::compose_lint_json(&res);
+    assert_eq!(out["issues"][0]["line"], 1);
+}
+
+#[test]
+fn lint_fix_resolves_order_and_sorted_arrays() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+    fs::write(
+        conv.join("index.toml"),
+        "[[rules]]\nid = \"pkg\"\npatterns = [\"package.json\"]\npolicy = \"policy.toml\"\n",
+    )
+    .unwrap();
+    fs::write(
+        conv.join("policy.toml"),
+        r#"
+[order]
+top = [["name", "version"], ["keywords"]]
+
+[[checks]]
+kind = "sortedArray"
+field = "keywords"
+
+[[checks]]
+kind = "required"
+fields = ["license"]
+"#,
+    )
+    .unwrap();
+    let pkg = root.join("package.json");
+    fs::write(
+        &pkg,
+        r#"{"keywords": ["lint", "cli"], "version": "1.0.0", "name": "x"}"#,
+    )
+    .unwrap();
+    let run = |fix: bool| {
+        let f = if fix {
+            lint::run_lint_fix
+        } else {
+            lint::run_lint
+        };
+        f(
+            root.to_str().unwrap(),
+            "conv/index.toml",
+            None,
+            "repo",
+            &std::collections::HashMap::new(),
+            None,
+            false,
+            None,
+            &[],
+        )
+    };
+    let (before, _) = run(false);
+    assert_eq!(before.issues.len(), 3);
+    assert_eq!(before.summary.fixed, None);
+
+    let (res, errors) = run(true);
+    assert!(errors.is_empty());
+    assert_eq!(res.summary.fixed, Some(2));
+    let mut fixed: Vec<_> = res.fixed.iter().map(|i| i.path.as_str()).collect();
+    fixed.sort();
+    assert_eq!(fixed, vec!["$", "$.keywords[1]"]);
+    // The missing license is not fixable
+    assert_eq!(res.issues.len(), 1);
+    assert_eq!(res.issues[0].path, "$.license");
+    assert_eq!(res.summary.errors, 1);
+    let v: serde_json::Value = serde_json::from_str(&fs::read_to_string(&pkg).unwrap()).unwrap();
+    let keys: Vec<_> = v.as_object().unwrap().keys().cloned().collect();
+    assert_eq!(keys, vec!["name", "version", "keywords"]);
+    assert_eq!(v["keywords"], serde_json::json!(["cli", "lint"]));
+
+    let out = rigra::output::compose_lint_json(&res);
+    assert_eq!(out["summary"]["fixed"], 2);
+    assert_eq!(out["fixed"].as_array().unwrap().len(), 2);
+    let lines = rigra::output::compose_lint_fixed_lines(&res, false);
+    assert_eq!(lines[0], "✔ Fixed 2 issue(s)");
+    assert!(lines[2].contains("⟦fixed⟧") && lines[2].contains("❲pkg❳"));
+
+    // Nothing left to fix on a second pass
+    let (again, _) = run(true);
+    assert_eq!(again.summary.fixed, Some(0));
+    assert_eq!(again.issues.len(), 1);
+}
+
+#[test]
+fn lint_fix_keeps_every_fix_when_rules_share_a_target() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+    let fields = ["a", "b", "c", "d", "e", "f", "g", "h"];
+    let mut index = String::new();
+    for f in fields {
+        index.push_str(&format!(
+            "[[rules]]\nid = \"sort-{f}\"\npatterns = [\"data.json\"]\npolicy = \"{f}.toml\"\n"
+        ));
+        fs::write(
+            conv.join(format!("{f}.toml")),
+            format!("[[checks]]\nkind = \"sortedArray\"\nfield = \"{f}\"\n"),
+        )
+        .unwrap();
+    }
+    fs::write(conv.join("index.toml"), index).unwrap();
+    let data = root.join("data.json");
+    let sorted: Vec<String> = (0..200).map(|i| format!("k{:05}", i)).collect();
+    let reversed: Vec<&String> = sorted.iter().rev().collect();
+    // Several workers even on a single core, so rules could overlap
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(fields.len())
+        .build()
+        .unwrap();
+    // Repeat so a lost write from concurrent rules would show up
+    for _ in 0..10 {
+        let obj: serde_json::Map<_, _> = fields
+            .iter()
+            .map(|f| (f.to_string(), serde_json::json!(reversed)))
+            .collect();
+        fs::write(&data, serde_json::to_string(&obj).unwrap()).unwrap();
+        let (res, errors) = pool.install(|| {
+            lint::run_lint_fix(
+                root.to_str().unwrap(),
+                "conv/index.toml",
+                None,
+                "repo",
+                &std::collections::HashMap::new(),
+                None,
+                false,
+                None,
+                &[],
+            )
+        });
+        assert!(errors.is_empty());
+        assert_eq!(res.summary.fixed, Some(fields.len()));
+        assert!(res.issues.is_empty());
+        let v: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&data).unwrap()).unwrap();
+        for f in fields {
+            assert_eq!(v[f], serde_json::json!(sorted), "field {}", f);
+        }
+    }
+}
+
+#[test]
+fn format_orders_yaml_and_json_targets_in_one_run() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+    fs::write(
+        conv.join("index.toml"),
+        r#"
+[[rules]]
+id = "ci"
+patterns = ["*.yml"]
+policy = "policy.toml"
+
+[[rules]]
+id = "pkg"
+patterns = ["*.json"]
+policy = "policy.toml"
+"#,
+    )
+    .unwrap();
+    fs::write(
+        conv.join("policy.toml"),
+        r#"
+checks = []
+
+[order]
+top = [["name"],["on"],["jobs"]]
+"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("ci.yml"),
+        "jobs:\n  build:\n    steps:\n    - run: make\non: push\nname: ci\n",
+    )
+    .unwrap();
+    fs::write(root.join("pkg.json"), r#"{"on":"x","name":"p"}"#).unwrap();
+
+    let (res, _errors) = lint::run_lint(
+        root.to_str().unwrap(),
+        "conv/index.toml",
+        None,
+        "repo",
+        &std::collections::HashMap::new(),
+        None,
+        false,
+        None,
+        &[],
+    );
+    // Order lint covers both formats
+    assert_eq!(res.issues.len(), 2);
+
+    let (results, errors) = format::run_format(
+        root.to_str().unwrap(),
+        "conv/index.toml",
+        None,
+        true,
+        false,
+        true,
+        None,
+        &std::collections::HashMap::new(),
+        &std::collections::HashMap::new(),
+        &std::collections::HashMap::new(),
+        None,
+        false,
+        false,
+        None,
+        &[],
+    );
+    assert!(errors.is_empty());
+    assert!(results.iter().all(|r| r.changed));
+    assert_eq!(
+        fs::read_to_string(root.join("ci.yml")).unwrap(),
+        "name: ci\non: push\njobs:\n  build:\n    steps:\n    - run: make\n"
+    );
+    assert_eq!(
+        fs::read_to_string(root.join("pkg.json")).unwrap(),
+        "{\n  \"name\": \"p\",\n  \"on\": \"x\"\n}"
+    );
+}
+
+#[test]
+fn lint_checks_toml_arrays_of_tables_as_object_arrays() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+    fs::write(
+        conv.join("index.toml"),
+        r#"
+[[rules]]
+id = "cargo"
+patterns = ["Cargo.toml"]
+policy = "policy.toml"
+"#,
+    )
+    .unwrap();
+    fs::write(
+        conv.join("policy.toml"),
+        r#"
+[[checks]]
+kind = "required"
+fields = ["package.name", "bin[0].name"]
+
+[[checks]]
+kind = "type"
+fields = { "package.edition" = "string", "bin[1].path" = "string" }
+"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname = \"x\"\nedition = 2021\n\n[[bin]]\nname = \"a\"\n\n[[bin]]\nname = \"b\"\npath = 1\n",
+    )
+    .unwrap();
+    let (res, errors) = lint::run_lint(
+        root.to_str().unwrap(),
+        "conv/index.toml",
+        None,
+        "repo",
+        &std::collections::HashMap::new(),
+        None,
+        false,
+        None,
+        &[],
+    );
+    assert!(errors.is_empty());
+    let mut paths: Vec<_> = res.issues.iter().map(|i| i.path.as_str()).collect();
+    paths.sort();
+    assert_eq!(paths, vec!["$.bin[1].path", "$.package.edition"]);
+}
+
+#[test]
+fn format_emit_order_lists_resulting_top_level_keys() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+    fs::write(
+        conv.join("index.toml"),
+        r#"
+[[rules]]
+id = "pkg"
+patterns = ["*.json"]
+policy = "policy.toml"
+"#,
+    )
+    .unwrap();
+    fs::write(
+        conv.join("policy.toml"),
+        r#"
+checks = []
+
+[order]
+top = [["name", "version"], ["*"], ["scripts"]]
+"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("package.json"),
+        r#"{"scripts":{},"zeta":1,"version":"1.0.0","alpha":2,"name":"x"}"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("tidy.json"),
+        "{\n  \"name\": \"y\",\n  \"beta\": 1\n}",
+    )
+    .unwrap();
+
+    let (results, errors) = format::run_format(
+        root.to_str().unwrap(),
+        "conv/index.toml",
+        None,
+        false,
+        true,
+        true,
+        None,
+        &std::collections::HashMap::new(),
+        &std::collections::HashMap::new(),
+        &std::collections::HashMap::new(),
+        None,
+        false,
+        false,
+        None,
+        &[],
+    );
+    assert!(errors.is_empty());
+    let orders: Vec<_> = results.iter().map(format::emitted_order).collect();
+    assert_eq!(
+        orders,
+        vec![
+            Some(vec![
+                "name".to_string(),
+                "version".to_string(),
+                "alpha".to_string(),
+                "zeta".to_string(),
+                "scripts".to_string(),
+            ]),
+            // Unchanged files report their original order
+            Some(vec!["name".to_string(), "beta".to_string()]),
+        ]
+    );
+    // Nothing is written
+    assert!(fs::read_to_string(root.join("package.json"))
+        .unwrap()
+        .starts_with("{\"scripts\""));
+}
+
+#[test]
+fn scope_hierarchy_enables_parent_scoped_rules() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(conv.join("templates")).unwrap();
+    fs::write(conv.join("templates/t.txt"), b"hello").unwrap();
+    fs::write(
+        conv.join("sync.toml"),
+        r#"
+[[sync]]
+id = "r1"
+source = "templates/t.txt"
+target = "out/repo.txt"
+when = "repo"
+
+[[sync]]
+id = "r2"
+source = "templates/t.txt"
+target = "out/app.txt"
+when = "app"
+"#,
+    )
+    .unwrap();
+    fs::write(conv.join("index.toml"), "sync = \"sync.toml\"\n").unwrap();
+    fs::write(
+        root.join("rigra.toml"),
+        "index = \"conv/index.toml\"\n[scopes]\nlib = [\"repo\"]\n",
+    )
+    .unwrap();
+
+    let eff = rigra::config::resolve_effective(
+        root.to_str(),
+        None,
+        Some("lib"),
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
+    let (actions, _errors) = sync::run_sync(
+        eff.repo_root.to_str().unwrap(),
+        &eff.index,
+        None,
+        &eff.scope,
+        true,
+        false,
+        false,
+        &[],
+    );
+    assert!(actions.iter().any(|a| a.rule_id == "r1" && a.wrote));
+    assert!(actions.iter().all(|a| a.rule_id != "r2"));
+
+    // Lint sees the same inherited scope for sync status
+    fs::remove_file(root.join("out/repo.txt")).unwrap();
+    let (res, _errors) = lint::run_lint(
+        eff.repo_root.to_str().unwrap(),
+        &eff.index,
+        None,
+        &eff.scope,
+        &std::collections::HashMap::new(),
+        None,
+        false,
+        None,
+        &[],
+    );
+    let rules: Vec<_> = res.issues.iter().map(|i| i.rule.as_str()).collect();
+    assert_eq!(rules, vec!["sync:r1"]);
+}
+
+#[test]
+fn order_rest_preserve_is_shared_by_format_and_lint() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+    fs::write(
+        conv.join("index.toml"),
+        r#"
+[[rules]]
+id = "pkg"
+patterns = ["*.json"]
+policy = "policy.toml"
+"#,
+    )
+    .unwrap();
+    fs::write(
+        conv.join("policy.toml"),
+        r#"
+checks = []
+
+[order]
+top = [["name"]]
+rest = "preserve"
+"#,
+    )
+    .unwrap();
+    // Unlisted keys out of lexicographic order but name already first
+    fs::write(
+        root.join("kept.json"),
+        "{\n  \"name\": \"x\",\n  \"zeta\": 1,\n  \"alpha\": 2\n}",
+    )
+    .unwrap();
+    fs::write(
+        root.join("moved.json"),
+        r#"{"zeta":1,"name":"x","alpha":2}"#,
+    )
+    .unwrap();
+
+    let (res, _errors) = lint::run_lint(
+        root.to_str().unwrap(),
+        "conv/index.toml",
+        None,
+        "repo",
+        &std::collections::HashMap::new(),
+        None,
+        false,
+        None,
+        &[],
+    );
+    let files: Vec<_> = res
+        .issues
+        .iter()
+        .map(|i| i.file.rsplit('/').next().unwrap().to_string())
+        .collect();
+    assert_eq!(files, vec!["moved.json"]);
+
+    let (results, _errors) = format::run_format(
+        root.to_str().unwrap(),
+        "conv/index.toml",
+        None,
+        true,
+        false,
+        true,
+        None,
+        &std::collections::HashMap::new(),
+        &std::collections::HashMap::new(),
+        &std::collections::HashMap::new(),
+        None,
+        false,
+        false,
+        None,
+        &[],
+    );
+    assert_eq!(
+        results.iter().map(|r| r.changed).collect::<Vec<_>>(),
+        vec![false, true]
+    );
+    assert_eq!(
+        fs::read_to_string(root.join("moved.json")).unwrap(),
+        "{\n  \"name\": \"x\",\n  \"zeta\": 1,\n  \"alpha\": 2\n}"
+    );
+}
+
+#[test]
+fn global_dry_run_previews_format_without_writing() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+    fs::write(
+        conv.join("index.toml"),
+        "[[rules]]\nid = \"pkg\"\npatterns = [\"*.json\"]\npolicy = \"policy.toml\"\n",
+    )
+    .unwrap();
+    fs::write(
+        conv.join("policy.toml"),
+        "checks = []\n[order]\ntop = [[\"name\"]]\n",
+    )
+    .unwrap();
+    // Config asks for writes; --dry-run must still win
+    fs::write(
+        root.join("rigra.toml"),
+        "index = \"conv/index.toml\"\n[format]\nwrite = true\n",
+    )
+    .unwrap();
+    let original = r#"{"b":1,"name":"x"}"#;
+    fs::write(root.join("pkg.json"), original).unwrap();
+
+    for args in [
+        vec!["format", "--dry-run", "--write"],
+        vec!["--dry-run", "format"],
+    ] {
+        let out = std::process::Command::new(env!("CARGO_BIN_EXE_rigra"))
+            .args(&args)
+            .args(["--repo-root", root.to_str().unwrap(), "--output", "json"])
+            .output()
+            .unwrap();
+        assert!(out.status.success(), "{:?}", out);
+        let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+        assert_eq!(json["summary"]["changed"], 1);
+        assert_eq!(json["summary"]["wrote"], 0);
+        assert_eq!(
+            json["results"][0]["preview"],
+            "{\n  \"name\": \"x\",\n  \"b\": 1\n}"
+        );
+        assert_eq!(fs::read_to_string(root.join("pkg.json")).unwrap(), original);
+    }
+
+    // No command touches any file in the repo, cache or .gitignore included
+    fs::create_dir_all(root.join(".git")).unwrap();
+    fs::create_dir_all(root.join(".rigra")).unwrap();
+    fs::create_dir_all(conv.join("templates")).unwrap();
+    fs::write(conv.join("templates/.editorconfig"), "root = true\n").unwrap();
+    fs::write(
+        conv.join("sync.toml"),
+        "[[sync]]\nid = \"ec\"\nsource = \"templates/.editorconfig\"\ntarget = \".editorconfig\"\nwhen = \"*\"\n",
+    )
+    .unwrap();
+    let index = fs::read_to_string(conv.join("index.toml")).unwrap();
+    fs::write(
+        conv.join("index.toml"),
+        format!("sync = \"sync.toml\"\n{}", index),
+    )
+    .unwrap();
+    fn snapshot(dir: &std::path::Path, out: &mut Vec<(std::path::PathBuf, Vec<u8>)>) {
+        let mut entries: Vec<_> = fs::read_dir(dir).unwrap().flatten().collect();
+        entries.sort_by_key(|e| e.path());
+        for e in entries {
+            let p = e.path();
+            if p.is_dir() {
+                out.push((p.clone(), Vec::new()));
+                snapshot(&p, out);
+            } else {
+                out.push((p.clone(), fs::read(&p).unwrap()));
+            }
+        }
+    }
+    let mut before = Vec::new();
+    snapshot(root, &mut before);
+    let policy = conv.join("policy.toml");
+    let root_s = root.to_str().unwrap();
+    let runs: [(&[&str], i32); 8] = [
+        (&["format", "--write", "--repo-root", root_s], 0),
+        (&["lint", "--fix", "--repo-root", root_s], 1),
+        (&["lint", "--baseline-update", "--repo-root", root_s], 1),
+        (&["sync", "--write", "--repo-root", root_s], 0),
+        (&["policy", "fmt", policy.to_str().unwrap(), "--write"], 0),
+        (
+            &[
+                "format",
+                "--fix-suggest",
+                "out.patch",
+                "--repo-root",
+                root_s,
+            ],
+            2,
+        ),
+        (
+            &[
+                "conv",
+                "install",
+                "--source",
+                "file:conv",
+                "--name",
+                "c@1",
+                "--repo-root",
+                root_s,
+            ],
+            2,
+        ),
+        (&["conv", "prune", "--repo-root", root_s], 2),
+    ];
+    for (args, code) in runs {
+        let out = std::process::Command::new(env!("CARGO_BIN_EXE_rigra"))
+            .arg("--dry-run")
+            .args(args)
+            .current_dir(root)
+            .output()
+            .unwrap();
+        assert_eq!(out.status.code(), Some(code), "{:?}: {:?}", args, out);
+        let mut after = Vec::new();
+        snapshot(root, &mut after);
+        assert!(after == before, "{:?} changed the repo", args);
+    }
+}
+
+#[test]
+fn nested_sub_orders_are_formatted_and_linted_by_path() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+    fs::write(
+        conv.join("index.toml"),
+        "[[rules]]\nid = \"pkg\"\npatterns = [\"package.json\"]\npolicy = \"policy.toml\"\n",
+    )
+    .unwrap();
+    fs::write(
+        conv.join("policy.toml"),
+        r#"
+checks = []
+
+[order]
+top = [["name"], ["repository"], ["scripts"]]
+
+[order.sub]
+repository = ["type", "url"]
+scripts = ["build", "test"]
+"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("package.json"),
+        r#"{"name":"x","repository":{"url":"u","type":"git"},"scripts":{"build":"b","lint":"l","test":"t"}}"#,
+    )
+    .unwrap();
+
+    let lint_paths = || {
+        let (res, _errors) = lint::run_lint(
+            root.to_str().unwrap(),
+            "conv/index.toml",
+            None,
+            "repo",
+            &std::collections::HashMap::new(),
+            None,
+            false,
+            None,
+            &[],
+        );
+        res.issues.into_iter().map(|i| i.path).collect::<Vec<_>>()
+    };
+    // Top level is fine; each nested object is reported at its own path
+    assert_eq!(lint_paths(), vec!["$.repository", "$.scripts"]);
+
+    format::run_format(
+        root.to_str().unwrap(),
+        "conv/index.toml",
+        None,
+        true,
+        false,
+        false,
+        None,
+        &std::collections::HashMap::new(),
+        &std::collections::HashMap::new(),
+        &std::collections::HashMap::new(),
+        None,
+        false,
+        false,
+        None,
+        &[],
+    );
+    let out: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(root.join("package.json")).unwrap()).unwrap();
+    let keys = |v: &serde_json::Value| v.as_object().unwrap().keys().cloned().collect::<Vec<_>>();
+    assert_eq!(keys(&out["repository"]), vec!["type", "url"]);
+    assert_eq!(keys(&out["scripts"]), vec!["build", "test", "lint"]);
+    assert!(lint_paths().is_empty());
+}
+
+#[test]
+fn doctor_reports_invalid_regex_without_matching_files() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+    fs::write(
+        conv.join("index.toml"),
+        "[[rules]]\nid = \"pkg\"\npatterns = [\"packages/*/package.json\"]\npolicy = \"policy.toml\"\n",
+    )
+    .unwrap();
+    fs::write(
+        conv.join("policy.toml"),
+        "[[checks]]\nkind = \"pattern\"\nfield = \"$.name\"\nregex = \"^(@acme/\"\n",
+    )
+    .unwrap();
+
+    let run = |output: &str| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_rigra"))
+            .args(["doctor", "--index", "conv/index.toml", "--output", output])
+            .args(["--repo-root", root.to_str().unwrap()])
+            .output()
+            .unwrap()
+    };
+    let out = run("json");
+    assert_eq!(out.status.code(), Some(1), "{:?}", out);
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(json["ok"], false);
+    assert_eq!(json["problems"][0]["rule"], "pkg");
+    let msg = json["problems"][0]["message"].as_str().unwrap();
+    assert!(
+        msg.starts_with("Invalid regex '^(@acme/' in pattern check"),
+        "{}",
+        msg
+    );
+
+    // A valid regex passes
+    fs::write(
+        conv.join("policy.toml"),
+        "[[checks]]\nkind = \"pattern\"\nfield = \"$.name\"\nregex = \"^@acme/\"\n",
+    )
+    .unwrap();
+    let out = run("human");
+    assert!(out.status.success(), "{:?}", out);
+    assert_eq!(
+        String::from_utf8_lossy(&out.stdout).trim(),
+        "No policy problems found."
+    );
+}
+
+#[test]
+fn doctor_reports_impossible_number_range() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+    fs::write(
+        conv.join("index.toml"),
+        "[[rules]]\nid = \"pkg\"\npatterns = [\"package.json\"]\npolicy = \"policy.toml\"\n",
+    )
+    .unwrap();
+    fs::write(
+        conv.join("policy.toml"),
+        "[[checks]]\nkind = \"numberRange\"\nfield = \"port\"\nmin = 8080\nmax = 8080\nexclusiveMax = true\n",
+    )
+    .unwrap();
+    let out = std::process::Command::new(env!("CARGO_BIN_EXE_rigra"))
+        .args(["doctor", "--index", "conv/index.toml", "--output", "json"])
+        .args(["--repo-root", root.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(1), "{:?}", out);
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(
+        json["problems"][0]["message"],
+        "Impossible numberRange for port: >= 8080 and < 8080 admit no value"
+    );
+}
+
+#[test]
+fn format_changed_only_lists_just_the_changed_files() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+    fs::write(
+        conv.join("index.toml"),
+        "[[rules]]\nid = \"pkg\"\npatterns = [\"*.json\"]\npolicy = \"policy.toml\"\n",
+    )
+    .unwrap();
+    fs::write(
+        conv.join("policy.toml"),
+        "[order]\ntop = [[\"name\", \"version\"]]\n",
+    )
+    .unwrap();
+    let messy = r#"{"version": "1.0.0", "name": "x"}"#;
+    fs::write(root.join("tidy.json"), messy).unwrap();
+    fs::write(root.join("messy.json"), messy).unwrap();
+    let run = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_rigra"))
+            .args(["format", "--index", "conv/index.toml", "--output", "human"])
+            .args(["--repo-root", root.to_str().unwrap()])
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    // Tidy both, then leave only messy.json out of order
+    assert!(run(&["--write"]).status.success());
+    fs::write(root.join("messy.json"), messy).unwrap();
+
+    for args in [&["--changed-only"][..], &["--changed-only", "--write"][..]] {
+        let out = run(args);
+        assert!(out.status.success(), "{:?}", out);
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        assert!(stdout.contains("messy.json"), "{}", stdout);
+        assert!(!stdout.contains("tidy.json"), "{}", stdout);
+    }
+    let out = run(&["--changed-only"]);
+    assert!(String::from_utf8_lossy(&out.stdout).contains("Everything is tidy"));
+}
+
+#[test]
+fn format_stdin_prints_formatted_text_for_the_matching_rule() {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+    fs::write(
+        conv.join("index.toml"),
+        r#"
+[[rules]]
+id = "pkg"
+patterns = ["packages/*/package.json"]
+policy = "pkg.toml"
+
+[[rules]]
+id = "ts"
+patterns = ["tsconfig.json"]
+policy = "ts.toml"
+"#,
+    )
+    .unwrap();
+    fs::write(
+        conv.join("pkg.toml"),
+        "[order]\ntop = [[\"name\", \"version\"], [\"scripts\"]]\n[linebreak]\nbetween_groups = true\n",
+    )
+    .unwrap();
+    fs::write(
+        conv.join("ts.toml"),
+        "[order]\ntop = [[\"compilerOptions\"]]\n",
+    )
+    .unwrap();
+
+    let run = |args: &[&str], input: &str| {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_rigra"))
+            .args(["format", "--index", "conv/index.toml", "--stdin"])
+            .args(["--repo-root", root.to_str().unwrap()])
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(input.as_bytes())
+            .unwrap();
+        child.wait_with_output().unwrap()
+    };
+
+    let input = "{\"scripts\":{},\"version\":\"1.0.0\",\"name\":\"x\"}\n";
+    let out = run(&["--stdin-filename", "packages/a/package.json"], input);
+    assert!(out.status.success(), "{:?}", out);
+    let formatted = "{\n  \"name\": \"x\",\n  \"version\": \"1.0.0\",\n\n  \"scripts\": {}\n}\n";
+    assert_eq!(String::from_utf8_lossy(&out.stdout), formatted);
+    // Nothing is read from or written to the matched path
+    assert!(!root.join("packages").exists());
+
+    // --check fails on unformatted input and passes on formatted input
+    let out = run(
+        &["--stdin-filename", "packages/a/package.json", "--check"],
+        input,
+    );
+    assert_eq!(out.status.code(), Some(1), "{:?}", out);
+    assert!(out.stdout.is_empty());
+    let out = run(
+        &["--stdin-filename", "packages/a/package.json", "--check"],
+        formatted,
+    );
+    assert!(out.status.success(), "{:?}", out);
+
+    // Several rules and no filename: ask for one
+    let out = run(&[], input);
+    assert_eq!(out.status.code(), Some(2), "{:?}", out);
+    assert!(String::from_utf8_lossy(&out.stderr).contains("--stdin-filename"));
+
+    // Unmatched paths pass through unchanged
+    let out = run(&["--stdin-filename", "README.json"], input);
+    assert!(out.status.success(), "{:?}", out);
+    assert_eq!(String::from_utf8_lossy(&out.stdout), input);
+}
+
+#[test]
+fn lint_files_from_stdin_checks_only_listed_targets() {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+    fs::create_dir_all(root.join("packages/a")).unwrap();
+    fs::create_dir_all(root.join("packages/b")).unwrap();
+    fs::write(
+        conv.join("index.toml"),
+        r#"
+[[rules]]
+id = "pkg"
+patterns = ["packages/*/package.json"]
+policy = "pkg.toml"
+
+[[rules]]
+id = "ts"
+patterns = ["tsconfig.json"]
+policy = "ts.toml"
+"#,
+    )
+    .unwrap();
+    fs::write(
+        conv.join("pkg.toml"),
+        "[[checks]]\nkind = \"required\"\nfields = [\"name\"]\n",
+    )
+    .unwrap();
+    fs::write(
+        conv.join("ts.toml"),
+        "[[checks]]\nkind = \"required\"\nfields = [\"compilerOptions\"]\n",
+    )
+    .unwrap();
+    fs::write(root.join("packages/a/package.json"), "{}").unwrap();
+    fs::write(root.join("packages/b/package.json"), "{}").unwrap();
+    fs::write(root.join("tsconfig.json"), "{}").unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rigra"))
+        .args(["lint", "--index", "conv/index.toml", "--output", "json"])
+        .args(["--repo-root", root.to_str().unwrap(), "--files-from", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"packages/b/package.json\n\nREADME.md\n")
+        .unwrap();
+    let out = child.wait_with_output().unwrap();
+    assert_eq!(out.status.code(), Some(1), "{:?}", out);
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let issues = json["issues"].as_array().unwrap();
+    assert_eq!(issues.len(), 1, "{}", json);
+    assert!(issues[0]["file"]
+        .as_str()
+        .unwrap()
+        .ends_with("packages/b/package.json"));
+    assert_eq!(issues[0]["rule"], "pkg");
+    assert_eq!(json["summary"]["files"], 1);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("No rule matches 'README.md'"), "{}", stderr);
+    assert!(!stderr.contains("packages/b/package.json"), "{}", stderr);
+}
+
+#[test]
+fn fail_on_sync_drift_turns_info_drift_into_errors() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(conv.join("templates")).unwrap();
+    fs::write(conv.join("index.toml"), "sync = \"sync.toml\"\n").unwrap();
+    fs::write(
+        conv.join("sync.toml"),
+        r#"
+[[sync]]
+id = "editorconfig"
+source = "templates/.editorconfig"
+target = ".editorconfig"
+when = "*"
+level = "info"
+"#,
+    )
+    .unwrap();
+    fs::write(conv.join("templates/.editorconfig"), "root = true\n").unwrap();
+
+    let lint = |extra: &[&str]| {
+        let out = std::process::Command::new(env!("CARGO_BIN_EXE_rigra"))
+            .args(["lint", "--index", "conv/index.toml", "--output", "json"])
+            .args(["--repo-root", root.to_str().unwrap()])
+            .args(extra)
+            .output()
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+        (out.status.code(), json)
+    };
+    let (code, json) = lint(&[]);
+    assert_eq!(code, Some(0), "{}", json);
+    assert_eq!(json["issues"][0]["severity"], "info");
+    assert_eq!(json["summary"]["infos"], 1);
+
+    let (code, json) = lint(&["--fail-on-sync-drift"]);
+    assert_eq!(code, Some(1), "{}", json);
+    assert_eq!(json["issues"][0]["rule"], "sync:editorconfig");
+    assert_eq!(json["issues"][0]["severity"], "error");
+    assert_eq!(json["summary"]["errors"], 1);
+    assert_eq!(json["summary"]["infos"], 0);
+}
+
+#[test]
+fn rule_filter_limits_lint_and_rejects_unknown_ids() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(conv.join("templates")).unwrap();
+    fs::write(
+        conv.join("index.toml"),
+        r#"sync = "sync.toml"
+
+[[rules]]
+id = "a"
+patterns = ["a.json"]
+policy = "req.toml"
+
+[[rules]]
+id = "b"
+patterns = ["b.json"]
+policy = "req.toml"
+"#,
+    )
+    .unwrap();
+    fs::write(
+        conv.join("req.toml"),
+        "[[checks]]\nkind = \"required\"\nfields = [\"name\"]\n",
+    )
+    .unwrap();
+    fs::write(
+        conv.join("sync.toml"),
+        "[[sync]]\nid = \"editorconfig\"\nsource = \"templates/.editorconfig\"\ntarget = \".editorconfig\"\nwhen = \"*\"\n",
+    )
+    .unwrap();
+    fs::write(conv.join("templates/.editorconfig"), "root = true\n").unwrap();
+    fs::write(root.join("a.json"), "{}\n").unwrap();
+    fs::write(root.join("b.json"), "{}\n").unwrap();
+
+    let run = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_rigra"))
+            .args(args)
+            .args(["--index", "conv/index.toml", "--output", "json"])
+            .args(["--repo-root", root.to_str().unwrap()])
+            .output()
+            .unwrap()
+    };
+    let out = run(&["lint"]);
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(json["issues"].as_array().unwrap().len(), 3, "{}", json);
+
+    let out = run(&["lint", "--rule", "b"]);
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let rules: Vec<_> = json["issues"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["rule"].as_str().unwrap())
+        .collect();
+    assert_eq!(rules, vec!["b"]);
+
+    let out = run(&["lint", "--rule", "b", "--rule", "nope"]);
+    assert_eq!(out.status.code(), Some(2));
+    let err = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        err.contains("Unknown rule 'nope'. Available rules: a, b, editorconfig"),
+        "{}",
+        err
+    );
+
+    // Sync ids are the sync policy's, not the index rules'
+    let out = run(&["sync", "--rule", "a"]);
+    assert_eq!(out.status.code(), Some(2));
+    let err = String::from_utf8_lossy(&out.stderr);
+    assert!(err.contains("Available rules: editorconfig"), "{}", err);
+}
+
+#[test]
+fn diff_baseline_reports_only_new_issues() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+    fs::write(
+        conv.join("index.toml"),
+        "[[rules]]\nid = \"pkg\"\npatterns = [\"package.json\"]\npolicy = \"pkg.toml\"\n",
+    )
+    .unwrap();
+    fs::write(
+        conv.join("pkg.toml"),
+        "[[checks]]\nkind = \"required\"\nfields = [\"name\", \"version\", \"license\"]\n",
+    )
+    .unwrap();
+    fs::write(root.join("package.json"), "{\"name\": \"x\"}\n").unwrap();
+
+    let lint = |extra: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_rigra"))
+            .args(["lint", "--index", "conv/index.toml", "--output", "json"])
+            .args(["--repo-root", root.to_str().unwrap()])
+            .args(extra)
+            .output()
+            .unwrap()
+    };
+    let out = lint(&["--baseline", "baseline.json", "--baseline-update"]);
+    assert!(out.status.success(), "{:?}", out);
+
+    // Dropping `name` introduces one issue on top of the baselined two
+    fs::write(root.join("package.json"), "{}\n").unwrap();
+    let out = lint(&["--diff-baseline", "baseline.json"]);
+    assert_eq!(out.status.code(), Some(1));
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let issues = json["issues"].as_array().unwrap();
+    assert_eq!(issues.len(), 1, "{}", json);
+    assert_eq!(issues[0]["path"], "$.name");
+    assert_eq!(json["summary"]["errors"], 1);
+    assert!(json.get("resolved").is_none());
+
+    // Fixing a baselined issue lists it as resolved
+    fs::write(
+        root.join("package.json"),
+        "{\"name\": \"x\", \"license\": \"MIT\"}\n",
+    )
+    .unwrap();
+    let out = lint(&["--diff-baseline", "baseline.json"]);
+    assert!(out.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert!(json["issues"].as_array().unwrap().is_empty());
+    assert_eq!(json["resolved"][0]["path"], "$.license");
+}
+
+#[test]
+fn max_warnings_exits_3_when_exceeded() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+    fs::write(
+        conv.join("index.toml"),
+        "[[rules]]\nid = \"pkg\"\npatterns = [\"package.json\"]\npolicy = \"pkg.toml\"\n",
+    )
+    .unwrap();
+    fs::write(
+        conv.join("pkg.toml"),
+        "defaultLevel = \"warning\"\n\n[[checks]]\nkind = \"required\"\nfields = [\"name\", \"license\"]\n",
+    )
+    .unwrap();
+    fs::write(root.join("package.json"), "{}\n").unwrap();
+
+    let lint = |extra: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_rigra"))
+            .args(["lint", "--index", "conv/index.toml"])
+            .args(["--repo-root", root.to_str().unwrap()])
+            .args(extra)
+            .output()
+            .unwrap()
+    };
+    // Warnings alone never fail without a threshold
+    assert_eq!(lint(&[]).status.code(), Some(0));
+    assert_eq!(lint(&["--max-warnings", "2"]).status.code(), Some(0));
+    let out = lint(&["--max-warnings", "1"]);
+    assert_eq!(out.status.code(), Some(3));
+    let err = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        err.contains("Warning threshold exceeded: 2 warnings (--max-warnings 1)"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn sync_check_reports_local_target_edit_after_write() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(conv.join("templates")).unwrap();
+    fs::write(conv.join("index.toml"), "sync = \"sync.toml\"\n").unwrap();
+    fs::write(
+        conv.join("sync.toml"),
+        "[[sync]]\nid = \"editorconfig\"\nsource = \"templates/.editorconfig\"\ntarget = \".editorconfig\"\nwhen = \"*\"\n",
+    )
+    .unwrap();
+    fs::write(conv.join("templates/.editorconfig"), "root = true\n").unwrap();
+    let sync = |extra: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_rigra"))
+            .args(["sync", "--index", "conv/index.toml"])
+            .args(["--repo-root", root.to_str().unwrap()])
+            .args(extra)
+            .output()
+            .unwrap()
+    };
+    assert!(sync(&["--write"]).status.success());
+    assert_eq!(sync(&["--check"]).status.code(), Some(0));
+    fs::write(root.join(".editorconfig"), "root = false\n").unwrap();
+    assert_eq!(sync(&["--check"]).status.code(), Some(1));
+}
+
+#[test]
+fn baseline_update_with_files_from_keeps_unlisted_entries() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(&conv).unwrap();
+    fs::write(
+        conv.join("index.toml"),
+        "[[rules]]\nid = \"pkg\"\npatterns = [\"*.json\"]\npolicy = \"pkg.toml\"\n",
+    )
+    .unwrap();
+    fs::write(
+        conv.join("pkg.toml"),
+        "defaultLevel = \"warning\"\n\n[[checks]]\nkind = \"required\"\nfields = [\"name\"]\n",
+    )
+    .unwrap();
+    fs::write(root.join("a.json"), "{}\n").unwrap();
+    fs::write(root.join("b.json"), "{}\n").unwrap();
+    fs::write(root.join("list.txt"), "a.json\n").unwrap();
+
+    let lint = |extra: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_rigra"))
+            .args(["lint", "--index", "conv/index.toml", "--output", "json"])
+            .args(["--repo-root", root.to_str().unwrap()])
+            .args(["--baseline", "bl/baseline.json"])
+            .args(extra)
+            .output()
+            .unwrap()
+    };
+    assert!(lint(&["--baseline-update"]).status.success());
+    // a.json is fixed; updating from a run over it alone must keep b.json's entry
+    fs::write(root.join("a.json"), "{\"name\": \"a\"}\n").unwrap();
+    let list = root.join("list.txt");
+    let out = lint(&["--baseline-update", "--files-from", list.to_str().unwrap()]);
+    let err = String::from_utf8_lossy(&out.stderr);
+    assert!(err.contains("0 added, 1 kept, 1 pruned"), "{}", err);
+    let out = lint(&[]);
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert!(json["issues"].as_array().unwrap().is_empty(), "{}", json);
+}
+
+#[test]
+fn read_only_runs_leave_gitignore_alone() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let conv = root.join("conv");
+    fs::create_dir_all(conv.join("templates")).unwrap();
+    fs::create_dir_all(root.join(".git")).unwrap();
+    fs::create_dir_all(root.join(".rigra")).unwrap();
+    fs::write(
+        conv.join("index.toml"),
+        "sync = \"sync.toml\"\n\n[[rules]]\nid = \"pkg\"\npatterns = [\"package.json\"]\npolicy = \"pkg.toml\"\n",
+    )
+    .unwrap();
+    fs::write(conv.join("pkg.toml"), "[order]\ntop = [[\"name\"]]\n").unwrap();
+    fs::write(
+        conv.join("sync.toml"),
+        "[[sync]]\nid = \"editorconfig\"\nsource = \"templates/.editorconfig\"\ntarget = \".editorconfig\"\nwhen = \"*\"\n",
+    )
+    .unwrap();
+    fs::write(conv.join("templates/.editorconfig"), "root = true\n").unwrap();
+    fs::write(root.join("package.json"), "{\"name\": \"x\"}\n").unwrap();
+
+    let rigra = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_rigra"))
+            .args(args)
+            .args(["--index", "conv/index.toml"])
+            .args(["--repo-root", root.to_str().unwrap()])
+            .output()
+            .unwrap()
+    };
+    for args in [
+        &["lint"][..],
+        &["--dry-run", "lint"],
+        &["format", "--check"],
+        &["sync"],
+        &["sync", "--check"],
+    ] {
+        rigra(args);
+        assert!(!root.join(".gitignore").exists(), "{:?}", args);
+    }
+    assert!(rigra(&["sync", "--write"]).status.success());
+    assert_eq!(
+        fs::read_to_string(root.join(".gitignore")).unwrap(),
+        ".rigra/\n"
+    );
+}